@@ -156,7 +156,8 @@ impl TokioDecoder for ImapClientCodec {
                                                 ));
                                             }
                                         }
-                                        ResponseDecodeError::Failed => {
+                                        ResponseDecodeError::TooLarge
+                                        | ResponseDecodeError::Failed => {
                                             let consumed = src.split_to(*to_consume_acc);
                                             self.state =
                                                 FramingState::ReadLine { to_consume_acc: 0 };
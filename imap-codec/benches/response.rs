@@ -7,7 +7,11 @@ use imap_types::{core::Vec1, fetch::MessageDataItem, response::Data};
 fn criterion_benchmark(c: &mut Criterion) {
     // # Setup
     let codec = ResponseCodec::new();
-    let instances = [("simple", create_simple()), ("complex", create_complex())];
+    let instances = [
+        ("simple", create_simple()),
+        ("complex", create_complex()),
+        ("search", create_search()),
+    ];
 
     for (instance, object) in instances {
         c.bench_function(
@@ -39,6 +43,14 @@ fn create_complex() -> Response<'static> {
     })
 }
 
+fn create_search() -> Response<'static> {
+    Response::Data(Data::Search(
+        (1..=100_000)
+            .map(|seq| NonZeroU32::new(seq).unwrap())
+            .collect(),
+    ))
+}
+
 #[inline]
 fn serialize(codec: &ResponseCodec, object: &Response) -> Vec<u8> {
     codec.encode(object).dump()
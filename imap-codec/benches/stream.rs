@@ -0,0 +1,147 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use imap_codec::{
+    decode::{Decoder, ParseOutcome},
+    encode::Encoder,
+    imap_types::response::{Greeting, Response},
+    GreetingCodec, ResponseCodec,
+};
+
+/// Size of the chunks in which the stream is "received" from the network.
+const CHUNK_SIZE: usize = 1024;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    // # Setup
+    let greeting_codec = GreetingCodec::new();
+    let response_codec = ResponseCodec::new();
+    let (greeting, stream) = create_stream();
+    let responses = parse(&response_codec, &stream);
+
+    let mut group = c.benchmark_group("stream");
+    group.throughput(Throughput::Bytes((greeting.len() + stream.len()) as u64));
+
+    group.bench_function("bench_stream_parse", |b| {
+        b.iter(|| {
+            greeting_codec.decode(black_box(&greeting)).unwrap();
+            parse(&response_codec, black_box(&stream));
+        })
+    });
+
+    group.bench_function("bench_stream_parse_chunked", |b| {
+        b.iter(|| {
+            greeting_codec.decode(black_box(&greeting)).unwrap();
+            parse_chunked(&response_codec, black_box(&stream));
+        })
+    });
+
+    let (_, object) = greeting_codec.decode(&greeting).unwrap();
+    group.bench_function("bench_stream_serialize", |b| {
+        b.iter(|| {
+            serialize(&greeting_codec, &response_codec, &object, &responses);
+        })
+    });
+
+    group.finish();
+}
+
+/// Create a greeting and a (deterministic) stream of responses as sent by a server
+/// during a typical session, i.e., `CAPABILITY`, `SELECT`, and a `FETCH` of a large message.
+fn create_stream() -> (Vec<u8>, Vec<u8>) {
+    let greeting = b"* OK [CAPABILITY IMAP4rev1 SASL-IR LOGIN-REFERRALS ID ENABLE IDLE LITERAL+ AUTH=PLAIN] Dovecot ready.\r\n".to_vec();
+
+    let mut stream = Vec::new();
+
+    stream.extend_from_slice(b"* CAPABILITY IMAP4rev1 SASL-IR LOGIN-REFERRALS ID ENABLE IDLE SORT SORT=DISPLAY THREAD=REFERENCES THREAD=REFS THREAD=ORDEREDSUBJECT MULTIAPPEND URL-PARTIAL CATENATE UNSELECT CHILDREN NAMESPACE UIDPLUS LIST-EXTENDED I18NLEVEL=1 CONDSTORE QRESYNC ESEARCH ESORT SEARCHRES WITHIN CONTEXT=SEARCH LIST-STATUS BINARY MOVE SNIPPET=FUZZY PREVIEW=FUZZY PREVIEW STATUS=SIZE SAVEDATE LITERAL+ NOTIFY SPECIAL-USE QUOTA\r\n");
+    stream.extend_from_slice(b"A1 OK Logged in\r\n");
+    stream.extend_from_slice(
+        b"* FLAGS (\\Answered \\Flagged \\Deleted \\Seen \\Draft $Forwarded $Junk)\r\n",
+    );
+    stream.extend_from_slice(b"* OK [PERMANENTFLAGS (\\Answered \\Flagged \\Deleted \\Seen \\Draft $Forwarded $Junk \\*)] Flags permitted.\r\n");
+    stream.extend_from_slice(b"* 4242 EXISTS\r\n");
+    stream.extend_from_slice(b"* 0 RECENT\r\n");
+    stream.extend_from_slice(b"* OK [UNSEEN 17] First unseen.\r\n");
+    stream.extend_from_slice(b"* OK [UIDVALIDITY 1698765432] UIDs valid\r\n");
+    stream.extend_from_slice(b"* OK [UIDNEXT 4711] Predicted next UID\r\n");
+    stream.extend_from_slice(b"A2 OK [READ-WRITE] Select completed.\r\n");
+
+    let mut body = Vec::new();
+    body.extend_from_slice(b"From: Alice <alice@example.org>\r\nTo: Bob <bob@example.org>\r\nSubject: Benchmark\r\nDate: Mon, 7 Feb 1994 21:52:25 -0800\r\nMessage-ID: <benchmark@example.org>\r\n\r\n");
+    for line in 0..1024 {
+        body.extend_from_slice(
+            format!("{line:04}: The quick brown fox jumps over the lazy dog.\r\n").as_bytes(),
+        );
+    }
+
+    stream.extend_from_slice(
+        format!(
+            "* 1 FETCH (UID 4242 FLAGS (\\Seen) RFC822.SIZE {} BODY[] {{{}}}\r\n",
+            body.len(),
+            body.len()
+        )
+        .as_bytes(),
+    );
+    stream.extend_from_slice(&body);
+    stream.extend_from_slice(b")\r\n");
+    stream.extend_from_slice(b"A3 OK Fetch completed.\r\n");
+
+    (greeting, stream)
+}
+
+#[inline]
+fn serialize(
+    greeting_codec: &GreetingCodec,
+    response_codec: &ResponseCodec,
+    greeting: &Greeting,
+    responses: &[Response],
+) -> Vec<u8> {
+    let mut out = greeting_codec.encode(greeting).dump();
+
+    for response in responses {
+        out.extend(response_codec.encode(response).dump());
+    }
+
+    out
+}
+
+/// Parse a stream that was fully received.
+#[inline]
+fn parse<'a>(codec: &ResponseCodec, mut input: &'a [u8]) -> Vec<Response<'a>> {
+    let mut responses = Vec::new();
+
+    while !input.is_empty() {
+        let (remaining, response) = codec.decode(input).unwrap();
+        responses.push(response);
+        input = remaining;
+    }
+
+    responses
+}
+
+/// Parse a stream that is received in chunks, i.e., retry parsing whenever more data arrived.
+#[inline]
+fn parse_chunked(codec: &ResponseCodec, input: &[u8]) -> usize {
+    let mut buffer = Vec::new();
+    let mut count = 0;
+
+    for chunk in input.chunks(CHUNK_SIZE) {
+        buffer.extend_from_slice(chunk);
+
+        loop {
+            match codec.feed(&buffer) {
+                ParseOutcome::Complete { consumed, .. } => {
+                    buffer.drain(..consumed);
+                    count += 1;
+                }
+                ParseOutcome::Incomplete => break,
+                ParseOutcome::Error(error) => panic!("{error:?}"),
+            }
+        }
+    }
+
+    assert!(buffer.is_empty());
+
+    count
+}
+
+criterion_group!(benches, criterion_benchmark);
+
+criterion_main!(benches);
@@ -54,7 +54,7 @@ fn main() {
                 read_more(&mut buffer, Role::Server);
             }
             // Parser failed.
-            Err(ResponseDecodeError::Failed) => {
+            Err(ResponseDecodeError::TooLarge | ResponseDecodeError::Failed) => {
                 println!("Error parsing response.");
                 println!("Clearing buffer.");
 
@@ -23,7 +23,8 @@ use crate::{
 /// `body = "(" (body-type-1part / body-type-mpart) ")"`
 ///
 /// Note: This parser is recursively defined. Thus, in order to not overflow the stack,
-/// it is needed to limit how may recursions are allowed. (8 should suffice).
+/// it is needed to limit how may recursions are allowed. Every nested multipart or
+/// `message/rfc822` body counts as one recursion.
 pub(crate) fn body(
     remaining_recursions: usize,
 ) -> impl Fn(&[u8]) -> IMAPResult<&[u8], BodyStructure> {
@@ -41,12 +42,10 @@ fn body_limited<'a>(
         }));
     }
 
-    let body_type_1part = move |input: &'a [u8]| {
-        body_type_1part_limited(input, remaining_recursions.saturating_sub(1))
-    };
-    let body_type_mpart = move |input: &'a [u8]| {
-        body_type_mpart_limited(input, remaining_recursions.saturating_sub(1))
-    };
+    let body_type_1part =
+        move |input: &'a [u8]| body_type_1part_limited(input, remaining_recursions);
+    let body_type_mpart =
+        move |input: &'a [u8]| body_type_mpart_limited(input, remaining_recursions);
 
     delimited(
         tag(b"("),
@@ -75,7 +74,7 @@ fn body_type_1part_limited<'a>(
         }));
     }
 
-    let body_type_msg = move |input: &'a [u8]| body_type_msg_limited(input, 8);
+    let body_type_msg = move |input: &'a [u8]| body_type_msg_limited(input, remaining_recursions);
 
     let mut parser = tuple((
         alt((body_type_msg, body_type_text, body_type_basic)),
@@ -447,7 +446,7 @@ fn body_type_mpart_limited(
     }
 
     let mut parser = tuple((
-        many1(body(remaining_recursion)),
+        many1(body(remaining_recursion.saturating_sub(1))),
         sp,
         media_subtype,
         opt(preceded(sp, body_ext_mpart)),
@@ -1,6 +1,8 @@
 pub mod decode;
 pub mod encode;
 
+use crate::decode::{ParseMode, ResponseParseOptions};
+
 /// Codec for greetings.
 #[derive(Clone, Debug, Default, PartialEq)]
 // We use `#[non_exhaustive]` to prevent users from using struct literal syntax.
@@ -23,7 +25,10 @@ pub struct AuthenticateDataCodec;
 /// Codec for responses.
 #[derive(Clone, Debug, Default, PartialEq)]
 #[non_exhaustive]
-pub struct ResponseCodec;
+pub struct ResponseCodec {
+    options: ResponseParseOptions,
+    max_response_size: Option<usize>,
+}
 
 /// Codec for idle dones.
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -47,6 +52,70 @@ impl_codec_new!(AuthenticateDataCodec);
 impl_codec_new!(ResponseCodec);
 impl_codec_new!(IdleDoneCodec);
 
+impl ResponseCodec {
+    /// Set the [`ParseMode`] used for decoding.
+    pub fn with_mode(mut self, mode: ParseMode) -> Self {
+        self.options.mode = mode;
+        self
+    }
+
+    /// Get the [`ParseMode`] used for decoding.
+    pub fn mode(&self) -> ParseMode {
+        self.options.mode
+    }
+
+    /// Set the maximum nesting depth of a `BODY` or `BODYSTRUCTURE` (default: 8).
+    ///
+    /// Every nested multipart or `message/rfc822` body counts as one level. A deeper body
+    /// structure is rejected with [`ResponseDecodeError::Failed`](crate::decode::ResponseDecodeError::Failed).
+    ///
+    /// Note: Parsing a body structure is recursive. Thus, a too high value may overflow the stack.
+    pub fn with_max_body_depth(mut self, max_body_depth: usize) -> Self {
+        self.options.max_body_depth = max_body_depth;
+        self
+    }
+
+    /// Get the maximum nesting depth of a `BODY` or `BODYSTRUCTURE`.
+    pub fn max_body_depth(&self) -> usize {
+        self.options.max_body_depth
+    }
+
+    /// Set the maximum nesting depth of a `THREAD` response (default: 8).
+    ///
+    /// Every parenthesized thread list counts as one level. A deeper thread is rejected with
+    /// [`ResponseDecodeError::Failed`](crate::decode::ResponseDecodeError::Failed).
+    ///
+    /// Note: Parsing a thread is recursive. Thus, a too high value may overflow the stack.
+    #[cfg(feature = "ext_sort_thread")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_sort_thread")))]
+    pub fn with_max_thread_depth(mut self, max_thread_depth: usize) -> Self {
+        self.options.max_thread_depth = max_thread_depth;
+        self
+    }
+
+    /// Get the maximum nesting depth of a `THREAD` response.
+    #[cfg(feature = "ext_sort_thread")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_sort_thread")))]
+    pub fn max_thread_depth(&self) -> usize {
+        self.options.max_thread_depth
+    }
+
+    /// Set the maximum size in bytes of a single response, including all literals (default: unlimited).
+    ///
+    /// A response that is (or will become) larger is rejected with
+    /// [`ResponseDecodeError::TooLarge`](crate::decode::ResponseDecodeError::TooLarge). This is
+    /// checked as soon as possible, i.e., while the response is still incomplete.
+    pub fn with_max_response_size(mut self, max_response_size: usize) -> Self {
+        self.max_response_size = Some(max_response_size);
+        self
+    }
+
+    /// Get the maximum size in bytes of a single response.
+    pub fn max_response_size(&self) -> Option<usize> {
+        self.max_response_size
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::num::NonZeroU32;
@@ -54,9 +123,9 @@ mod tests {
     use imap_types::{
         auth::AuthenticateData,
         command::{Command, CommandBody},
-        core::{IString, Literal, LiteralMode, NString, Tag, Vec1},
+        core::{Atom, IString, Literal, LiteralMode, NString, Tag, Vec1},
         extensions::idle::IdleDone,
-        fetch::MessageDataItem,
+        fetch::{MessageDataItem, MessageDataItemOther},
         mailbox::Mailbox,
         response::{Data, Greeting, GreetingKind, Response},
     };
@@ -152,6 +221,21 @@ mod tests {
                     ))))),
                 }),
             ),
+            (
+                b"* 1 FETCH (UID 1 XVENDOR (a \"b\" (1 2)))\r\n",
+                b"",
+                Response::Data(Data::Fetch {
+                    seq: NonZeroU32::new(1).unwrap(),
+                    items: Vec1::try_from(vec![
+                        MessageDataItem::Uid(NonZeroU32::new(1).unwrap()),
+                        MessageDataItem::Other(MessageDataItemOther::unvalidated(
+                            Atom::try_from("XVENDOR").unwrap(),
+                            b"(a \"b\" (1 2))".as_ref(),
+                        )),
+                    ])
+                    .unwrap(),
+                }),
+            ),
         ]);
     }
 
@@ -8,6 +8,17 @@
 //! This makes real-world decoding of IMAP more elaborate.
 //!
 //! Have a look at the [parse_command](https://github.com/duesee/imap-codec/blob/main/imap-codec/examples/parse_command.rs) example to see how a real-world application could decode IMAP.
+//!
+//! ## Allocations
+//!
+//! Decoded messages borrow from the input where possible, e.g., atoms, quoted strings without
+//! escapes, and literals. Collections such as flag lists or `SEARCH` results are owned by the
+//! decoded message. Thus, there is no parser state or scratch buffer that could be reused across
+//! calls: every returned collection needs its own allocation anyway. See the "Allocations"
+//! section in `CONTRIBUTING.md` for the rationale.
+//!
+//! Use the `stream` benchmark (`cargo bench -p imap-codec --bench stream`) to measure parsing
+//! throughput.
 
 use std::num::{ParseIntError, TryFromIntError};
 
@@ -125,6 +136,48 @@ pub trait Decoder {
     }
 }
 
+/// Parsing mode.
+///
+/// Real-world servers violate the IMAP grammar in small ways. The parsing mode decides whether
+/// these (common) deviations are rejected or tolerated.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum ParseMode {
+    /// Follow the IMAP grammar.
+    ///
+    /// Note: Enabled `quirk_*` features still apply.
+    #[default]
+    Strict,
+
+    /// Tolerate common deviations from the IMAP grammar.
+    ///
+    /// In addition to the `quirk_*` features, the following is accepted in responses:
+    ///
+    /// * a missing or empty status text, e.g., `* OK\r\n` or `A1 OK \r\n`,
+    /// * a missing space before a response code, e.g., `* OK[ALERT] ...\r\n`, and
+    /// * a bare `\n` as line ending, e.g., `* 1 EXISTS\n`.
+    ///
+    /// Missing text is rectified to `...`.
+    Lax,
+}
+
+/// Options shared by the response parsers.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(crate) struct ResponseParseOptions {
+    pub(crate) mode: ParseMode,
+    pub(crate) max_body_depth: usize,
+    pub(crate) max_thread_depth: usize,
+}
+
+impl Default for ResponseParseOptions {
+    fn default() -> Self {
+        Self {
+            mode: ParseMode::default(),
+            max_body_depth: 8,
+            max_thread_depth: 8,
+        }
+    }
+}
+
 /// Error during greeting decoding.
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -196,6 +249,51 @@ pub enum CommandDecodeError<'a> {
     Failed,
 }
 
+/// Outcome of [`ResponseCodec::feed`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseOutcome<'a> {
+    /// More data is needed.
+    Incomplete,
+
+    /// A response was decoded.
+    Complete {
+        /// Number of bytes consumed from the input.
+        consumed: usize,
+
+        /// Decoded response.
+        response: Response<'a>,
+    },
+
+    /// Decoding failed, i.e., more data won't help.
+    Error(ResponseDecodeError),
+}
+
+/// Response decoded by [`ResponseCodec::decode_spanned`].
+///
+/// Keeps the exact bytes the response was decoded from, e.g., to forward them unchanged.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SpannedResponse<'a> {
+    response: Response<'a>,
+    original: &'a [u8],
+}
+
+impl<'a> SpannedResponse<'a> {
+    /// Get the decoded response.
+    pub fn response(&self) -> &Response<'a> {
+        &self.response
+    }
+
+    /// Get the bytes the response was decoded from (including the trailing CRLF).
+    pub fn original_bytes(&self) -> &'a [u8] {
+        self.original
+    }
+
+    /// Take the decoded response, dropping the original bytes.
+    pub fn into_response(self) -> Response<'a> {
+        self.response
+    }
+}
+
 /// Error during authenticate data line decoding.
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -227,6 +325,11 @@ pub enum ResponseDecodeError {
         length: u32,
     },
 
+    /// The response exceeds the configured maximum size.
+    ///
+    /// See [`ResponseCodec::with_max_response_size`].
+    TooLarge,
+
     /// Decoding failed.
     Failed,
 }
@@ -296,20 +399,104 @@ impl Decoder for ResponseCodec {
         &self,
         input: &'a [u8],
     ) -> Result<(&'a [u8], Self::Message<'a>), Self::Error<'static>> {
-        match response(input) {
-            Ok((rem, rsp)) => Ok((rem, rsp)),
-            Err(nom::Err::Incomplete(_)) => Err(ResponseDecodeError::Incomplete),
+        let max = self.max_response_size.unwrap_or(usize::MAX);
+
+        match response(&self.options)(input) {
+            Ok((rem, rsp)) => {
+                if input.len() - rem.len() > max {
+                    return Err(ResponseDecodeError::TooLarge);
+                }
+
+                Ok((rem, rsp))
+            }
+            Err(nom::Err::Incomplete(_)) => {
+                // The response needs at least one more byte.
+                if input.len() >= max {
+                    return Err(ResponseDecodeError::TooLarge);
+                }
+
+                Err(ResponseDecodeError::Incomplete)
+            }
             Err(nom::Err::Error(error) | nom::Err::Failure(error)) => match error {
                 IMAPParseError {
                     kind: IMAPErrorKind::Literal { length, .. },
                     ..
-                } => Err(ResponseDecodeError::LiteralFound { length }),
+                } => {
+                    // The response needs at least the literal data.
+                    if input.len().saturating_add(length as usize) > max {
+                        return Err(ResponseDecodeError::TooLarge);
+                    }
+
+                    Err(ResponseDecodeError::LiteralFound { length })
+                }
                 _ => Err(ResponseDecodeError::Failed),
             },
         }
     }
 }
 
+impl ResponseCodec {
+    /// Decode a response from a (possibly partial) buffer.
+    ///
+    /// In contrast to [`Decoder::decode`], this unifies all cases in which more data is needed into
+    /// [`ParseOutcome::Incomplete`]. This is useful for clients reading from a socket because a
+    /// server sends literal data right away.
+    pub fn feed<'a>(&self, input: &'a [u8]) -> ParseOutcome<'a> {
+        match self.decode(input) {
+            Ok((remaining, response)) => ParseOutcome::Complete {
+                consumed: input.len() - remaining.len(),
+                response,
+            },
+            Err(ResponseDecodeError::Incomplete | ResponseDecodeError::LiteralFound { .. }) => {
+                ParseOutcome::Incomplete
+            }
+            Err(error) => ParseOutcome::Error(error),
+        }
+    }
+
+    /// Decode as many complete responses as possible from a buffer.
+    ///
+    /// Servers often send several responses at once, e.g., untagged responses followed by the
+    /// tagged completion. Decoding stops at the first response that is incomplete or fails to
+    /// decode. That response (and everything after it) is returned as the remaining input. Use
+    /// [`ResponseCodec::feed`] on the remaining input to tell both cases apart.
+    pub fn decode_all<'a>(&self, mut input: &'a [u8]) -> (Vec<Response<'a>>, &'a [u8]) {
+        let mut responses = Vec::new();
+
+        while let Ok((remaining, response)) = self.decode(input) {
+            responses.push(response);
+            input = remaining;
+        }
+
+        (responses, input)
+    }
+
+    /// Decode a response and retain the bytes it was decoded from.
+    ///
+    /// Encoding a decoded response yields its canonical form, e.g., `IMAP4REV1` instead of
+    /// `imap4rev1`. Use [`SpannedResponse::original_bytes`] to re-emit the response byte-for-byte.
+    pub fn decode_spanned<'a>(
+        &self,
+        input: &'a [u8],
+    ) -> Result<(&'a [u8], SpannedResponse<'a>), ResponseDecodeError> {
+        let (remaining, response) = self.decode(input)?;
+        let original = &input[..input.len() - remaining.len()];
+
+        Ok((remaining, SpannedResponse { response, original }))
+    }
+}
+
+/// Decode a response using the given [`ParseMode`].
+///
+/// This is a shorthand for `ResponseCodec::new().with_mode(mode).decode(input)`. Use a
+/// [`ResponseCodec`] to configure further options.
+pub fn parse_response_with<'a>(
+    mode: ParseMode,
+    input: &'a [u8],
+) -> Result<(&'a [u8], Response<'a>), ResponseDecodeError> {
+    ResponseCodec::new().with_mode(mode).decode(input)
+}
+
 impl Decoder for AuthenticateDataCodec {
     type Message<'a> = AuthenticateData<'a>;
     type Error<'a> = AuthenticateDataDecodeError;
@@ -350,11 +537,12 @@ mod tests {
 
     use imap_types::{
         command::{Command, CommandBody},
-        core::{IString, Literal, NString, Vec1},
+        core::{AString, IString, Literal, NString, QuotedChar, Tag, Vec1},
         extensions::idle::IdleDone,
         fetch::MessageDataItem,
         mailbox::Mailbox,
-        response::{Data, Greeting, GreetingKind, Response},
+        response::{Code, Data, Greeting, GreetingKind, Response, Status},
+        status::StatusDataItem,
     };
 
     use super::*;
@@ -476,6 +664,10 @@ mod tests {
             // Failed
             (b"* noop\r\n".as_ref(), Err(CommandDecodeError::Failed)),
             (b"A  noop\r\n".as_ref(), Err(CommandDecodeError::Failed)),
+            (
+                b"A login \"alice\r\nB logout\" x\r\n".as_ref(),
+                Err(CommandDecodeError::Failed),
+            ),
         ];
 
         for (test, expected) in tests {
@@ -683,4 +875,352 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_decode_response_literal_mailbox() {
+        let status = Response::Data(Data::Status {
+            mailbox: Mailbox::Inbox,
+            items: vec![StatusDataItem::Messages(3)].into(),
+        });
+        let list = Response::Data(Data::List {
+            items: vec![],
+            delimiter: Some(QuotedChar::try_from('/').unwrap()),
+            mailbox: Mailbox::from(AString::String(IString::Literal(
+                Literal::try_from(b"a b".as_ref()).unwrap(),
+            ))),
+        });
+
+        let tests = [
+            (
+                b"* STATUS {5}\r\n".as_ref(),
+                Err(ResponseDecodeError::LiteralFound { length: 5 }),
+            ),
+            (
+                b"* STATUS {5}\r\nINB".as_ref(),
+                Err(ResponseDecodeError::Incomplete),
+            ),
+            (
+                b"* STATUS {5}\r\nINBOX (MESSAGES 3)".as_ref(),
+                Err(ResponseDecodeError::Incomplete),
+            ),
+            (
+                b"* STATUS {5}\r\nINBOX (MESSAGES 3)\r\n".as_ref(),
+                Ok((b"".as_ref(), status)),
+            ),
+            (
+                b"* LIST () \"/\" {3}\r\n".as_ref(),
+                Err(ResponseDecodeError::LiteralFound { length: 3 }),
+            ),
+            (
+                b"* LIST () \"/\" {3}\r\na b\r\n".as_ref(),
+                Ok((b"".as_ref(), list)),
+            ),
+        ];
+
+        for (test, expected) in tests {
+            let got = ResponseCodec::default().decode(test);
+            dbg!((std::str::from_utf8(test).unwrap(), &expected, &got));
+            assert_eq!(expected, got);
+        }
+    }
+
+    #[test]
+    fn test_decode_response_mode() {
+        let test = b"* OK[ALERT] Hello\r\n".as_ref();
+
+        assert_eq!(
+            ResponseCodec::default().decode(test),
+            Err(ResponseDecodeError::Failed)
+        );
+        assert_eq!(
+            ResponseCodec::default()
+                .with_mode(ParseMode::Lax)
+                .decode(test),
+            Ok((
+                b"".as_ref(),
+                Response::Status(Status::ok(None, Some(Code::Alert), "Hello").unwrap())
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_response_with() {
+        // Not covered by any `quirk_*` feature.
+        let test = b"* OK[ALERT] Hello\r\n".as_ref();
+
+        assert_eq!(
+            parse_response_with(ParseMode::Strict, test),
+            Err(ResponseDecodeError::Failed)
+        );
+        assert_eq!(
+            parse_response_with(ParseMode::Lax, test),
+            Ok((
+                b"".as_ref(),
+                Response::Status(Status::ok(None, Some(Code::Alert), "Hello").unwrap())
+            ))
+        );
+    }
+
+    #[test]
+    fn test_decode_response_max_body_depth() {
+        fn nested_multipart(depth: usize) -> Vec<u8> {
+            let mut out = b"* 1 FETCH (BODYSTRUCTURE ".to_vec();
+            out.extend(b"(".repeat(depth));
+            out.extend(b"\"TEXT\" \"PLAIN\" NIL NIL NIL \"7BIT\" 0 0");
+            out.extend(b") \"MIXED\"".repeat(depth - 1));
+            out.extend(b"))\r\n");
+            out
+        }
+
+        fn nested_message(depth: usize) -> Vec<u8> {
+            let mut out = b"* 1 FETCH (BODYSTRUCTURE ".to_vec();
+            for _ in 1..depth {
+                out.extend(b"(\"MESSAGE\" \"RFC822\" NIL NIL NIL \"7BIT\" 0 ");
+                out.extend(b"(NIL NIL NIL NIL NIL NIL NIL NIL NIL NIL) ");
+            }
+            out.extend(b"(\"TEXT\" \"PLAIN\" NIL NIL NIL \"7BIT\" 0 0)");
+            out.extend(b" 0)".repeat(depth - 1));
+            out.extend(b")\r\n");
+            out
+        }
+
+        for nested in [nested_multipart, nested_message] {
+            assert!(ResponseCodec::default().decode(&nested(8)).is_ok());
+            assert_eq!(
+                ResponseCodec::default().decode(&nested(9)),
+                Err(ResponseDecodeError::Failed)
+            );
+
+            let codec = ResponseCodec::default().with_max_body_depth(32);
+            assert!(codec.decode(&nested(32)).is_ok());
+            assert_eq!(codec.decode(&nested(33)), Err(ResponseDecodeError::Failed));
+
+            // Must not overflow the stack.
+            assert_eq!(
+                ResponseCodec::default().decode(&nested(100_000)),
+                Err(ResponseDecodeError::Failed)
+            );
+        }
+    }
+
+    #[cfg(feature = "ext_sort_thread")]
+    #[test]
+    fn test_decode_response_max_thread_depth() {
+        fn nested_thread(depth: usize) -> Vec<u8> {
+            let mut out = b"* THREAD ".to_vec();
+            out.extend(b"(".repeat(depth - 1));
+            out.extend(b"(1)");
+            out.extend(b"(2))".repeat(depth - 1));
+            out.extend(b"\r\n");
+            out
+        }
+
+        assert!(ResponseCodec::default().decode(&nested_thread(8)).is_ok());
+        assert_eq!(
+            ResponseCodec::default().decode(&nested_thread(9)),
+            Err(ResponseDecodeError::Failed)
+        );
+
+        let codec = ResponseCodec::default().with_max_thread_depth(32);
+        assert!(codec.decode(&nested_thread(32)).is_ok());
+        assert_eq!(
+            codec.decode(&nested_thread(33)),
+            Err(ResponseDecodeError::Failed)
+        );
+
+        // Must not overflow the stack.
+        assert_eq!(
+            ResponseCodec::default().decode(&nested_thread(100_000)),
+            Err(ResponseDecodeError::Failed)
+        );
+    }
+
+    #[test]
+    fn test_decode_response_max_response_size() {
+        let codec = ResponseCodec::default().with_max_response_size(32);
+
+        // Under the limit.
+        let test = b"* 1 FETCH (RFC822 {5}\r\nhello)\r\n".as_ref();
+        assert!(test.len() <= 32);
+        assert!(codec.decode(test).is_ok());
+        assert_eq!(
+            codec.decode(b"* 1 FETCH (RFC822 {5}\r\n"),
+            Err(ResponseDecodeError::LiteralFound { length: 5 })
+        );
+        assert_eq!(
+            codec.decode(b"* 1 FETCH (RFC822 {5}\r\nhel"),
+            Err(ResponseDecodeError::Incomplete)
+        );
+
+        // Over the limit.
+        let test = b"* 1 FETCH (RFC822 {11}\r\nhello world)\r\n".as_ref();
+        assert!(test.len() > 32);
+        assert!(ResponseCodec::default().decode(test).is_ok());
+        assert_eq!(codec.decode(test), Err(ResponseDecodeError::TooLarge));
+        assert_eq!(
+            codec.feed(test),
+            ParseOutcome::Error(ResponseDecodeError::TooLarge)
+        );
+
+        // Rejected early, i.e., before the literal data was received ...
+        assert_eq!(
+            codec.decode(b"* 1 FETCH (RFC822 {4294967295}\r\n"),
+            Err(ResponseDecodeError::TooLarge)
+        );
+
+        // ... or while the line is still incomplete.
+        assert_eq!(
+            codec.decode(b"* OK aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"),
+            Err(ResponseDecodeError::TooLarge)
+        );
+    }
+
+    #[test]
+    fn test_decode_response_spanned() {
+        use crate::encode::Encoder;
+
+        let tests = [
+            (
+                b"* CAPABILITY imap4rev1\r\n".as_ref(),
+                b"* CAPABILITY IMAP4REV1\r\n".as_ref(),
+            ),
+            (b"* ok [alert] Hello\r\n", b"* OK [ALERT] Hello\r\n"),
+            (
+                b"* 1 fetch (rfc822 {5}\r\nhello)\r\n",
+                b"* 1 FETCH (RFC822 {5}\r\nhello)\r\n",
+            ),
+        ];
+
+        for (test, canonical) in tests {
+            let input = [test, b"A1 OK ...\r\n"].concat();
+
+            let (remaining, got) = ResponseCodec::default().decode_spanned(&input).unwrap();
+            assert_eq!(remaining, b"A1 OK ...\r\n");
+            assert_eq!(got.original_bytes(), test);
+            assert_ne!(test, canonical);
+            assert_eq!(
+                ResponseCodec::default().encode(got.response()).dump(),
+                canonical
+            );
+            assert_eq!(
+                got.into_response(),
+                ResponseCodec::default().decode(test).unwrap().1
+            );
+        }
+
+        assert_eq!(
+            ResponseCodec::default().decode_spanned(b"* 1 FETCH (RFC822 {5}\r\n"),
+            Err(ResponseDecodeError::LiteralFound { length: 5 })
+        );
+    }
+
+    #[test]
+    fn test_decode_all_responses() {
+        let tests = [
+            (b"".as_ref(), vec![], b"".as_ref()),
+            (b"* 3 EXISTS", vec![], b"* 3 EXISTS"),
+            (
+                b"* 3 EXISTS\r\nA1 OK done\r\n",
+                vec![
+                    Response::Data(Data::Exists(3)),
+                    Response::Status(
+                        Status::ok(Some(Tag::try_from("A1").unwrap()), None, "done").unwrap(),
+                    ),
+                ],
+                b"",
+            ),
+            (
+                b"* 3 EXISTS\r\n* 1 RECENT\r\nA1 OK do",
+                vec![
+                    Response::Data(Data::Exists(3)),
+                    Response::Data(Data::Recent(1)),
+                ],
+                b"A1 OK do",
+            ),
+            (
+                b"* 3 EXISTS\r\n* 1 FETCH (RFC822 {5}\r\nhel",
+                vec![Response::Data(Data::Exists(3))],
+                b"* 1 FETCH (RFC822 {5}\r\nhel",
+            ),
+            (
+                b"* 3 EXISTS\r\n* 1 EXISTS\r\r\nA1 OK done\r\n",
+                vec![Response::Data(Data::Exists(3))],
+                b"* 1 EXISTS\r\r\nA1 OK done\r\n",
+            ),
+        ];
+
+        for (test, expected, expected_remaining) in tests {
+            let (got, remaining) = ResponseCodec::default().decode_all(test);
+            assert_eq!(expected, got);
+            assert_eq!(expected_remaining, remaining);
+        }
+    }
+
+    #[test]
+    fn test_feed_response() {
+        let fetch = Response::Data(Data::Fetch {
+            seq: NonZeroU32::new(1).unwrap(),
+            items: Vec1::from(MessageDataItem::Rfc822(NString(Some(IString::Literal(
+                Literal::try_from(b"hello".as_ref()).unwrap(),
+            ))))),
+        });
+
+        let tests = [
+            // Incomplete
+            (b"".as_ref(), ParseOutcome::Incomplete),
+            (b"* 1 EXISTS".as_ref(), ParseOutcome::Incomplete),
+            (b"* 1 EXISTS\r".as_ref(), ParseOutcome::Incomplete),
+            // Truncated literal
+            (b"* 1 FETCH (RFC822 {5}".as_ref(), ParseOutcome::Incomplete),
+            (
+                b"* 1 FETCH (RFC822 {5}\r\n".as_ref(),
+                ParseOutcome::Incomplete,
+            ),
+            (
+                b"* 1 FETCH (RFC822 {5}\r\nhel".as_ref(),
+                ParseOutcome::Incomplete,
+            ),
+            (
+                b"* 1 FETCH (RFC822 {5}\r\nhello)".as_ref(),
+                ParseOutcome::Incomplete,
+            ),
+            // Complete
+            (
+                b"* 1 EXISTS\r\n".as_ref(),
+                ParseOutcome::Complete {
+                    consumed: 12,
+                    response: Response::Data(Data::Exists(1)),
+                },
+            ),
+            (
+                b"* 1 EXISTS\r\n* 2 EXISTS\r\n".as_ref(),
+                ParseOutcome::Complete {
+                    consumed: 12,
+                    response: Response::Data(Data::Exists(1)),
+                },
+            ),
+            (
+                b"* 1 FETCH (RFC822 {5}\r\nhello)\r\n".as_ref(),
+                ParseOutcome::Complete {
+                    consumed: 31,
+                    response: fetch,
+                },
+            ),
+            // Error
+            (
+                b"* 1 EXISTS\r\r\n".as_ref(),
+                ParseOutcome::Error(ResponseDecodeError::Failed),
+            ),
+            (
+                b"*  search 1 2 3\r\n".as_ref(),
+                ParseOutcome::Error(ResponseDecodeError::Failed),
+            ),
+        ];
+
+        for (test, expected) in tests {
+            let got = ResponseCodec::default().feed(test);
+            dbg!((std::str::from_utf8(test).unwrap(), &expected, &got));
+            assert_eq!(expected, got);
+        }
+    }
 }
@@ -51,6 +51,8 @@ use base64::{engine::general_purpose::STANDARD as base64, Engine};
 use chrono::{DateTime as ChronoDateTime, FixedOffset};
 #[cfg(any(feature = "ext_binary", feature = "ext_metadata"))]
 use imap_types::core::NString8;
+#[cfg(feature = "ext_esearch")]
+use imap_types::extensions::esearch::SearchReturnOption;
 use imap_types::{
     auth::{AuthMechanism, AuthenticateData},
     body::{
@@ -79,6 +81,7 @@ use imap_types::{
     status::{StatusDataItem, StatusDataItemName},
     utils::escape_quoted,
 };
+use thiserror::Error;
 use utils::{join_serializable, List1AttributeValueOrNil, List1OrNil};
 
 use crate::{AuthenticateDataCodec, CommandCodec, GreetingCodec, IdleDoneCodec, ResponseCodec};
@@ -139,6 +142,68 @@ impl Encoded {
 
         out
     }
+
+    /// Dump the (remaining) encoded data using the given [`EncodeOptions`].
+    ///
+    /// This is useful for inspection or tools that expect `\n`. Note that IMAP requires `\r\n` on
+    /// the wire, and that a literal announcement, e.g., `{3}\r\n`, always ends with `\r\n`.
+    pub fn dump_with(self, options: EncodeOptions) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut fragments = self.items.into_iter().peekable();
+
+        while let Some(fragment) = fragments.next() {
+            match fragment {
+                Fragment::Line { mut data } => {
+                    let announces_literal =
+                        matches!(fragments.peek(), Some(Fragment::Literal { .. }));
+
+                    if !announces_literal && data.ends_with(b"\r\n") {
+                        data.truncate(data.len() - 2);
+                        data.extend_from_slice(options.line_ending.as_bytes());
+                    }
+
+                    out.append(&mut data);
+                }
+                Fragment::Literal { mut data, .. } => out.append(&mut data),
+            }
+        }
+
+        out
+    }
+}
+
+/// Options for [`Encoded::dump_with`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct EncodeOptions {
+    pub line_ending: LineEnding,
+}
+
+impl EncodeOptions {
+    /// Set the [`LineEnding`].
+    pub fn with_line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+}
+
+/// Line ending used to terminate a message.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum LineEnding {
+    /// `\r\n` as required by IMAP.
+    #[default]
+    CrLf,
+    /// `\n`
+    Lf,
+}
+
+impl LineEnding {
+    pub fn as_bytes(&self) -> &'static [u8] {
+        match self {
+            Self::CrLf => b"\r\n",
+            Self::Lf => b"\n",
+        }
+    }
 }
 
 impl Iterator for Encoded {
@@ -252,6 +317,111 @@ impl_encoder_for_codec!(AuthenticateDataCodec, AuthenticateData<'a>);
 impl_encoder_for_codec!(ResponseCodec, Response<'a>);
 impl_encoder_for_codec!(IdleDoneCodec, IdleDone);
 
+/// Error during capability-checked encoding.
+///
+/// See [`CommandCodec::encode_checked`].
+#[derive(Clone, Debug, Eq, Error, Hash, PartialEq)]
+#[error("{command} requires the {capability} capability")]
+pub struct UnsupportedFeature<'a> {
+    /// Name of the command.
+    pub command: &'static str,
+    /// Capability the server did not advertise.
+    pub capability: Capability<'a>,
+}
+
+impl CommandCodec {
+    /// Encode a command after checking that the server advertised all required capabilities.
+    ///
+    /// This rejects, e.g., a `MOVE` when the server didn't advertise `MOVE`, or a `UID EXPUNGE`
+    /// when the server didn't advertise `UIDPLUS`.
+    ///
+    /// Note: Only the command itself is checked, not its arguments (such as `BINARY` fetch items).
+    pub fn encode_checked<'a>(
+        &self,
+        command: &Command<'a>,
+        capabilities: &[Capability],
+    ) -> Result<Encoded, UnsupportedFeature<'a>> {
+        if let Some(capability) = missing_capability(&command.body, capabilities) {
+            return Err(UnsupportedFeature {
+                command: command.name(),
+                capability,
+            });
+        }
+
+        Ok(self.encode(command))
+    }
+}
+
+fn missing_capability<'a>(
+    body: &CommandBody<'a>,
+    capabilities: &[Capability],
+) -> Option<Capability<'a>> {
+    let has = |required: &Capability| capabilities.contains(required);
+
+    let required = match body {
+        #[cfg(feature = "starttls")]
+        CommandBody::StartTLS => Capability::StartTls,
+        CommandBody::Authenticate {
+            mechanism,
+            initial_response,
+        } => {
+            if initial_response.is_some() && !has(&Capability::SaslIr) {
+                return Some(Capability::SaslIr);
+            }
+
+            Capability::Auth(mechanism.clone())
+        }
+        #[cfg(feature = "ext_sort_thread")]
+        CommandBody::Sort { .. } => {
+            if capabilities
+                .iter()
+                .any(|capability| matches!(capability, Capability::Sort(_)))
+            {
+                return None;
+            }
+
+            Capability::Sort(None)
+        }
+        #[cfg(feature = "ext_sort_thread")]
+        CommandBody::Thread { algorithm, .. } => Capability::Thread(algorithm.clone()),
+        CommandBody::Unselect => Capability::Unselect,
+        #[cfg(feature = "ext_uidplus")]
+        CommandBody::ExpungeUid { .. } => Capability::UidPlus,
+        CommandBody::Idle => Capability::Idle,
+        CommandBody::Enable { .. } => Capability::Enable,
+        CommandBody::Compress { algorithm } => Capability::Compress {
+            algorithm: algorithm.clone(),
+        },
+        CommandBody::GetQuota { .. }
+        | CommandBody::GetQuotaRoot { .. }
+        | CommandBody::SetQuota { .. } => Capability::Quota,
+        CommandBody::Move { .. } => Capability::Move,
+        #[cfg(feature = "ext_id")]
+        CommandBody::Id { .. } => Capability::Id,
+        #[cfg(feature = "ext_metadata")]
+        CommandBody::SetMetadata { .. } | CommandBody::GetMetadata { .. } => {
+            if has(&Capability::MetadataServer) {
+                return None;
+            }
+
+            Capability::Metadata
+        }
+        #[cfg(feature = "ext_acl")]
+        CommandBody::SetAcl { .. }
+        | CommandBody::DeleteAcl { .. }
+        | CommandBody::GetAcl { .. }
+        | CommandBody::ListRights { .. }
+        | CommandBody::MyRights { .. } => Capability::Acl,
+        _ => return None,
+    };
+
+    if has(&required) {
+        None
+    } else {
+        Some(required)
+    }
+}
+
 // -------------------------------------------------------------------------------------------------
 
 pub(crate) trait EncodeIntoContext {
@@ -260,15 +430,37 @@ pub(crate) trait EncodeIntoContext {
 
 // ----- Primitive ---------------------------------------------------------------------------------
 
+/// Write `value` as decimal number.
+///
+/// Note: This avoids an allocation (or the formatting machinery) per number, which matters for
+/// long lists of numbers, e.g., in `SEARCH` responses.
+fn write_decimal(mut value: u64, ctx: &mut EncodeContext) -> std::io::Result<()> {
+    // `u64::MAX` has 20 digits.
+    let mut buffer = [0u8; 20];
+    let mut start = buffer.len();
+
+    loop {
+        start -= 1;
+        buffer[start] = b'0' + (value % 10) as u8;
+        value /= 10;
+
+        if value == 0 {
+            break;
+        }
+    }
+
+    ctx.write_all(&buffer[start..])
+}
+
 impl EncodeIntoContext for u32 {
     fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
-        ctx.write_all(self.to_string().as_bytes())
+        write_decimal(u64::from(*self), ctx)
     }
 }
 
 impl EncodeIntoContext for u64 {
     fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
-        ctx.write_all(self.to_string().as_bytes())
+        write_decimal(*self, ctx)
     }
 }
 
@@ -285,6 +477,8 @@ impl<'a> EncodeIntoContext for Command<'a> {
 
 impl<'a> EncodeIntoContext for Tag<'a> {
     fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        debug_assert!(Tag::validate(self.inner()).is_ok(), "invalid {self:?}");
+
         ctx.write_all(self.inner().as_bytes())
     }
 }
@@ -436,6 +630,8 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
             CommandBody::Search {
                 charset,
                 criteria,
+                #[cfg(feature = "ext_esearch")]
+                return_options,
                 uid,
             } => {
                 if *uid {
@@ -443,6 +639,12 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
                 } else {
                     ctx.write_all(b"SEARCH")?;
                 }
+                #[cfg(feature = "ext_esearch")]
+                if !return_options.is_empty() {
+                    ctx.write_all(b" RETURN (")?;
+                    join_serializable(return_options, b" ", ctx)?;
+                    ctx.write_all(b")")?;
+                }
                 if let Some(charset) = charset {
                     ctx.write_all(b" CHARSET ")?;
                     charset.encode_ctx(ctx)?;
@@ -659,6 +861,60 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
                     ctx.write_all(b")")
                 }
             }
+            #[cfg(feature = "ext_acl")]
+            CommandBody::SetAcl {
+                mailbox,
+                identifier,
+                mod_rights,
+            } => {
+                ctx.write_all(b"SETACL ")?;
+                mailbox.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                identifier.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                mod_rights.encode_ctx(ctx)
+            }
+            #[cfg(feature = "ext_acl")]
+            CommandBody::DeleteAcl {
+                mailbox,
+                identifier,
+            } => {
+                ctx.write_all(b"DELETEACL ")?;
+                mailbox.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                identifier.encode_ctx(ctx)
+            }
+            #[cfg(feature = "ext_acl")]
+            CommandBody::GetAcl { mailbox } => {
+                ctx.write_all(b"GETACL ")?;
+                mailbox.encode_ctx(ctx)
+            }
+            #[cfg(feature = "ext_acl")]
+            CommandBody::ListRights {
+                mailbox,
+                identifier,
+            } => {
+                ctx.write_all(b"LISTRIGHTS ")?;
+                mailbox.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                identifier.encode_ctx(ctx)
+            }
+            #[cfg(feature = "ext_acl")]
+            CommandBody::MyRights { mailbox } => {
+                ctx.write_all(b"MYRIGHTS ")?;
+                mailbox.encode_ctx(ctx)
+            }
+            #[cfg(feature = "legacy")]
+            CommandBody::XList {
+                reference,
+                mailbox_wildcard,
+            } => {
+                ctx.write_all(b"XLIST")?;
+                ctx.write_all(b" ")?;
+                reference.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                mailbox_wildcard.encode_ctx(ctx)
+            }
         }
     }
 }
@@ -693,12 +949,16 @@ impl<'a> EncodeIntoContext for AString<'a> {
 
 impl<'a> EncodeIntoContext for Atom<'a> {
     fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        debug_assert!(Atom::validate(self.inner()).is_ok(), "invalid {self:?}");
+
         ctx.write_all(self.inner().as_bytes())
     }
 }
 
 impl<'a> EncodeIntoContext for AtomExt<'a> {
     fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        debug_assert!(AtomExt::validate(self.inner()).is_ok(), "invalid {self:?}");
+
         ctx.write_all(self.inner().as_bytes())
     }
 }
@@ -714,6 +974,8 @@ impl<'a> EncodeIntoContext for IString<'a> {
 
 impl<'a> EncodeIntoContext for Literal<'a> {
     fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        debug_assert!(Literal::validate(self.data()).is_ok(), "invalid {self:?}");
+
         match self.mode() {
             LiteralMode::Sync => write!(ctx, "{{{}}}\r\n", self.as_ref().len())?,
             LiteralMode::NonSync => write!(ctx, "{{{}+}}\r\n", self.as_ref().len())?,
@@ -729,6 +991,8 @@ impl<'a> EncodeIntoContext for Literal<'a> {
 
 impl<'a> EncodeIntoContext for Quoted<'a> {
     fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        debug_assert!(Quoted::validate(self.inner()).is_ok(), "invalid {self:?}");
+
         write!(ctx, "\"{}\"", escape_quoted(self.inner()))
     }
 }
@@ -818,6 +1082,13 @@ impl<'a> EncodeIntoContext for Charset<'a> {
     }
 }
 
+#[cfg(feature = "ext_esearch")]
+impl EncodeIntoContext for SearchReturnOption {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        write!(ctx, "{self}")
+    }
+}
+
 impl<'a> EncodeIntoContext for SearchKey<'a> {
     fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
         match self {
@@ -942,6 +1213,8 @@ impl EncodeIntoContext for Sequence {
                 ctx.write_all(b":")?;
                 to.encode_ctx(ctx)
             }
+            #[cfg(feature = "ext_searchres")]
+            Sequence::SearchResult => ctx.write_all(b"$"),
         }
     }
 }
@@ -1110,7 +1383,7 @@ impl EncodeIntoContext for Part {
 
 impl EncodeIntoContext for NonZeroU32 {
     fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
-        write!(ctx, "{self}")
+        write_decimal(u64::from(self.get()), ctx)
     }
 }
 
@@ -1245,9 +1518,14 @@ impl<'a> EncodeIntoContext for Code<'a> {
             }
             // RFC 2221
             #[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
-            Code::Referral(url) => {
-                ctx.write_all(b"REFERRAL ")?;
-                ctx.write_all(url.as_bytes())
+            Code::Referral(urls) => {
+                ctx.write_all(b"REFERRAL")?;
+
+                for url in urls.as_ref() {
+                    write!(ctx, " {url}")?;
+                }
+
+                Ok(())
             }
             Code::CompressionActive => ctx.write_all(b"COMPRESSIONACTIVE"),
             Code::OverQuota => ctx.write_all(b"OVERQUOTA"),
@@ -1294,6 +1572,8 @@ impl<'a> EncodeIntoContext for CodeOther<'a> {
 
 impl<'a> EncodeIntoContext for Text<'a> {
     fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        debug_assert!(Text::validate(self.inner()).is_ok(), "invalid {self:?}");
+
         ctx.write_all(self.inner().as_bytes())
     }
 }
@@ -1458,6 +1738,35 @@ impl<'a> EncodeIntoContext for Data<'a> {
                 ctx.write_all(b" ")?;
                 items.encode_ctx(ctx)?;
             }
+            #[cfg(feature = "legacy")]
+            Data::XList {
+                items,
+                delimiter,
+                mailbox,
+            } => {
+                ctx.write_all(b"* XLIST (")?;
+                join_serializable(items, b" ", ctx)?;
+                ctx.write_all(b") ")?;
+
+                if let Some(delimiter) = delimiter {
+                    ctx.write_all(b"\"")?;
+                    delimiter.encode_ctx(ctx)?;
+                    ctx.write_all(b"\"")?;
+                } else {
+                    ctx.write_all(b"NIL")?;
+                }
+                ctx.write_all(b" ")?;
+                mailbox.encode_ctx(ctx)?;
+            }
+            Data::Other(other) => {
+                ctx.write_all(b"* ")?;
+                other.name().encode_ctx(ctx)?;
+
+                if !other.value().is_empty() {
+                    ctx.write_all(b" ")?;
+                    ctx.write_all(other.value())?;
+                }
+            }
         }
 
         ctx.write_all(b"\r\n")
@@ -1482,36 +1791,7 @@ impl EncodeIntoContext for QuotedChar {
 
 impl EncodeIntoContext for StatusDataItem {
     fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
-        match self {
-            Self::Messages(count) => {
-                ctx.write_all(b"MESSAGES ")?;
-                count.encode_ctx(ctx)
-            }
-            Self::Recent(count) => {
-                ctx.write_all(b"RECENT ")?;
-                count.encode_ctx(ctx)
-            }
-            Self::UidNext(next) => {
-                ctx.write_all(b"UIDNEXT ")?;
-                next.encode_ctx(ctx)
-            }
-            Self::UidValidity(identifier) => {
-                ctx.write_all(b"UIDVALIDITY ")?;
-                identifier.encode_ctx(ctx)
-            }
-            Self::Unseen(count) => {
-                ctx.write_all(b"UNSEEN ")?;
-                count.encode_ctx(ctx)
-            }
-            Self::Deleted(count) => {
-                ctx.write_all(b"DELETED ")?;
-                count.encode_ctx(ctx)
-            }
-            Self::DeletedStorage(count) => {
-                ctx.write_all(b"DELETED-STORAGE ")?;
-                count.encode_ctx(ctx)
-            }
-        }
+        write!(ctx, "{self}")
     }
 }
 
@@ -1584,6 +1864,11 @@ impl<'a> EncodeIntoContext for MessageDataItem<'a> {
                 ctx.write_all(b"] ")?;
                 size.encode_ctx(ctx)
             }
+            Self::Other(other) => {
+                other.name().encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                ctx.write_all(other.value())
+            }
         }
     }
 }
@@ -1951,6 +2236,227 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "invalid Literal")]
+    fn test_encode_invalid_literal_panics() {
+        // Deserialization bypasses validation, i.e., this literal contains a NUL byte.
+        let literal: Literal =
+            serde_json::from_str(r#"{"data":[104,0,105],"mode":"Sync"}"#).unwrap();
+
+        let rsp = Response::Data(Data::Fetch {
+            seq: NonZeroU32::new(1).unwrap(),
+            items: Vec1::from(MessageDataItem::Rfc822(NString::from(literal))),
+        });
+
+        ResponseCodec::default().encode(&rsp);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "invalid Tag")]
+    fn test_encode_invalid_tag_panics() {
+        let tag: Tag = serde_json::from_str(r#""A 1""#).unwrap();
+
+        CommandCodec::default().encode(&Command {
+            tag,
+            body: CommandBody::Noop,
+        });
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "invalid Text")]
+    fn test_encode_invalid_text_panics() {
+        let text: Text = serde_json::from_str(r#""a\r\nb""#).unwrap();
+
+        ResponseCodec::default().encode(&Response::Status(Status::Untagged(StatusBody {
+            kind: StatusKind::Ok,
+            code: None,
+            text,
+        })));
+    }
+
+    #[test]
+    fn test_write_decimal() {
+        for value in [0, 1, 9, 10, 42, 100, 4294967295, u64::MAX] {
+            let mut ctx = EncodeContext::new();
+            write_decimal(value, &mut ctx).unwrap();
+            assert_eq!(ctx.dump(), value.to_string().as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_encode_search_long() {
+        let seqs: Vec<_> = (1..=100_000).map(|n| NonZeroU32::new(n).unwrap()).collect();
+
+        let mut expected = String::from("* SEARCH");
+        for seq in seqs.iter() {
+            expected.push_str(&format!(" {seq}"));
+        }
+        expected.push_str("\r\n");
+
+        let got = ResponseCodec::default()
+            .encode(&Response::Data(Data::Search(seqs)))
+            .dump();
+        assert_eq!(got, expected.as_bytes());
+
+        assert_eq!(
+            ResponseCodec::default()
+                .encode(&Response::Data(Data::Search(vec![NonZeroU32::new(
+                    u32::MAX
+                )
+                .unwrap()])))
+                .dump(),
+            b"* SEARCH 4294967295\r\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn test_encode_zeroized_login() {
+        use zeroize::Zeroize;
+
+        let tests = [
+            (String::from("xyz123"), b"A LOGIN alice \"\"\r\n".as_ref()),
+            (String::from("xyz 123"), b"A LOGIN alice \"\"\r\n"),
+            (String::from("xyz\n123"), b"A LOGIN alice {0}\r\n\r\n"),
+        ];
+
+        for (password, expected) in tests {
+            let mut cmd = CommandBody::login("alice", password)
+                .unwrap()
+                .tag("A")
+                .unwrap();
+
+            // Encoding must not trip the debug assertions, e.g., by producing an empty atom.
+            if let CommandBody::Login { password, .. } = &mut cmd.body {
+                password.zeroize();
+            }
+
+            assert_eq!(CommandCodec::default().encode(&cmd).dump(), expected);
+        }
+    }
+
+    #[test]
+    fn test_dump_with_line_ending() {
+        let rsp = Response::Data(Data::Fetch {
+            seq: NonZeroU32::new(1).unwrap(),
+            items: Vec1::from(MessageDataItem::BodyExt {
+                section: None,
+                origin: None,
+                data: NString::from(Literal::try_from(b"xxx".as_ref()).unwrap()),
+            }),
+        });
+
+        let tests = [
+            (
+                EncodeOptions::default(),
+                b"* 1 FETCH (BODY[] {3}\r\nxxx)\r\n".as_ref(),
+            ),
+            (
+                EncodeOptions::default().with_line_ending(LineEnding::Lf),
+                b"* 1 FETCH (BODY[] {3}\r\nxxx)\n",
+            ),
+        ];
+
+        for (options, expected) in tests {
+            let got = ResponseCodec::default().encode(&rsp).dump_with(options);
+            assert_eq!(expected, got, "{}", escape_byte_string(&got));
+        }
+
+        let cmd = Command::new("A", CommandBody::Noop).unwrap();
+        assert_eq!(
+            CommandCodec::default()
+                .encode(&cmd)
+                .dump_with(EncodeOptions::default().with_line_ending(LineEnding::Lf)),
+            b"A NOOP\n"
+        );
+        assert_eq!(
+            CommandCodec::default()
+                .encode(&cmd)
+                .dump_with(EncodeOptions::default()),
+            CommandCodec::default().encode(&cmd).dump()
+        );
+    }
+
+    #[test]
+    fn test_encode_checked() {
+        let codec = CommandCodec::default();
+        let caps = [Capability::Imap4Rev1, Capability::Move, Capability::Idle];
+
+        let cmd = Command::new(
+            "A",
+            CommandBody::Move {
+                sequence_set: "1:*".try_into().unwrap(),
+                mailbox: "Trash".try_into().unwrap(),
+                uid: false,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            codec.encode_checked(&cmd, &caps).unwrap().dump(),
+            b"A MOVE 1:* Trash\r\n"
+        );
+        assert_eq!(
+            codec.encode_checked(&cmd, &[Capability::Imap4Rev1]).err(),
+            Some(UnsupportedFeature {
+                command: "MOVE",
+                capability: Capability::Move,
+            })
+        );
+
+        let cmd = Command::new(
+            "A",
+            CommandBody::authenticate_with_ir(AuthMechanism::Plain, b"".as_ref()),
+        )
+        .unwrap();
+        assert_eq!(
+            codec
+                .encode_checked(&cmd, &[Capability::Auth(AuthMechanism::Plain)])
+                .err(),
+            Some(UnsupportedFeature {
+                command: "AUTHENTICATE",
+                capability: Capability::SaslIr,
+            })
+        );
+        assert!(codec
+            .encode_checked(
+                &cmd,
+                &[Capability::Auth(AuthMechanism::Plain), Capability::SaslIr]
+            )
+            .is_ok());
+
+        // Commands of IMAP4rev1 are always accepted.
+        let cmd = Command::new("A", CommandBody::Noop).unwrap();
+        assert!(codec.encode_checked(&cmd, &[]).is_ok());
+    }
+
+    #[cfg(feature = "ext_uidplus")]
+    #[test]
+    fn test_encode_checked_uid_expunge() {
+        let cmd = Command::new(
+            "A",
+            CommandBody::ExpungeUid {
+                sequence_set: "1:3".try_into().unwrap(),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            CommandCodec::default()
+                .encode_checked(&cmd, &[Capability::Imap4Rev1])
+                .err(),
+            Some(UnsupportedFeature {
+                command: "EXPUNGE",
+                capability: Capability::UidPlus,
+            })
+        );
+        assert!(CommandCodec::default()
+            .encode_checked(&cmd, &[Capability::Imap4Rev1, Capability::UidPlus])
+            .is_ok());
+    }
+
     #[test]
     fn test_api_encoder_usage() {
         let cmd = Command::new(
@@ -2022,6 +2528,36 @@ mod tests {
                 ]
                 .as_ref(),
             ),
+            (
+                Command::new("A", CommandBody::login("alice smith", "pass word").unwrap()).unwrap(),
+                [Fragment::Line {
+                    data: b"A LOGIN \"alice smith\" \"pass word\"\r\n".to_vec(),
+                }]
+                .as_ref(),
+            ),
+            (
+                Command::new("A", CommandBody::login("jörg", "pässwörd").unwrap()).unwrap(),
+                [
+                    Fragment::Line {
+                        data: b"A LOGIN {5}\r\n".to_vec(),
+                    },
+                    Fragment::Literal {
+                        data: "jörg".as_bytes().to_vec(),
+                        mode: LiteralMode::Sync,
+                    },
+                    Fragment::Line {
+                        data: b" {10}\r\n".to_vec(),
+                    },
+                    Fragment::Literal {
+                        data: "pässwörd".as_bytes().to_vec(),
+                        mode: LiteralMode::Sync,
+                    },
+                    Fragment::Line {
+                        data: b"\r\n".to_vec(),
+                    },
+                ]
+                .as_ref(),
+            ),
             (
                 Command::new("A", CommandBody::authenticate(AuthMechanism::Login)).unwrap(),
                 [Fragment::Line {
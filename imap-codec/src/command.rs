@@ -18,11 +18,13 @@ use imap_types::{
 use nom::{
     branch::alt,
     bytes::streaming::{tag, tag_no_case},
-    combinator::{map, opt, value},
+    combinator::{map, opt, value, verify},
     multi::{separated_list0, separated_list1},
     sequence::{delimited, preceded, terminated, tuple},
 };
 
+#[cfg(feature = "ext_acl")]
+use crate::extensions::acl::acl;
 #[cfg(feature = "ext_binary")]
 use crate::extensions::binary::literal8;
 #[cfg(feature = "ext_id")]
@@ -31,6 +33,8 @@ use crate::extensions::id::id;
 use crate::extensions::metadata::{getmetadata, setmetadata};
 #[cfg(feature = "ext_uidplus")]
 use crate::extensions::uidplus::uid_expunge;
+#[cfg(feature = "legacy")]
+use crate::extensions::xlist::xlist;
 #[cfg(feature = "ext_sort_thread")]
 use crate::extensions::{sort::sort, thread::thread};
 use crate::{
@@ -132,7 +136,8 @@ pub(crate) fn command_any(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
 ///                getquotaroot / ; RFC 9208
 ///                setquota /     ; RFC 9208
 ///                setmetadata /  ; RFC 5464
-///                getmetadata    ; RFC 5464
+///                getmetadata /  ; RFC 5464
+///                acl            ; RFC 4314
 /// ```
 ///
 /// Note: Valid only in Authenticated or Selected state
@@ -159,6 +164,10 @@ pub(crate) fn command_auth(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
         setmetadata,
         #[cfg(feature = "ext_metadata")]
         getmetadata,
+        #[cfg(feature = "ext_acl")]
+        acl,
+        #[cfg(feature = "legacy")]
+        xlist,
     ))(input)
 }
 
@@ -539,7 +548,11 @@ pub(crate) fn store_att_flags(
             }),
         )),
         sp,
-        alt((flag_list, separated_list1(sp, flag))),
+        // Note: `\Recent` can't be altered by the client.
+        verify(
+            alt((flag_list, separated_list1(sp, flag))),
+            |flags: &Vec<Flag>| !flags.iter().any(Flag::is_recent),
+        ),
     ));
 
     let (remaining, ((store_type, _, store_response), _, flag_list)) = parser(input)?;
@@ -576,12 +589,16 @@ mod tests {
     use std::num::NonZeroU32;
 
     use imap_types::{
-        core::Tag,
-        fetch::{MessageDataItemName, Section},
+        core::{Tag, Vec1},
+        fetch::{Macro, MessageDataItemName, Section},
+        flag::{Flag, StoreResponse, StoreType},
+        mailbox::{Mailbox, MailboxNameEncoding},
+        search::SearchKey,
+        sequence::{Seq, SeqSequenceSet, Uid, UidSequenceSet},
     };
 
     use super::*;
-    use crate::{encode::Encoder, CommandCodec};
+    use crate::{decode::Decoder, encode::Encoder, testing::kat_inverse_command, CommandCodec};
 
     #[test]
     fn test_parse_fetch() {
@@ -660,4 +677,268 @@ mod tests {
 
         assert_eq!(buffer, b"A AUTHENTICATE PLAIN =\r\n")
     }
+
+    #[test]
+    fn test_kat_inverse_command_noop_check_logout() {
+        kat_inverse_command(&[
+            (
+                b"A1 NOOP\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new("A1", CommandBody::Noop).unwrap(),
+            ),
+            (
+                b"A1 CHECK\r\n",
+                b"",
+                Command::new("A1", CommandBody::Check).unwrap(),
+            ),
+            (
+                b"A1 LOGOUT\r\n",
+                b"",
+                Command::new("A1", CommandBody::Logout).unwrap(),
+            ),
+            (
+                b"A1 logout\r\nA2 NOOP\r\n",
+                b"A2 NOOP\r\n",
+                Command::new("A1", CommandBody::Logout).unwrap(),
+            ),
+        ]);
+    }
+
+    #[cfg(feature = "starttls")]
+    #[test]
+    fn test_kat_inverse_command_starttls() {
+        kat_inverse_command(&[
+            (
+                b"A1 STARTTLS\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new("A1", CommandBody::StartTLS).unwrap(),
+            ),
+            (
+                b"A1 starttls\r\n",
+                b"",
+                Command::new("A1", CommandBody::StartTLS).unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_command_close_expunge() {
+        kat_inverse_command(&[
+            (
+                b"A1 CLOSE\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new("A1", CommandBody::Close).unwrap(),
+            ),
+            (
+                b"A1 EXPUNGE\r\n",
+                b"",
+                Command::new("A1", CommandBody::Expunge).unwrap(),
+            ),
+        ]);
+
+        // EXPUNGE takes no arguments. Only UID EXPUNGE (UIDPLUS) takes a sequence set.
+        assert!(CommandCodec::default()
+            .decode(b"A1 EXPUNGE 1:3\r\n")
+            .is_err());
+    }
+
+    #[test]
+    fn test_kat_inverse_command_subscribe_unsubscribe() {
+        kat_inverse_command(&[
+            (
+                b"A1 SUBSCRIBE box\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new("A1", CommandBody::subscribe("box").unwrap()).unwrap(),
+            ),
+            (
+                b"A1 UNSUBSCRIBE box\r\n",
+                b"",
+                Command::new("A1", CommandBody::unsubscribe("box").unwrap()).unwrap(),
+            ),
+            (
+                b"A1 SUBSCRIBE \"Sent Items\"\r\n",
+                b"",
+                Command::new("A1", CommandBody::subscribe("Sent Items").unwrap()).unwrap(),
+            ),
+            (
+                "A1 UNSUBSCRIBE {9}\r\nEntwürfe\r\n".as_bytes(),
+                b"",
+                Command::new(
+                    "A1",
+                    CommandBody::unsubscribe(
+                        Mailbox::from_unicode_with("Entwürfe", MailboxNameEncoding::Utf8).unwrap(),
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A1 SUBSCRIBE Entw&APw-rfe\r\n",
+                b"",
+                Command::new(
+                    "A1",
+                    CommandBody::subscribe(Mailbox::from_unicode("Entwürfe")).unwrap(),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_command_create_delete_rename() {
+        kat_inverse_command(&[
+            (
+                b"A1 CREATE Archive\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new("A1", CommandBody::create("Archive").unwrap()).unwrap(),
+            ),
+            (
+                b"A1 DELETE \"Old Stuff\"\r\n",
+                b"",
+                Command::new("A1", CommandBody::delete("Old Stuff").unwrap()).unwrap(),
+            ),
+            (
+                b"A1 CREATE Entw&APw-rfe\r\n",
+                b"",
+                Command::new(
+                    "A1",
+                    CommandBody::create(Mailbox::from_unicode("Entwürfe")).unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                "A1 DELETE {9}\r\nEntwürfe\r\n".as_bytes(),
+                b"",
+                Command::new(
+                    "A1",
+                    CommandBody::delete(
+                        Mailbox::from_unicode_with("Entwürfe", MailboxNameEncoding::Utf8).unwrap(),
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A1 RENAME &ZeVnLIqe- \"&ZeVnLIqe- Old\"\r\n",
+                b"",
+                Command::new(
+                    "A1",
+                    CommandBody::rename(
+                        Mailbox::from_unicode("日本語"),
+                        Mailbox::from_unicode("日本語 Old"),
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            // Renaming INBOX is special on the server side but is sent as is.
+            (
+                b"A1 RENAME inbox Archive\r\n",
+                b"",
+                Command::new(
+                    "A1",
+                    CommandBody::rename(Mailbox::Inbox, "Archive").unwrap(),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_encode_rename_inbox() {
+        let command = Command::new(
+            "A1",
+            CommandBody::rename(Mailbox::Inbox, "Old-Inbox").unwrap(),
+        )
+        .unwrap();
+
+        let got = CommandCodec::default().encode(&command).dump();
+        assert_eq!(b"A1 RENAME INBOX Old-Inbox\r\n".as_ref(), got);
+    }
+
+    #[test]
+    fn test_encode_select_examine() {
+        let tests = [
+            (
+                CommandBody::select("INBOX").unwrap(),
+                b"A1 SELECT INBOX\r\n".as_ref(),
+            ),
+            (
+                CommandBody::examine("inbox").unwrap(),
+                b"A1 EXAMINE INBOX\r\n",
+            ),
+            (
+                CommandBody::select("Sent Items").unwrap(),
+                b"A1 SELECT \"Sent Items\"\r\n",
+            ),
+        ];
+
+        for (body, expected) in tests {
+            let command = Command::new("A1", body).unwrap();
+            let got = CommandCodec::default().encode(&command).dump();
+            assert_eq!(expected, got);
+        }
+    }
+
+    #[test]
+    fn test_encode_typed_sequence_set() {
+        let tests = [
+            (
+                CommandBody::fetch_typed(SeqSequenceSet::try_from("1:*").unwrap(), Macro::Fast),
+                b"A FETCH 1:* FAST\r\n".as_ref(),
+            ),
+            (
+                CommandBody::fetch_typed(UidSequenceSet::try_from("1:*").unwrap(), Macro::Fast),
+                b"A UID FETCH 1:* FAST\r\n".as_ref(),
+            ),
+            (
+                CommandBody::store_typed(
+                    SeqSequenceSet::try_from("1,3").unwrap(),
+                    StoreType::Add,
+                    StoreResponse::Silent,
+                    vec![Flag::Seen],
+                )
+                .unwrap(),
+                b"A STORE 1,3 +FLAGS.SILENT (\\Seen)\r\n",
+            ),
+            (
+                CommandBody::store_typed(
+                    UidSequenceSet::try_from("1,3").unwrap(),
+                    StoreType::Add,
+                    StoreResponse::Silent,
+                    vec![Flag::Seen],
+                )
+                .unwrap(),
+                b"A UID STORE 1,3 +FLAGS.SILENT (\\Seen)\r\n",
+            ),
+            (
+                CommandBody::copy_typed(SeqSequenceSet::try_from("5").unwrap(), "Archive").unwrap(),
+                b"A COPY 5 Archive\r\n",
+            ),
+            (
+                CommandBody::copy_typed(UidSequenceSet::try_from("5").unwrap(), "Archive").unwrap(),
+                b"A UID COPY 5 Archive\r\n",
+            ),
+            (
+                CommandBody::search_typed::<Seq>(
+                    None,
+                    Vec1::from(SearchKey::from(UidSequenceSet::try_from("1:5").unwrap())),
+                ),
+                b"A SEARCH UID 1:5\r\n",
+            ),
+            (
+                CommandBody::search_typed::<Uid>(
+                    None,
+                    Vec1::from(SearchKey::from(SeqSequenceSet::try_from("1:5").unwrap())),
+                ),
+                b"A UID SEARCH 1:5\r\n",
+            ),
+        ];
+
+        for (body, expected) in tests {
+            let command = Command::new("A", body).unwrap();
+            let got = CommandCodec::default().encode(&command).dump();
+            assert_eq!(expected, got);
+        }
+    }
 }
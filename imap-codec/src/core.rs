@@ -251,14 +251,30 @@ pub(crate) fn text(input: &[u8]) -> IMAPResult<&[u8], Text> {
 // ----- base64 -----
 
 /// `base64 = *(4base64-char) [base64-terminal]`
+///
+/// Note: The length and padding are validated before anything is allocated.
 pub(crate) fn base64(input: &[u8]) -> IMAPResult<&[u8], Vec<u8>> {
-    map_res(
-        recognize(tuple((
-            take_while(is_base64_char),
-            opt(alt((tag("=="), tag("=")))),
-        ))),
-        |input| _base64.decode(input),
-    )(input)
+    let (remaining, encoded) = recognize(tuple((
+        take_while(is_base64_char),
+        opt(alt((tag("=="), tag("=")))),
+    )))(input)?;
+
+    // Padding can only occur in the terminal. Thus, a wrong length means that the input was
+    // truncated or that `=` was used somewhere in the middle.
+    if encoded.len() % 4 != 0 {
+        return Err(nom::Err::Error(IMAPParseError {
+            input,
+            kind: IMAPErrorKind::BadBase64,
+        }));
+    }
+
+    match _base64.decode(encoded) {
+        Ok(decoded) => Ok((remaining, decoded)),
+        Err(_) => Err(nom::Err::Error(IMAPParseError {
+            input,
+            kind: IMAPErrorKind::BadBase64,
+        })),
+    }
 }
 
 /// `base64-char = ALPHA / DIGIT / "+" / "/" ; Case-sensitive`
@@ -325,11 +341,13 @@ mod tests {
 
         let (rem, val) = quoted(br#""Hello \"World\""???"#).unwrap();
         assert_eq!(rem, br#"???"#);
-        // Should it be this (Hello \"World\") ...
-        //assert_eq!(val, r#"Hello \"World\""#);
-        // ... or this (Hello "World")?
+        // Escapes are removed, i.e., `Hello \"World\"` becomes `Hello "World"`.
         assert_eq!(val, Quoted::try_from("Hello \"World\"").unwrap());
 
+        let (rem, val) = quoted(br#""\\\"\\"???"#).unwrap();
+        assert_eq!(rem, br#"???"#);
+        assert_eq!(val, Quoted::try_from(r#"\"\"#).unwrap());
+
         // Test Incomplete
         assert!(matches!(quoted(br#""#), Err(nom::Err::Incomplete(_))));
         assert!(matches!(quoted(br#""\"#), Err(nom::Err::Incomplete(_))));
@@ -342,6 +360,29 @@ mod tests {
         assert!(matches!(quoted(br#"\"#), Err(nom::Err::Error(_))));
     }
 
+    #[test]
+    fn test_quoted_rejects_control_chars() {
+        let tests = [
+            b"\"foo\r\nbar\"???".as_ref(),
+            b"\"foo\rbar\"???".as_ref(),
+            b"\"foo\nbar\"???".as_ref(),
+            b"\"a\x00b\"???".as_ref(),
+            // Only `"` and `\` may be escaped.
+            b"\"foo\\\r\nbar\"???".as_ref(),
+            b"\"foo\\\nbar\"???".as_ref(),
+            b"\"a\\\x00b\"???".as_ref(),
+        ];
+
+        for test in tests {
+            assert!(matches!(quoted(test), Err(nom::Err::Error(_))));
+            assert!(matches!(string(test), Err(nom::Err::Error(_))));
+            assert!(matches!(astring(test), Err(nom::Err::Error(_))));
+        }
+
+        assert!(Quoted::try_from("foo\r\nbar").is_err());
+        assert!(Quoted::try_from("a\x00b").is_err());
+    }
+
     #[test]
     fn test_quoted_char() {
         let (rem, val) = quoted_char(b"\\\"xxx").unwrap();
@@ -418,6 +459,55 @@ mod tests {
         assert!(Charset::try_from("\x00").is_err());
     }
 
+    #[test]
+    fn test_encode_string_from_bytes() {
+        let tests = [
+            (b"".as_ref(), b"{0}\r\n".as_ref()),
+            (b"hi", b"\"hi\""),
+            (b"a\"b", b"\"a\\\"b\""),
+            (b"a\nb", b"{3}\r\na\nb"),
+        ];
+
+        for (test, expected) in tests {
+            let mut ctx = EncodeContext::new();
+            IString::from_bytes(test)
+                .unwrap()
+                .encode_ctx(&mut ctx)
+                .unwrap();
+
+            assert_eq!(expected, ctx.dump());
+        }
+    }
+
+    #[test]
+    fn test_encode_string_round_trip() {
+        let tests = [
+            ("alice", r#""alice""#.as_bytes()),
+            (r#"Hello "World""#, r#""Hello \"World\"""#.as_bytes()),
+            ("\\", r#""\\""#.as_bytes()),
+            (r#"\""#, r#""\\\"""#.as_bytes()),
+            ("a\r\nb", b"{4}\r\na\r\nb".as_ref()),
+            ("\"\n", b"{2}\r\n\"\n".as_ref()),
+            ("ü", "{2}\r\nü".as_bytes()),
+        ];
+
+        for (test, expected) in tests {
+            let istr = IString::try_from(test).unwrap();
+
+            let mut ctx = EncodeContext::new();
+            istr.encode_ctx(&mut ctx).unwrap();
+            let out = ctx.dump();
+            assert_eq!(expected, out);
+
+            // Append something to make streaming parsers happy.
+            let out = [out.as_slice(), b"?"].concat();
+            let (rem, got) = string(&out).unwrap();
+            assert_eq!(rem, b"?");
+            assert_eq!(istr, got);
+            assert_eq!(test.as_bytes(), got.as_ref());
+        }
+    }
+
     #[test]
     fn test_is_base64_char() {
         assert!(is_base64_char(b'a'));
@@ -433,4 +523,49 @@ mod tests {
         //_base64.decode(b"aa==").unwrap();
         _base64.decode(b"aQ==").unwrap();
     }
+
+    #[test]
+    fn test_parse_base64() {
+        let tests = [
+            (b"\r\n".as_ref(), b"".as_ref()),
+            (b"AA==\r\n", b"\x00"),
+            (b"AAA=\r\n", b"\x00\x00"),
+            (b"AAAA\r\n", b"\x00\x00\x00"),
+            (b"aGVsbG8=\r\n", b"hello"),
+            (b"dGVzdA==\r\n", b"test"),
+        ];
+
+        for (test, expected) in tests {
+            let (rem, got) = base64(test).unwrap();
+            assert_eq!(rem, b"\r\n");
+            assert_eq!(got, expected);
+        }
+
+        let tests = [
+            // Invalid padding
+            b"A===\r\n".as_ref(),
+            b"AA=A\r\n",
+            b"A=AA\r\n",
+            b"=AAA\r\n",
+            b"aa==\r\n",
+            // Truncated
+            b"A\r\n",
+            b"AA\r\n",
+            b"AAA\r\n",
+            b"AAAAA\r\n",
+            b"AA=\r\n",
+        ];
+
+        for test in tests {
+            assert!(matches!(
+                base64(test),
+                Err(nom::Err::Error(IMAPParseError {
+                    kind: IMAPErrorKind::BadBase64,
+                    ..
+                }))
+            ));
+        }
+
+        assert!(matches!(base64(b"AAAA"), Err(nom::Err::Incomplete(_))));
+    }
 }
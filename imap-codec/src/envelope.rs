@@ -259,4 +259,40 @@ mod tests {
         );
         assert_eq!(rem, b"");
     }
+
+    #[test]
+    fn test_parse_address_malformed() {
+        // Truncated input needs more data ...
+        for test in [
+            b"(".as_ref(),
+            b"(nil nil",
+            b"(nil nil \"xxx\" ",
+            b"(nil nil \"xxx\" nil",
+            b"(nil {3}\r\nxx",
+        ] {
+            assert!(matches!(address(test), Err(nom::Err::Incomplete(_))));
+        }
+
+        // ... while a wrong shape is an error.
+        for test in [
+            b"()".as_ref(),
+            b"(nil)",
+            b"(nil nil nil)",
+            b"(nil nil nil nil nil)",
+            b"(nil nil \"xxx\" \"xxx\" \"xxx\")",
+            b"(nil  nil nil nil)",
+            b"(nil nil xxx nil)",
+            b"(\"xxx\" nil nil ())",
+            b"nil",
+        ] {
+            assert!(matches!(address(test), Err(nom::Err::Error(_))));
+        }
+
+        // An address list must not be empty.
+        assert!(matches!(env_from(b"() "), Err(nom::Err::Error(_))));
+        assert!(matches!(
+            env_from(b"((nil nil nil nil) (nil nil nil)) "),
+            Err(nom::Err::Error(_))
+        ));
+    }
 }
@@ -1,7 +1,11 @@
+#[cfg(feature = "ext_acl")]
+pub mod acl;
 #[cfg(feature = "ext_binary")]
 pub mod binary;
 pub mod compress;
 pub mod enable;
+#[cfg(feature = "ext_esearch")]
+pub mod esearch;
 #[cfg(feature = "ext_id")]
 pub mod id;
 pub mod idle;
@@ -10,6 +14,8 @@ pub mod literal;
 pub mod metadata;
 pub mod r#move;
 pub mod quota;
+#[cfg(feature = "ext_searchres")]
+pub mod searchres;
 #[cfg(feature = "ext_sort_thread")]
 pub mod sort;
 #[cfg(feature = "ext_sort_thread")]
@@ -17,3 +23,5 @@ pub mod thread;
 #[cfg(feature = "ext_uidplus")]
 pub mod uidplus;
 pub mod unselect;
+#[cfg(feature = "legacy")]
+pub mod xlist;
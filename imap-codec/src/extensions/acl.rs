@@ -0,0 +1,295 @@
+//! IMAP4 Access Control List (ACL) Extension
+
+use std::io::Write;
+
+use abnf_core::streaming::sp;
+use imap_types::{
+    command::CommandBody,
+    core::AString,
+    extensions::acl::{ModRights, Rights},
+};
+use nom::{
+    branch::alt,
+    bytes::streaming::tag_no_case,
+    combinator::map_opt,
+    sequence::{preceded, tuple},
+};
+
+use crate::{
+    core::astring,
+    decode::IMAPResult,
+    encode::{EncodeContext, EncodeIntoContext},
+    mailbox::mailbox,
+};
+
+/// ```abnf
+/// acl = setacl / deleteacl / getacl / listrights / myrights
+/// ```
+///
+/// Note: This is not part of the RFC 4314 grammar. It merely groups the ACL commands.
+pub(crate) fn acl(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    alt((setacl, deleteacl, getacl, listrights, myrights))(input)
+}
+
+/// ```abnf
+/// identifier = astring
+/// ```
+#[inline]
+pub(crate) fn identifier(input: &[u8]) -> IMAPResult<&[u8], AString> {
+    astring(input)
+}
+
+/// ```abnf
+/// mod-rights = astring
+///              ;; +rights to add, -rights to remove
+///              ;; rights to replace
+/// ```
+pub(crate) fn mod_rights(input: &[u8]) -> IMAPResult<&[u8], ModRights> {
+    map_opt(astring, |astring| {
+        ModRights::try_from(String::from_utf8(astring.as_ref().to_vec()).ok()?).ok()
+    })(input)
+}
+
+/// ```abnf
+/// setacl = "SETACL" SP mailbox SP identifier SP mod-rights
+/// ```
+pub(crate) fn setacl(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = tuple((
+        tag_no_case("SETACL "),
+        mailbox,
+        sp,
+        identifier,
+        sp,
+        mod_rights,
+    ));
+
+    let (remaining, (_, mailbox, _, identifier, _, mod_rights)) = parser(input)?;
+
+    Ok((
+        remaining,
+        CommandBody::SetAcl {
+            mailbox,
+            identifier,
+            mod_rights,
+        },
+    ))
+}
+
+/// ```abnf
+/// deleteacl = "DELETEACL" SP mailbox SP identifier
+/// ```
+pub(crate) fn deleteacl(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = tuple((tag_no_case("DELETEACL "), mailbox, sp, identifier));
+
+    let (remaining, (_, mailbox, _, identifier)) = parser(input)?;
+
+    Ok((
+        remaining,
+        CommandBody::DeleteAcl {
+            mailbox,
+            identifier,
+        },
+    ))
+}
+
+/// ```abnf
+/// getacl = "GETACL" SP mailbox
+/// ```
+pub(crate) fn getacl(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = preceded(tag_no_case("GETACL "), mailbox);
+
+    let (remaining, mailbox) = parser(input)?;
+
+    Ok((remaining, CommandBody::GetAcl { mailbox }))
+}
+
+/// ```abnf
+/// listrights = "LISTRIGHTS" SP mailbox SP identifier
+/// ```
+pub(crate) fn listrights(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = tuple((tag_no_case("LISTRIGHTS "), mailbox, sp, identifier));
+
+    let (remaining, (_, mailbox, _, identifier)) = parser(input)?;
+
+    Ok((
+        remaining,
+        CommandBody::ListRights {
+            mailbox,
+            identifier,
+        },
+    ))
+}
+
+/// ```abnf
+/// myrights = "MYRIGHTS" SP mailbox
+/// ```
+pub(crate) fn myrights(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = preceded(tag_no_case("MYRIGHTS "), mailbox);
+
+    let (remaining, mailbox) = parser(input)?;
+
+    Ok((remaining, CommandBody::MyRights { mailbox }))
+}
+
+impl<'a> EncodeIntoContext for Rights<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        // Note: Empty rights are not an atom.
+        if self.inner().is_empty() {
+            ctx.write_all(b"\"\"")
+        } else {
+            ctx.write_all(self.inner().as_bytes())
+        }
+    }
+}
+
+impl<'a> EncodeIntoContext for ModRights<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        match self {
+            ModRights::Add(rights) => write!(ctx, "+{rights}"),
+            ModRights::Remove(rights) => write!(ctx, "-{rights}"),
+            ModRights::Replace(rights) => rights.encode_ctx(ctx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imap_types::{
+        command::{Command, CommandBody},
+        core::AString,
+        extensions::acl::{ModRights, Rights},
+        mailbox::Mailbox,
+        response::Capability,
+    };
+
+    use super::*;
+    use crate::testing::kat_inverse_command;
+
+    #[test]
+    fn test_parse_mod_rights() {
+        let tests = [
+            (
+                b"+lrs ".as_ref(),
+                ModRights::Add(Rights::try_from("lrs").unwrap()),
+            ),
+            (b"-w ", ModRights::Remove(Rights::try_from("w").unwrap())),
+            (
+                b"lrswi ",
+                ModRights::Replace(Rights::try_from("lrswi").unwrap()),
+            ),
+            (
+                b"\"lr\" ",
+                ModRights::Replace(Rights::try_from("lr").unwrap()),
+            ),
+            (b"\"\" ", ModRights::Replace(Rights::try_from("").unwrap())),
+        ];
+
+        for (test, expected) in tests {
+            let (rem, got) = mod_rights(test).unwrap();
+            assert_eq!(rem, b" ");
+            assert_eq!(got, expected);
+        }
+
+        assert!(mod_rights(b"LRS ").is_err());
+        assert!(mod_rights(b"+-l ").is_err());
+    }
+
+    #[test]
+    fn test_kat_inverse_command_acl() {
+        kat_inverse_command(&[
+            (
+                b"A1 SETACL INBOX alice +lrs\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A1",
+                    CommandBody::SetAcl {
+                        mailbox: Mailbox::Inbox,
+                        identifier: AString::try_from("alice").unwrap(),
+                        mod_rights: ModRights::Add(Rights::try_from("lrs").unwrap()),
+                    },
+                )
+                .unwrap(),
+            ),
+            (
+                b"A1 SETACL INBOX anyone -w\r\n",
+                b"",
+                Command::new(
+                    "A1",
+                    CommandBody::SetAcl {
+                        mailbox: Mailbox::Inbox,
+                        identifier: AString::try_from("anyone").unwrap(),
+                        mod_rights: ModRights::Remove(Rights::try_from("w").unwrap()),
+                    },
+                )
+                .unwrap(),
+            ),
+            (
+                b"A1 SETACL Shared \"bob smith\" \"\"\r\n",
+                b"",
+                Command::new(
+                    "A1",
+                    CommandBody::SetAcl {
+                        mailbox: Mailbox::try_from("Shared").unwrap(),
+                        identifier: AString::try_from("bob smith").unwrap(),
+                        mod_rights: ModRights::Replace(Rights::try_from("").unwrap()),
+                    },
+                )
+                .unwrap(),
+            ),
+            (
+                b"A1 DELETEACL INBOX alice\r\n",
+                b"",
+                Command::new(
+                    "A1",
+                    CommandBody::DeleteAcl {
+                        mailbox: Mailbox::Inbox,
+                        identifier: AString::try_from("alice").unwrap(),
+                    },
+                )
+                .unwrap(),
+            ),
+            (
+                b"A1 GETACL INBOX\r\n",
+                b"",
+                Command::new(
+                    "A1",
+                    CommandBody::GetAcl {
+                        mailbox: Mailbox::Inbox,
+                    },
+                )
+                .unwrap(),
+            ),
+            (
+                b"A1 LISTRIGHTS INBOX alice\r\n",
+                b"",
+                Command::new(
+                    "A1",
+                    CommandBody::ListRights {
+                        mailbox: Mailbox::Inbox,
+                        identifier: AString::try_from("alice").unwrap(),
+                    },
+                )
+                .unwrap(),
+            ),
+            (
+                b"A1 MYRIGHTS INBOX\r\n",
+                b"",
+                Command::new(
+                    "A1",
+                    CommandBody::MyRights {
+                        mailbox: Mailbox::Inbox,
+                    },
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_capability_acl() {
+        assert_eq!(
+            Capability::from(imap_types::core::Atom::try_from("acl").unwrap()),
+            Capability::Acl
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! IMAP4 Extension for Returning SEARCH Results in Extended Format (ESEARCH)
+
+// Additional changes:
+//
+// search = "SEARCH" [search-return-opts] [SP "CHARSET" SP charset] 1*(SP search-key)
+//                   ^^^^^^^^^^^^^^^^^^^^
+
+use abnf_core::streaming::sp;
+use imap_types::extensions::esearch::SearchReturnOption;
+use nom::{
+    branch::alt,
+    bytes::streaming::{tag, tag_no_case},
+    combinator::{map, opt, value},
+    multi::separated_list0,
+    sequence::delimited,
+};
+
+use crate::decode::IMAPResult;
+
+/// ```abnf
+/// search-return-opts = SP "RETURN" SP "(" [search-return-opt *(SP search-return-opt)] ")"
+/// ```
+///
+/// Note: This parser is optional and returns an empty list when `RETURN` is missing.
+pub(crate) fn search_return_opts(input: &[u8]) -> IMAPResult<&[u8], Vec<SearchReturnOption>> {
+    map(
+        opt(delimited(
+            tag_no_case(b" RETURN ("),
+            separated_list0(sp, search_return_opt),
+            tag(b")"),
+        )),
+        |return_options| match return_options {
+            // `RETURN ()` is equivalent to `RETURN (ALL)`.
+            Some(return_options) if return_options.is_empty() => vec![SearchReturnOption::All],
+            Some(return_options) => return_options,
+            None => vec![],
+        },
+    )(input)
+}
+
+/// ```abnf
+/// search-return-opt = "MIN" / "MAX" / "ALL" / "COUNT"
+/// ```
+pub(crate) fn search_return_opt(input: &[u8]) -> IMAPResult<&[u8], SearchReturnOption> {
+    alt((
+        value(SearchReturnOption::Min, tag_no_case(b"MIN")),
+        value(SearchReturnOption::Max, tag_no_case(b"MAX")),
+        value(SearchReturnOption::All, tag_no_case(b"ALL")),
+        value(SearchReturnOption::Count, tag_no_case(b"COUNT")),
+    ))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use imap_types::{
+        command::{Command, CommandBody},
+        core::{AString, Charset, Vec1},
+        search::SearchKey,
+    };
+
+    use super::*;
+    use crate::{search::search, testing::kat_inverse_command};
+
+    #[test]
+    fn test_kat_inverse_command_search_return_options() {
+        kat_inverse_command(&[
+            (
+                b"A1 UID SEARCH RETURN (MIN MAX) FROM alice\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A1",
+                    CommandBody::esearch(
+                        None,
+                        Vec1::from(SearchKey::From(AString::try_from("alice").unwrap())),
+                        vec![SearchReturnOption::Min, SearchReturnOption::Max],
+                        true,
+                    ),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A1 SEARCH RETURN (COUNT ALL) CHARSET UTF-8 SUBJECT x\r\n",
+                b"",
+                Command::new(
+                    "A1",
+                    CommandBody::esearch(
+                        Some(Charset::try_from("UTF-8").unwrap()),
+                        Vec1::from(SearchKey::Subject(AString::try_from("x").unwrap())),
+                        vec![SearchReturnOption::Count, SearchReturnOption::All],
+                        false,
+                    ),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A1 SEARCH CHARSET UTF-8 FROM alice\r\n",
+                b"",
+                Command::new(
+                    "A1",
+                    CommandBody::search(
+                        Some(Charset::try_from("UTF-8").unwrap()),
+                        Vec1::from(SearchKey::From(AString::try_from("alice").unwrap())),
+                        false,
+                    ),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A1 UID SEARCH FROM alice\r\n",
+                b"",
+                Command::new(
+                    "A1",
+                    CommandBody::search(
+                        None,
+                        Vec1::from(SearchKey::From(AString::try_from("alice").unwrap())),
+                        true,
+                    ),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_search_return_empty() {
+        // `RETURN ()` is equivalent to `RETURN (ALL)`.
+        let (rem, got) = search(b"SEARCH RETURN () ALL\r\n").unwrap();
+        assert_eq!(rem, b"\r\n");
+        assert_eq!(
+            got,
+            CommandBody::esearch(
+                None,
+                Vec1::from(SearchKey::All),
+                vec![SearchReturnOption::All],
+                false
+            )
+        );
+    }
+}
@@ -494,6 +494,58 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_kat_inverse_command_metadata_multiple() {
+        kat_inverse_command(&[
+            (
+                b"A GETMETADATA (MAXSIZE 1024 DEPTH infinity) INBOX (/shared/comment /private/comment)\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::GetMetadata {
+                        options: vec![
+                            GetMetadataOption::MaxSize(1024),
+                            GetMetadataOption::Depth(Depth::Infinity),
+                        ],
+                        mailbox: Mailbox::Inbox,
+                        entries: Vec1::try_from(vec![
+                            Entry::try_from(AString::try_from("/shared/comment").unwrap()).unwrap(),
+                            Entry::try_from(AString::try_from("/private/comment").unwrap()).unwrap(),
+                        ])
+                        .unwrap(),
+                    },
+                )
+                .unwrap(),
+            ),
+            (
+                b"A SETMETADATA INBOX (/shared/comment \"Shared\" /private/comment NIL)\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::SetMetadata {
+                        mailbox: Mailbox::Inbox,
+                        entry_values: Vec1::try_from(vec![
+                            EntryValue {
+                                entry: Entry::try_from(AString::try_from("/shared/comment").unwrap())
+                                    .unwrap(),
+                                value: NString8::NString(NString(Some(
+                                    IString::try_from("Shared").unwrap(),
+                                ))),
+                            },
+                            EntryValue {
+                                entry: Entry::try_from(AString::try_from("/private/comment").unwrap())
+                                    .unwrap(),
+                                value: NString8::NString(NString(None)),
+                            },
+                        ])
+                        .unwrap(),
+                    },
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
     #[test]
     fn test_kat_inverse_response_metadata() {
         kat_inverse_response(&[
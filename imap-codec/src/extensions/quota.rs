@@ -395,6 +395,20 @@ mod tests {
                     .tag("A001")
                     .unwrap(),
             ),
+            (
+                b"A1 SETQUOTA \"\" (STORAGE 512 MESSAGE 1000)\r\n",
+                b"",
+                CommandBody::set_quota(
+                    "",
+                    vec![
+                        QuotaSet::new(Resource::Storage, 512),
+                        QuotaSet::new(Resource::Message, 1000),
+                    ],
+                )
+                .unwrap()
+                .tag("A1")
+                .unwrap(),
+            ),
         ]);
     }
 
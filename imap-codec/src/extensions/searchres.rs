@@ -0,0 +1,104 @@
+//! IMAP Extension for Referencing the Last SEARCH Result (SEARCHRES)
+
+// Additional changes:
+//
+// sequence-set =/ seq-last-command
+
+use imap_types::sequence::Sequence;
+use nom::{bytes::streaming::tag, combinator::value};
+
+use crate::decode::IMAPResult;
+
+/// ```abnf
+/// seq-last-command = "$"
+/// ```
+pub(crate) fn seq_last_command(input: &[u8]) -> IMAPResult<&[u8], Sequence> {
+    value(Sequence::SearchResult, tag(b"$"))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use imap_types::{
+        command::{Command, CommandBody},
+        fetch::{Macro, MacroOrMessageDataItemNames},
+        flag::{Flag, StoreResponse, StoreType},
+        search::SearchKey,
+        sequence::SequenceSet,
+    };
+
+    #[cfg(feature = "ext_uidplus")]
+    use crate::extensions::uidplus::uid_set;
+    use crate::{decode::Decoder, testing::kat_inverse_command, CommandCodec};
+
+    #[test]
+    fn test_kat_inverse_command_searchres() {
+        kat_inverse_command(&[
+            (
+                b"A STORE $ +FLAGS (\\Seen)\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::store(
+                        "$",
+                        StoreType::Add,
+                        StoreResponse::Answer,
+                        vec![Flag::Seen],
+                        false,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A UID FETCH 1,$ FAST\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::fetch(
+                        "1,$",
+                        MacroOrMessageDataItemNames::Macro(Macro::Fast),
+                        true,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A UID COPY $ INBOX\r\n",
+                b"",
+                Command::new("A", CommandBody::copy("$", "INBOX", true).unwrap()).unwrap(),
+            ),
+            (
+                b"A SEARCH UID $\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::search(
+                        None,
+                        SearchKey::Uid(SequenceSet::try_from("$").unwrap()).into(),
+                        false,
+                    ),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_searchres_only_where_allowed() {
+        // `$` is not a number and can't be part of a range.
+        for test in [
+            b"A STORE $:5 +FLAGS (\\Seen)\r\n".as_ref(),
+            b"A FETCH 1:$ FAST\r\n",
+        ] {
+            assert!(CommandCodec::default().decode(test).is_err());
+        }
+
+        // A `uid-set`, e.g., in `COPYUID`, must list the UIDs explicitly.
+        #[cfg(feature = "ext_uidplus")]
+        {
+            assert!(uid_set(b"$ ").is_err());
+            assert_eq!(uid_set(b"1,$ ").unwrap().0, b",$ ");
+        }
+    }
+}
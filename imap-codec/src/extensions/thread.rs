@@ -81,15 +81,17 @@ pub(crate) fn thread_alg(input: &[u8]) -> IMAPResult<&[u8], ThreadingAlgorithm>
 /// ```abnf
 /// thread-data = "THREAD" [SP 1*thread-list]
 /// ```
-pub(crate) fn thread_data(input: &[u8]) -> IMAPResult<&[u8], Data> {
-    let mut parser = preceded(
-        tag_no_case("THREAD"),
-        opt(preceded(sp, many1(thread_list(8)))),
-    );
+pub(crate) fn thread_data(max_thread_depth: usize) -> impl Fn(&[u8]) -> IMAPResult<&[u8], Data> {
+    move |input: &[u8]| {
+        let mut parser = preceded(
+            tag_no_case("THREAD"),
+            opt(preceded(sp, many1(thread_list(max_thread_depth)))),
+        );
 
-    let (remaining, thread_list) = parser(input)?;
+        let (remaining, thread_list) = parser(input)?;
 
-    Ok((remaining, Data::Thread(thread_list.unwrap_or_default())))
+        Ok((remaining, Data::Thread(thread_list.unwrap_or_default())))
+    }
 }
 
 pub(crate) fn thread_list(
@@ -152,6 +152,17 @@ mod tests {
                 )
                 .unwrap(),
             ),
+            (
+                b"A1 UID EXPUNGE 1:3\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A1",
+                    CommandBody::ExpungeUid {
+                        sequence_set: "1:3".try_into().unwrap(),
+                    },
+                )
+                .unwrap(),
+            ),
             (
                 b"A UID EXPUNGE 1:1337\r\n?".as_ref(),
                 b"?".as_ref(),
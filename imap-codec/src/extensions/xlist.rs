@@ -0,0 +1,129 @@
+//! Legacy XLIST command and response (e.g., older Gmail servers)
+
+// Additional changes:
+//
+// command-auth =/ xlist
+// response-data = "*" SP (... / xlist-data) CRLF
+
+use abnf_core::streaming::sp;
+use imap_types::{command::CommandBody, response::Data};
+use nom::{bytes::streaming::tag_no_case, sequence::tuple};
+
+use crate::{
+    decode::IMAPResult,
+    mailbox::{list_mailbox, mailbox, mailbox_list},
+};
+
+/// ```abnf
+/// xlist = "XLIST" SP mailbox SP list-mailbox
+/// ```
+pub(crate) fn xlist(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = tuple((tag_no_case(b"XLIST"), sp, mailbox, sp, list_mailbox));
+
+    let (remaining, (_, _, reference, _, mailbox_wildcard)) = parser(input)?;
+
+    Ok((
+        remaining,
+        CommandBody::XList {
+            reference,
+            mailbox_wildcard,
+        },
+    ))
+}
+
+/// ```abnf
+/// xlist-data = "XLIST" SP mailbox-list
+/// ```
+pub(crate) fn xlist_data(input: &[u8]) -> IMAPResult<&[u8], Data> {
+    let mut parser = tuple((tag_no_case(b"XLIST"), sp, mailbox_list));
+
+    let (remaining, (_, _, (items, delimiter, mailbox))) = parser(input)?;
+
+    Ok((
+        remaining,
+        Data::XList {
+            items: items.unwrap_or_default(),
+            delimiter,
+            mailbox,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use imap_types::{
+        command::{Command, CommandBody},
+        core::{Atom, QuotedChar},
+        flag::FlagNameAttribute,
+        mailbox::{ListMailbox, Mailbox},
+        response::{Data, Response},
+    };
+
+    use crate::testing::{kat_inverse_command, kat_inverse_response};
+
+    #[test]
+    fn test_kat_inverse_command_xlist() {
+        kat_inverse_command(&[
+            (
+                b"A XLIST \"\" *\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::XList {
+                        reference: Mailbox::try_from("").unwrap(),
+                        mailbox_wildcard: ListMailbox::try_from("*").unwrap(),
+                    },
+                )
+                .unwrap(),
+            ),
+            (
+                b"A xlist inbox %\r\n?",
+                b"?",
+                Command::new(
+                    "A",
+                    CommandBody::XList {
+                        reference: Mailbox::Inbox,
+                        mailbox_wildcard: ListMailbox::try_from("%").unwrap(),
+                    },
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_xlist() {
+        kat_inverse_response(&[
+            (
+                b"* XLIST (\\Inbox) \"/\" INBOX\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::XList {
+                    items: vec![FlagNameAttribute::from(Atom::try_from("Inbox").unwrap())],
+                    delimiter: Some(QuotedChar::try_from('/').unwrap()),
+                    mailbox: Mailbox::Inbox,
+                }),
+            ),
+            (
+                b"* XLIST (\\HasNoChildren \\AllMail) \"/\" \"[Gmail]/All Mail\"\r\n",
+                b"",
+                Response::Data(Data::XList {
+                    items: vec![
+                        FlagNameAttribute::from(Atom::try_from("HasNoChildren").unwrap()),
+                        FlagNameAttribute::from(Atom::try_from("AllMail").unwrap()),
+                    ],
+                    delimiter: Some(QuotedChar::try_from('/').unwrap()),
+                    mailbox: Mailbox::try_from("[Gmail]/All Mail").unwrap(),
+                }),
+            ),
+            (
+                b"* XLIST () NIL foo\r\n",
+                b"",
+                Response::Data(Data::XList {
+                    items: vec![],
+                    delimiter: None,
+                    mailbox: Mailbox::try_from("foo").unwrap(),
+                }),
+            ),
+        ]);
+    }
+}
@@ -4,26 +4,25 @@ use abnf_core::streaming::sp;
 #[cfg(feature = "ext_binary")]
 use imap_types::core::NString8;
 use imap_types::{
-    core::{AString, Vec1},
-    fetch::{MessageDataItem, MessageDataItemName, Part, PartSpecifier, Section},
+    core::{AString, Atom, Vec1},
+    fetch::{MessageDataItem, MessageDataItemName, MessageDataItemOther, Part, Section},
+    utils::indicators::{is_char, is_ctl},
 };
-#[cfg(feature = "ext_binary")]
-use nom::sequence::preceded;
 use nom::{
     branch::alt,
-    bytes::streaming::{tag, tag_no_case},
-    combinator::{map, opt, value},
+    bytes::streaming::{tag, tag_no_case, take_while1},
+    combinator::{map, opt, recognize, value, verify},
     multi::separated_list1,
-    sequence::{delimited, tuple},
+    sequence::{delimited, preceded, tuple},
 };
 
 #[cfg(feature = "ext_binary")]
 use crate::extensions::binary::{literal8, partial, section_binary};
 use crate::{
     body::body,
-    core::{astring, nstring, number, nz_number},
+    core::{astring, atom, nstring, number, nz_number, string},
     datetime::date_time,
-    decode::IMAPResult,
+    decode::{IMAPErrorKind, IMAPParseError, IMAPResult},
     envelope::envelope,
     flag::flag_fetch,
 };
@@ -123,15 +122,26 @@ pub(crate) fn fetch_att(input: &[u8]) -> IMAPResult<&[u8], MessageDataItemName>
 /// `msg-att = "("
 ///            (msg-att-dynamic / msg-att-static) *(SP (msg-att-dynamic / msg-att-static))
 ///            ")"`
-pub(crate) fn msg_att(input: &[u8]) -> IMAPResult<&[u8], Vec1<MessageDataItem>> {
-    delimited(
-        tag(b"("),
-        map(
-            separated_list1(sp, alt((msg_att_dynamic, msg_att_static))),
-            Vec1::unvalidated,
-        ),
-        tag(b")"),
-    )(input)
+pub(crate) fn msg_att(
+    max_body_depth: usize,
+) -> impl Fn(&[u8]) -> IMAPResult<&[u8], Vec1<MessageDataItem>> {
+    move |input: &[u8]| {
+        delimited(
+            tag(b"("),
+            map(
+                separated_list1(
+                    sp,
+                    alt((
+                        msg_att_dynamic,
+                        msg_att_static(max_body_depth),
+                        msg_att_other,
+                    )),
+                ),
+                Vec1::unvalidated,
+            ),
+            tag(b")"),
+        )(input)
+    }
 }
 
 /// `msg-att-dynamic = "FLAGS" SP "(" [flag-fetch *(SP flag-fetch)] ")"`
@@ -162,76 +172,161 @@ pub(crate) fn msg_att_dynamic(input: &[u8]) -> IMAPResult<&[u8], MessageDataItem
 /// ```
 ///
 /// Note: MUST NOT change for a message
-pub(crate) fn msg_att_static(input: &[u8]) -> IMAPResult<&[u8], MessageDataItem> {
-    alt((
-        map(
-            tuple((tag_no_case(b"ENVELOPE"), sp, envelope)),
-            |(_, _, envelope)| MessageDataItem::Envelope(envelope),
-        ),
-        map(
-            tuple((tag_no_case(b"INTERNALDATE"), sp, date_time)),
-            |(_, _, date_time)| MessageDataItem::InternalDate(date_time),
-        ),
-        map(
-            tuple((tag_no_case(b"RFC822.HEADER"), sp, nstring)),
-            |(_, _, nstring)| MessageDataItem::Rfc822Header(nstring),
-        ),
-        map(
-            tuple((tag_no_case(b"RFC822.TEXT"), sp, nstring)),
-            |(_, _, nstring)| MessageDataItem::Rfc822Text(nstring),
-        ),
-        map(
-            tuple((tag_no_case(b"RFC822.SIZE"), sp, number)),
-            |(_, _, num)| MessageDataItem::Rfc822Size(num),
-        ),
-        map(
-            tuple((tag_no_case(b"RFC822"), sp, nstring)),
-            |(_, _, nstring)| MessageDataItem::Rfc822(nstring),
-        ),
-        map(
-            tuple((tag_no_case(b"BODYSTRUCTURE"), sp, body(8))),
-            |(_, _, body)| MessageDataItem::BodyStructure(body),
-        ),
-        map(
-            tuple((tag_no_case(b"BODY"), sp, body(8))),
-            |(_, _, body)| MessageDataItem::Body(body),
-        ),
-        map(
-            tuple((
-                tag_no_case(b"BODY"),
-                section,
-                opt(delimited(tag(b"<"), number, tag(b">"))),
-                sp,
-                nstring,
-            )),
-            |(_, section, origin, _, data)| MessageDataItem::BodyExt {
-                section,
-                origin,
-                data,
-            },
-        ),
-        map(tuple((tag_no_case(b"UID"), sp, uniqueid)), |(_, _, uid)| {
-            MessageDataItem::Uid(uid)
-        }),
-        #[cfg(feature = "ext_binary")]
-        map(
-            tuple((
-                tag_no_case(b"BINARY"),
-                section_binary,
-                sp,
-                alt((
-                    map(nstring, NString8::NString),
-                    map(literal8, NString8::Literal8),
+pub(crate) fn msg_att_static(
+    max_body_depth: usize,
+) -> impl Fn(&[u8]) -> IMAPResult<&[u8], MessageDataItem> {
+    move |input: &[u8]| {
+        alt((
+            map(
+                tuple((tag_no_case(b"ENVELOPE"), sp, envelope)),
+                |(_, _, envelope)| MessageDataItem::Envelope(envelope),
+            ),
+            map(
+                tuple((tag_no_case(b"INTERNALDATE"), sp, date_time)),
+                |(_, _, date_time)| MessageDataItem::InternalDate(date_time),
+            ),
+            map(
+                tuple((tag_no_case(b"RFC822.HEADER"), sp, nstring)),
+                |(_, _, nstring)| MessageDataItem::Rfc822Header(nstring),
+            ),
+            map(
+                tuple((tag_no_case(b"RFC822.TEXT"), sp, nstring)),
+                |(_, _, nstring)| MessageDataItem::Rfc822Text(nstring),
+            ),
+            map(
+                tuple((tag_no_case(b"RFC822.SIZE"), sp, number)),
+                |(_, _, num)| MessageDataItem::Rfc822Size(num),
+            ),
+            map(
+                tuple((tag_no_case(b"RFC822"), sp, nstring)),
+                |(_, _, nstring)| MessageDataItem::Rfc822(nstring),
+            ),
+            map(
+                tuple((tag_no_case(b"BODYSTRUCTURE"), sp, body(max_body_depth))),
+                |(_, _, body)| MessageDataItem::BodyStructure(body),
+            ),
+            map(
+                tuple((tag_no_case(b"BODY"), sp, body(max_body_depth))),
+                |(_, _, body)| MessageDataItem::Body(body),
+            ),
+            map(
+                tuple((
+                    tag_no_case(b"BODY"),
+                    section,
+                    opt(delimited(tag(b"<"), number, tag(b">"))),
+                    sp,
+                    nstring,
                 )),
-            )),
-            |(_, section, _, value)| MessageDataItem::Binary { section, value },
+                |(_, section, origin, _, data)| MessageDataItem::BodyExt {
+                    section,
+                    origin,
+                    data,
+                },
+            ),
+            map(tuple((tag_no_case(b"UID"), sp, uniqueid)), |(_, _, uid)| {
+                MessageDataItem::Uid(uid)
+            }),
+            #[cfg(feature = "ext_binary")]
+            map(
+                tuple((
+                    tag_no_case(b"BINARY"),
+                    section_binary,
+                    sp,
+                    alt((
+                        map(nstring, NString8::NString),
+                        map(literal8, NString8::Literal8),
+                    )),
+                )),
+                |(_, section, _, value)| MessageDataItem::Binary { section, value },
+            ),
+            #[cfg(feature = "ext_binary")]
+            map(
+                tuple((tag_no_case(b"BINARY.SIZE"), section_binary, sp, number)),
+                |(_, section, _, size)| MessageDataItem::BinarySize { section, size },
+            ),
+        ))(input)
+    }
+}
+
+/// Fallback for unknown message data items, e.g., from vendor extensions.
+///
+/// ```abnf
+/// msg-att-other = atom SP msg-att-other-value
+/// ```
+///
+/// Note: This is not part of the IMAP grammar. The value is not interpreted but only skipped,
+/// honoring parenthesized lists, quoted strings, and literals, to know where the item ends.
+pub(crate) fn msg_att_other(input: &[u8]) -> IMAPResult<&[u8], MessageDataItem> {
+    fn is_known(name: &Atom) -> bool {
+        const KNOWN: &[&str] = &[
+            "BINARY",
+            "BINARY.SIZE",
+            "BODY",
+            "BODYSTRUCTURE",
+            "ENVELOPE",
+            "FLAGS",
+            "INTERNALDATE",
+            "RFC822",
+            "RFC822.HEADER",
+            "RFC822.SIZE",
+            "RFC822.TEXT",
+            "UID",
+        ];
+
+        let name = name.inner();
+
+        // Malformed known items (e.g. `BODY[...]`) must not be parsed as unknown items.
+        name.contains(['[', '<']) || KNOWN.iter().any(|known| known.eq_ignore_ascii_case(name))
+    }
+
+    let mut parser = tuple((verify(atom, |name| !is_known(name)), sp, |input| {
+        msg_att_other_value_limited(input, 8)
+    }));
+
+    let (remaining, (name, _, value)) = parser(input)?;
+
+    Ok((
+        remaining,
+        MessageDataItem::Other(MessageDataItemOther::unvalidated(name, value)),
+    ))
+}
+
+/// ```abnf
+/// msg-att-other-value = "(" [msg-att-other-value *(SP msg-att-other-value)] ")" /
+///                       string /
+///                       1*<any CHAR except "(" / ")" / "{" / SP / CTL / DQUOTE>
+/// ```
+pub(crate) fn msg_att_other_value_limited(
+    input: &[u8],
+    remaining_recursions: usize,
+) -> IMAPResult<&[u8], &[u8]> {
+    if remaining_recursions == 0 {
+        return Err(nom::Err::Failure(IMAPParseError {
+            input,
+            kind: IMAPErrorKind::RecursionLimitExceeded,
+        }));
+    }
+
+    let msg_att_other_value =
+        move |input| msg_att_other_value_limited(input, remaining_recursions.saturating_sub(1));
+
+    recognize(alt((
+        value(
+            (),
+            delimited(
+                tag(b"("),
+                opt(separated_list1(sp, msg_att_other_value)),
+                tag(b")"),
+            ),
         ),
-        #[cfg(feature = "ext_binary")]
-        map(
-            tuple((tag_no_case(b"BINARY.SIZE"), section_binary, sp, number)),
-            |(_, section, _, size)| MessageDataItem::BinarySize { section, size },
+        value((), string),
+        value(
+            (),
+            take_while1(|b| {
+                is_char(b) && !is_ctl(b) && !matches!(b, b'(' | b')' | b'{' | b' ' | b'"')
+            }),
         ),
-    ))(input)
+    )))(input)
 }
 
 #[inline]
@@ -250,53 +345,68 @@ pub(crate) fn section(input: &[u8]) -> IMAPResult<&[u8], Option<Section>> {
 /// `section-spec = section-msgtext / (section-part ["." section-text])`
 pub(crate) fn section_spec(input: &[u8]) -> IMAPResult<&[u8], Section> {
     alt((
-        map(section_msgtext, |part_specifier| match part_specifier {
-            PartSpecifier::PartNumber(_) => unreachable!(),
-            PartSpecifier::Header => Section::Header(None),
-            PartSpecifier::HeaderFields(fields) => Section::HeaderFields(None, fields),
-            PartSpecifier::HeaderFieldsNot(fields) => Section::HeaderFieldsNot(None, fields),
-            PartSpecifier::Text => Section::Text(None),
-            PartSpecifier::Mime => unreachable!(),
-        }),
+        map(section_msgtext, |msgtext| msgtext.into_section(None)),
         map(
-            tuple((section_part, opt(tuple((tag(b"."), section_text))))),
-            |(part_number, maybe_part_specifier)| {
-                if let Some((_, part_specifier)) = maybe_part_specifier {
-                    match part_specifier {
-                        PartSpecifier::PartNumber(_) => unreachable!(),
-                        PartSpecifier::Header => Section::Header(Some(Part(part_number))),
-                        PartSpecifier::HeaderFields(fields) => {
-                            Section::HeaderFields(Some(Part(part_number)), fields)
-                        }
-                        PartSpecifier::HeaderFieldsNot(fields) => {
-                            Section::HeaderFieldsNot(Some(Part(part_number)), fields)
-                        }
-                        PartSpecifier::Text => Section::Text(Some(Part(part_number))),
-                        PartSpecifier::Mime => Section::Mime(Part(part_number)),
-                    }
-                } else {
-                    Section::Part(Part(part_number))
+            tuple((section_part, opt(preceded(tag(b"."), section_text)))),
+            |(part_number, maybe_section_text)| {
+                let part = Part(part_number);
+
+                match maybe_section_text {
+                    Some(SectionText::Msgtext(msgtext)) => msgtext.into_section(Some(part)),
+                    Some(SectionText::Mime) => Section::Mime(part),
+                    None => Section::Part(part),
                 }
             },
         ),
     ))(input)
 }
 
+/// Output of `section-msgtext`.
+///
+/// Note: In contrast to [`PartSpecifier`](imap_types::fetch::PartSpecifier), this can't hold a
+/// part number or `MIME`, i.e., it is valid on the top level.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum SectionMsgtext<'a> {
+    Header,
+    HeaderFields(Vec1<AString<'a>>),
+    HeaderFieldsNot(Vec1<AString<'a>>),
+    Text,
+}
+
+impl<'a> SectionMsgtext<'a> {
+    fn into_section(self, part: Option<Part>) -> Section<'a> {
+        match self {
+            Self::Header => Section::Header(part),
+            Self::HeaderFields(fields) => Section::HeaderFields(part, fields),
+            Self::HeaderFieldsNot(fields) => Section::HeaderFieldsNot(part, fields),
+            Self::Text => Section::Text(part),
+        }
+    }
+}
+
+/// Output of `section-text`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum SectionText<'a> {
+    Msgtext(SectionMsgtext<'a>),
+    /// Only valid after a part number.
+    Mime,
+}
+
 /// `section-msgtext = "HEADER" / "HEADER.FIELDS" [".NOT"] SP header-list / "TEXT"`
 ///
 /// Top-level or MESSAGE/RFC822 part
-pub(crate) fn section_msgtext(input: &[u8]) -> IMAPResult<&[u8], PartSpecifier> {
+pub(crate) fn section_msgtext(input: &[u8]) -> IMAPResult<&[u8], SectionMsgtext> {
     alt((
         map(
             tuple((tag_no_case(b"HEADER.FIELDS.NOT"), sp, header_list)),
-            |(_, _, header_list)| PartSpecifier::HeaderFieldsNot(header_list),
+            |(_, _, header_list)| SectionMsgtext::HeaderFieldsNot(header_list),
         ),
         map(
             tuple((tag_no_case(b"HEADER.FIELDS"), sp, header_list)),
-            |(_, _, header_list)| PartSpecifier::HeaderFields(header_list),
+            |(_, _, header_list)| SectionMsgtext::HeaderFields(header_list),
         ),
-        value(PartSpecifier::Header, tag_no_case(b"HEADER")),
-        value(PartSpecifier::Text, tag_no_case(b"TEXT")),
+        value(SectionMsgtext::Header, tag_no_case(b"HEADER")),
+        value(SectionMsgtext::Text, tag_no_case(b"TEXT")),
     ))(input)
 }
 
@@ -311,10 +421,10 @@ pub(crate) fn section_part(input: &[u8]) -> IMAPResult<&[u8], Vec1<NonZeroU32>>
 /// `section-text = section-msgtext / "MIME"`
 ///
 /// Text other than actual body part (headers, etc.)
-pub(crate) fn section_text(input: &[u8]) -> IMAPResult<&[u8], PartSpecifier> {
+pub(crate) fn section_text(input: &[u8]) -> IMAPResult<&[u8], SectionText> {
     alt((
-        section_msgtext,
-        value(PartSpecifier::Mime, tag_no_case(b"MIME")),
+        map(section_msgtext, SectionText::Msgtext),
+        value(SectionText::Mime, tag_no_case(b"MIME")),
     ))(input)
 }
 
@@ -336,13 +446,287 @@ pub(crate) fn header_fld_name(input: &[u8]) -> IMAPResult<&[u8], AString> {
 mod tests {
     use imap_types::{
         body::{BasicFields, Body, BodyStructure, SpecificFields},
+        command::{Command, CommandBody},
         core::{IString, NString},
         datetime::DateTime,
         envelope::Envelope,
+        flag::{Flag, FlagFetch},
+        response::{Data, Response},
     };
 
     use super::*;
-    use crate::testing::known_answer_test_encode;
+    use crate::{
+        decode::Decoder,
+        testing::{kat_inverse_command, kat_inverse_response, known_answer_test_encode},
+        CommandCodec,
+    };
+
+    #[test]
+    fn test_parse_section_mime() {
+        // `MIME` must be prefixed by a part number ...
+        assert!(matches!(section(b"[MIME] "), Err(nom::Err::Error(_))));
+        assert!(CommandCodec::default()
+            .decode(b"A FETCH 1 BODY.PEEK[MIME]\r\n")
+            .is_err());
+
+        // ... like this.
+        let (rem, got) = section(b"[1.2.MIME] ").unwrap();
+        assert_eq!(rem, b" ");
+        assert_eq!(
+            got,
+            Some(Section::Mime(Part(
+                Vec1::try_from(vec![
+                    NonZeroU32::new(1).unwrap(),
+                    NonZeroU32::new(2).unwrap()
+                ])
+                .unwrap()
+            )))
+        );
+    }
+
+    #[test]
+    fn test_parse_section_combinations() {
+        let part = || {
+            Part(
+                Vec1::try_from(vec![
+                    NonZeroU32::new(1).unwrap(),
+                    NonZeroU32::new(2).unwrap(),
+                ])
+                .unwrap(),
+            )
+        };
+        let fields = || Vec1::from(AString::try_from("FROM").unwrap());
+
+        let tests = [
+            (b"[] ".as_ref(), None),
+            (b"[HEADER] ", Some(Section::Header(None))),
+            (
+                b"[HEADER.FIELDS (FROM)] ",
+                Some(Section::HeaderFields(None, fields())),
+            ),
+            (
+                b"[HEADER.FIELDS.NOT (FROM)] ",
+                Some(Section::HeaderFieldsNot(None, fields())),
+            ),
+            (b"[TEXT] ", Some(Section::Text(None))),
+            (b"[1.2] ", Some(Section::Part(part()))),
+            (b"[1.2.HEADER] ", Some(Section::Header(Some(part())))),
+            (
+                b"[1.2.HEADER.FIELDS (FROM)] ",
+                Some(Section::HeaderFields(Some(part()), fields())),
+            ),
+            (
+                b"[1.2.HEADER.FIELDS.NOT (FROM)] ",
+                Some(Section::HeaderFieldsNot(Some(part()), fields())),
+            ),
+            (b"[1.2.TEXT] ", Some(Section::Text(Some(part())))),
+            (b"[1.2.MIME] ", Some(Section::Mime(part()))),
+        ];
+
+        for (test, expected) in tests {
+            let (rem, got) = section(test).unwrap();
+            assert_eq!(rem, b" ");
+            assert_eq!(got, expected);
+        }
+
+        for test in [b"[MIME] ".as_ref(), b"[1.MIME.TEXT] ", b"[1.2.3.4.FOO] "] {
+            assert!(section(test).is_err());
+        }
+    }
+
+    #[test]
+    fn test_parse_header_list() {
+        let (rem, got) = header_list(b"(FROM) ").unwrap();
+        assert_eq!(rem, b" ");
+        assert_eq!(got, Vec1::from(AString::try_from("FROM").unwrap()));
+
+        let (rem, got) = header_list(b"(FROM \"TO\" {2}\r\nCC) ").unwrap();
+        assert_eq!(rem, b" ");
+        assert_eq!(got.as_ref().len(), 3);
+
+        // Duplicates are kept (see `Section::normalize`).
+        let (_, got) = header_list(b"(FROM from) ").unwrap();
+        assert_eq!(got.as_ref().len(), 2);
+
+        // The list must not be empty.
+        assert!(matches!(header_list(b"() "), Err(nom::Err::Error(_))));
+        assert!(CommandCodec::default()
+            .decode(b"A FETCH 1 BODY[HEADER.FIELDS ()]\r\n")
+            .is_err());
+        assert!(CommandCodec::default()
+            .decode(b"A FETCH 1 BODY[HEADER.FIELDS (FROM TO)]\r\n")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_kat_inverse_command_fetch_peek() {
+        let body_ext = |peek| MessageDataItemName::BodyExt {
+            section: Some(Section::Header(None)),
+            partial: None,
+            peek,
+        };
+
+        kat_inverse_command(&[
+            (
+                b"A FETCH 1 BODY[HEADER]\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::fetch("1", vec![body_ext(false)], false).unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A FETCH 1 BODY.PEEK[HEADER]\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::fetch("1", vec![body_ext(true)], false).unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A FETCH 1 (BODY[HEADER] BODY.PEEK[HEADER])\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::fetch("1", vec![body_ext(false), body_ext(true)], false).unwrap(),
+                )
+                .unwrap(),
+            ),
+        ]);
+
+        // The response is always `BODY[...]`.
+        kat_inverse_response(&[(
+            b"* 1 FETCH (BODY[HEADER] \"\")\r\n".as_ref(),
+            b"".as_ref(),
+            Response::Data(
+                Data::fetch(
+                    1,
+                    vec![MessageDataItem::BodyExt {
+                        section: Some(Section::Header(None)),
+                        origin: None,
+                        data: NString::try_from("").unwrap(),
+                    }],
+                )
+                .unwrap(),
+            ),
+        )]);
+    }
+
+    #[test]
+    fn test_kat_inverse_command_fetch_rfc822() {
+        let fetch = |names: Vec<MessageDataItemName<'static>>| {
+            Command::new("A", CommandBody::fetch("1", names, false).unwrap()).unwrap()
+        };
+
+        kat_inverse_command(&[
+            (
+                b"A FETCH 1 RFC822\r\n".as_ref(),
+                b"".as_ref(),
+                fetch(vec![MessageDataItemName::Rfc822]),
+            ),
+            (
+                b"A FETCH 1 RFC822.HEADER\r\n",
+                b"",
+                fetch(vec![MessageDataItemName::Rfc822Header]),
+            ),
+            (
+                b"A FETCH 1 RFC822.SIZE\r\n",
+                b"",
+                fetch(vec![MessageDataItemName::Rfc822Size]),
+            ),
+            (
+                b"A FETCH 1 RFC822.TEXT\r\n",
+                b"",
+                fetch(vec![MessageDataItemName::Rfc822Text]),
+            ),
+            (
+                b"A FETCH 1 (RFC822.HEADER RFC822.TEXT RFC822.SIZE RFC822)\r\n",
+                b"",
+                fetch(vec![
+                    MessageDataItemName::Rfc822Header,
+                    MessageDataItemName::Rfc822Text,
+                    MessageDataItemName::Rfc822Size,
+                    MessageDataItemName::Rfc822,
+                ]),
+            ),
+        ]);
+
+        kat_inverse_response(&[(
+            b"* 1 FETCH (RFC822.HEADER \"a\" RFC822.TEXT \"b\" RFC822.SIZE 2 RFC822 \"ab\")\r\n"
+                .as_ref(),
+            b"".as_ref(),
+            Response::Data(
+                Data::fetch(
+                    1,
+                    vec![
+                        MessageDataItem::Rfc822Header(NString::try_from("a").unwrap()),
+                        MessageDataItem::Rfc822Text(NString::try_from("b").unwrap()),
+                        MessageDataItem::Rfc822Size(2),
+                        MessageDataItem::Rfc822(NString::try_from("ab").unwrap()),
+                    ],
+                )
+                .unwrap(),
+            ),
+        )]);
+    }
+
+    #[test]
+    fn test_kat_inverse_command_fetch_partial() {
+        kat_inverse_command(&[
+            (
+                b"A FETCH 1 BODY[]<0.512>\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::fetch(
+                        "1",
+                        vec![MessageDataItemName::BodyExt {
+                            section: None,
+                            partial: Some((0, NonZeroU32::new(512).unwrap())),
+                            peek: false,
+                        }],
+                        false,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A FETCH 1 BODY.PEEK[1]<100.200>\r\n",
+                b"",
+                Command::new(
+                    "A",
+                    CommandBody::fetch(
+                        "1",
+                        vec![MessageDataItemName::BodyExt {
+                            section: Some(Section::Part(Part(Vec1::from(
+                                NonZeroU32::new(1).unwrap(),
+                            )))),
+                            partial: Some((100, NonZeroU32::new(200).unwrap())),
+                            peek: true,
+                        }],
+                        false,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+        ]);
+
+        // The count must not be zero.
+        assert!(CommandCodec::default()
+            .decode(b"A FETCH 1 BODY[]<0.0>\r\n")
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_msg_att_empty() {
+        // `msg-att` requires at least one item.
+        assert!(matches!(msg_att(8)(b"() "), Err(nom::Err::Error(_))));
+        assert!(msg_att(8)(b"(UID 1) ").is_ok());
+    }
 
     #[test]
     fn test_encode_message_data_item_name() {
@@ -372,6 +756,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_msg_att_other() {
+        let (remaining, got) = msg_att(8)(
+            b"(FLAGS (\\Seen) XVENDOR (a \"b\" {3}\r\nxyz (1 2 NIL)) X-GM-LABELS (\\Inbox))???",
+        )
+        .unwrap();
+
+        assert_eq!(remaining, b"???");
+        assert_eq!(
+            got.into_inner(),
+            vec![
+                MessageDataItem::Flags(vec![FlagFetch::Flag(Flag::Seen)]),
+                MessageDataItem::Other(MessageDataItemOther::unvalidated(
+                    Atom::try_from("XVENDOR").unwrap(),
+                    b"(a \"b\" {3}\r\nxyz (1 2 NIL))".as_ref(),
+                )),
+                MessageDataItem::Other(MessageDataItemOther::unvalidated(
+                    Atom::try_from("X-GM-LABELS").unwrap(),
+                    b"(\\Inbox)".as_ref(),
+                )),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_msg_att_other_does_not_hide_known_items() {
+        let tests = [
+            b"(UID x)".as_ref(),
+            b"(uid x)",
+            b"(FLAGS x)",
+            b"(BODY[FOO] NIL)",
+            b"(RFC822.SIZE NIL)",
+        ];
+
+        for test in tests {
+            assert!(matches!(msg_att(8)(test), Err(nom::Err::Error(_))));
+        }
+    }
+
     #[test]
     fn test_encode_message_data_item() {
         let tests = [
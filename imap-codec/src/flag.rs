@@ -134,11 +134,18 @@ pub(crate) fn mbx_list_flags(input: &[u8]) -> IMAPResult<&[u8], Vec<FlagNameAttr
 #[cfg(test)]
 mod tests {
     use imap_types::{
+        command::{Command, CommandBody},
         core::Atom,
-        flag::{Flag, FlagFetch, FlagNameAttribute, FlagPerm},
+        flag::{Flag, FlagFetch, FlagNameAttribute, FlagPerm, StoreResponse, StoreType},
+        response::{Data, Response},
     };
 
     use super::*;
+    use crate::{
+        decode::Decoder,
+        testing::{kat_inverse_command, kat_inverse_response},
+        CommandCodec,
+    };
 
     #[test]
     fn test_parse_flag_fetch() {
@@ -173,6 +180,86 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_flag_keyword() {
+        let tests = [
+            ("$MDNSent ", Flag::try_keyword("$MDNSent").unwrap()),
+            ("NonJunk ", Flag::try_keyword("NonJunk").unwrap()),
+            ("1 ", Flag::try_keyword("1").unwrap()),
+        ];
+
+        for (test, expected) in tests {
+            let (rem, got) = flag(test.as_bytes()).unwrap();
+            assert_eq!(rem, b" ");
+            assert_eq!(expected, got);
+            assert_eq!(test.trim_end(), got.to_string());
+        }
+
+        assert!(Flag::try_keyword("foo bar").is_err());
+        assert!(Flag::try_keyword("\\Seen").is_err());
+        assert!(Flag::try_keyword("").is_err());
+    }
+
+    #[test]
+    fn test_kat_inverse_command_store_keywords() {
+        kat_inverse_command(&[(
+            b"A1 STORE 1 +FLAGS ($MDNSent NonJunk 1 \\Seen)\r\n".as_ref(),
+            b"".as_ref(),
+            Command::new(
+                "A1",
+                CommandBody::store(
+                    "1",
+                    StoreType::Add,
+                    StoreResponse::Answer,
+                    vec![
+                        Flag::try_keyword("$MDNSent").unwrap(),
+                        Flag::try_keyword("NonJunk").unwrap(),
+                        Flag::try_keyword("1").unwrap(),
+                        Flag::Seen,
+                    ],
+                    false,
+                )
+                .unwrap(),
+            )
+            .unwrap(),
+        )]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_flags_recent() {
+        kat_inverse_response(&[(
+            b"* FLAGS (\\Seen \\Recent)\r\n".as_ref(),
+            b"".as_ref(),
+            Response::Data(Data::Flags(vec![
+                Flag::Seen,
+                Flag::try_from("\\Recent").unwrap(),
+            ])),
+        )]);
+
+        assert!(Flag::try_from("\\Recent").unwrap().is_recent());
+        assert!(Flag::try_from("\\recent").unwrap().is_recent());
+        assert!(!Flag::try_from("Recent").unwrap().is_recent());
+        assert!(!Flag::Seen.is_recent());
+    }
+
+    #[test]
+    fn test_parse_store_recent() {
+        let tests = [
+            b"A1 STORE 1 +FLAGS (\\Recent)\r\n".as_ref(),
+            b"A1 STORE 1 FLAGS \\Seen \\recent\r\n",
+            b"A1 UID STORE 1 -FLAGS.SILENT (\\Seen \\RECENT)\r\n",
+        ];
+
+        for test in tests {
+            assert!(CommandCodec::default().decode(test).is_err());
+        }
+
+        // A keyword named "Recent" is fine.
+        assert!(CommandCodec::default()
+            .decode(b"A1 STORE 1 +FLAGS (Recent)\r\n")
+            .is_ok());
+    }
+
     #[test]
     fn test_parse_mbx_list_flags() {
         let tests = [
@@ -20,7 +20,7 @@ use crate::extensions::metadata::metadata_resp;
 use crate::extensions::thread::thread_data;
 use crate::{
     core::{astring, nil, number, nz_number, quoted_char, string},
-    decode::IMAPResult,
+    decode::{IMAPResult, ResponseParseOptions},
     extensions::quota::{quota_response, quotaroot_response},
     flag::{flag_list, mbx_list_flags},
     status::status_att_list,
@@ -66,69 +66,93 @@ pub(crate) fn mailbox(input: &[u8]) -> IMAPResult<&[u8], Mailbox> {
 ///                number SP "EXISTS" /
 ///                number SP "RECENT"
 /// ```
-pub(crate) fn mailbox_data(input: &[u8]) -> IMAPResult<&[u8], Data> {
-    alt((
-        map(
-            tuple((tag_no_case(b"FLAGS"), sp, flag_list)),
-            |(_, _, flags)| Data::Flags(flags),
-        ),
-        map(
-            tuple((tag_no_case(b"LIST"), sp, mailbox_list)),
-            |(_, _, (items, delimiter, mailbox))| Data::List {
-                items: items.unwrap_or_default(),
-                mailbox,
-                delimiter,
-            },
-        ),
-        map(
-            tuple((tag_no_case(b"LSUB"), sp, mailbox_list)),
-            |(_, _, (items, delimiter, mailbox))| Data::Lsub {
-                items: items.unwrap_or_default(),
-                mailbox,
-                delimiter,
-            },
-        ),
-        map(
-            tuple((tag_no_case(b"SEARCH"), many0(preceded(sp, nz_number)))),
-            |(_, nums)| Data::Search(nums),
-        ),
-        #[cfg(feature = "ext_sort_thread")]
-        map(
-            preceded(tag_no_case(b"SORT"), many0(preceded(sp, nz_number))),
-            Data::Sort,
-        ),
-        #[cfg(feature = "ext_sort_thread")]
-        thread_data,
-        map(
-            tuple((
-                tag_no_case(b"STATUS"),
-                sp,
-                mailbox,
-                sp,
-                delimited(tag(b"("), opt(status_att_list), tag(b")")),
-                #[cfg(feature = "quirk_trailing_space")]
-                opt(sp),
-                #[cfg(not(feature = "quirk_trailing_space"))]
-                nom::combinator::success(()),
-            )),
-            |(_, _, mailbox, _, items, _)| Data::Status {
-                mailbox,
-                items: items.unwrap_or_default().into(),
-            },
-        ),
-        #[cfg(feature = "ext_metadata")]
-        metadata_resp,
-        map(
-            tuple((number, sp, tag_no_case(b"EXISTS"))),
-            |(num, _, _)| Data::Exists(num),
-        ),
-        map(
-            tuple((number, sp, tag_no_case(b"RECENT"))),
-            |(num, _, _)| Data::Recent(num),
-        ),
-        quotaroot_response,
-        quota_response,
-    ))(input)
+pub(crate) fn mailbox_data(
+    options: &ResponseParseOptions,
+) -> impl Fn(&[u8]) -> IMAPResult<&[u8], Data> {
+    #[cfg(feature = "ext_sort_thread")]
+    let max_thread_depth = options.max_thread_depth;
+    #[cfg(not(feature = "ext_sort_thread"))]
+    let _ = options;
+
+    move |input: &[u8]| {
+        alt((
+            map(
+                tuple((
+                    tag_no_case(b"FLAGS"),
+                    sp,
+                    flag_list,
+                    #[cfg(feature = "quirk_trailing_space")]
+                    opt(sp),
+                    #[cfg(not(feature = "quirk_trailing_space"))]
+                    nom::combinator::success(()),
+                )),
+                |(_, _, flags, _)| Data::Flags(flags),
+            ),
+            map(
+                tuple((tag_no_case(b"LIST"), sp, mailbox_list)),
+                |(_, _, (items, delimiter, mailbox))| Data::List {
+                    items: items.unwrap_or_default(),
+                    mailbox,
+                    delimiter,
+                },
+            ),
+            map(
+                tuple((tag_no_case(b"LSUB"), sp, mailbox_list)),
+                |(_, _, (items, delimiter, mailbox))| Data::Lsub {
+                    items: items.unwrap_or_default(),
+                    mailbox,
+                    delimiter,
+                },
+            ),
+            map(
+                tuple((
+                    tag_no_case(b"SEARCH"),
+                    many0(preceded(sp, nz_number)),
+                    #[cfg(feature = "quirk_trailing_space")]
+                    opt(sp),
+                    #[cfg(not(feature = "quirk_trailing_space"))]
+                    nom::combinator::success(()),
+                )),
+                |(_, nums, _)| Data::Search(nums),
+            ),
+            #[cfg(feature = "ext_sort_thread")]
+            map(
+                preceded(tag_no_case(b"SORT"), many0(preceded(sp, nz_number))),
+                Data::Sort,
+            ),
+            #[cfg(feature = "ext_sort_thread")]
+            thread_data(max_thread_depth),
+            map(
+                tuple((
+                    tag_no_case(b"STATUS"),
+                    sp,
+                    mailbox,
+                    sp,
+                    delimited(tag(b"("), opt(status_att_list), tag(b")")),
+                    #[cfg(feature = "quirk_trailing_space")]
+                    opt(sp),
+                    #[cfg(not(feature = "quirk_trailing_space"))]
+                    nom::combinator::success(()),
+                )),
+                |(_, _, mailbox, _, items, _)| Data::Status {
+                    mailbox,
+                    items: items.unwrap_or_default().into(),
+                },
+            ),
+            #[cfg(feature = "ext_metadata")]
+            metadata_resp,
+            map(
+                tuple((number, sp, tag_no_case(b"EXISTS"))),
+                |(num, _, _)| Data::Exists(num),
+            ),
+            map(
+                tuple((number, sp, tag_no_case(b"RECENT"))),
+                |(num, _, _)| Data::Recent(num),
+            ),
+            quotaroot_response,
+            quota_response,
+        ))(input)
+    }
 }
 
 /// `mailbox-list = "(" [mbx-list-flags] ")" SP
@@ -156,7 +180,10 @@ pub(crate) fn mailbox_list(
 
 #[cfg(test)]
 mod tests {
+    use imap_types::mailbox::MailboxNameEncoding;
+
     use super::*;
+    use crate::testing::known_answer_test_encode;
 
     #[test]
     fn test_mailbox() {
@@ -166,4 +193,39 @@ mod tests {
         assert!(mailbox(b"inbox.sent ").is_ok());
         assert!(mailbox(b"aaa").is_err());
     }
+
+    #[test]
+    fn test_encode_mailbox() {
+        let tests = [
+            (Mailbox::try_from("inbox").unwrap(), b"INBOX".as_ref()),
+            (Mailbox::try_from("iNbOx").unwrap(), b"INBOX"),
+            (Mailbox::try_from("Sent").unwrap(), b"Sent"),
+            (Mailbox::try_from("sent").unwrap(), b"sent"),
+        ];
+
+        for test in tests {
+            known_answer_test_encode(test);
+        }
+
+        let (_, got) = mailbox(b"\"iNbOx\" ").unwrap();
+        assert_eq!(Mailbox::Inbox, got);
+    }
+
+    #[test]
+    fn test_encode_mailbox_encoding() {
+        let tests = [
+            (MailboxNameEncoding::ModifiedUtf7, b"Entw&APw-rfe ".as_ref()),
+            (MailboxNameEncoding::Utf8, "{9}\r\nEntwürfe ".as_bytes()),
+        ];
+
+        for (encoding, expected) in tests {
+            let got = Mailbox::from_unicode_with("Entwürfe", encoding).unwrap();
+            known_answer_test_encode((got.clone(), &expected[..expected.len() - 1]));
+
+            let (rem, parsed) = mailbox(expected).unwrap();
+            assert_eq!(rem, b" ");
+            assert_eq!(got, parsed);
+            assert_eq!("Entwürfe", parsed.to_unicode_with(encoding).unwrap());
+        }
+    }
 }
@@ -2,24 +2,26 @@
 use abnf_core::streaming::crlf;
 #[cfg(feature = "quirk_crlf_relaxed")]
 use abnf_core::streaming::crlf_relaxed as crlf;
-use abnf_core::streaming::sp;
+use abnf_core::streaming::{crlf_relaxed, sp};
 use base64::{engine::general_purpose::STANDARD as _base64, Engine};
 use imap_types::{
-    core::{Text, Vec1},
+    core::{Atom, Text, Vec1},
     response::{
-        Bye, Capability, Code, CodeOther, CommandContinuationRequest, Data, Greeting, GreetingKind,
-        Response, Status, StatusBody, StatusKind, Tagged,
+        Bye, Capability, Code, CodeOther, CommandContinuationRequest, Data, DataOther, Greeting,
+        GreetingKind, Response, Status, StatusBody, StatusKind, Tagged,
     },
 };
-#[cfg(feature = "quirk_missing_text")]
-use nom::combinator::peek;
+#[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
+use imap_types::{extensions::referrals::ImapUrl, utils::indicators::is_text_char};
 use nom::{
     branch::alt,
     bytes::streaming::{tag, tag_no_case, take_until, take_while},
-    combinator::{map, map_res, opt, value},
+    combinator::{map, map_res, opt, peek, recognize, value, verify},
     multi::separated_list1,
     sequence::{delimited, preceded, terminated, tuple},
 };
+#[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
+use nom::{bytes::streaming::take_while1, combinator::map_opt, multi::many1_count};
 
 #[cfg(feature = "ext_id")]
 use crate::extensions::id::id_response;
@@ -27,11 +29,13 @@ use crate::extensions::id::id_response;
 use crate::extensions::metadata::metadata_code;
 #[cfg(feature = "ext_uidplus")]
 use crate::extensions::uidplus::{resp_code_apnd, resp_code_copy};
+#[cfg(feature = "legacy")]
+use crate::extensions::xlist::xlist_data;
 use crate::{
     core::{atom, charset, nz_number, tag_imap, text},
-    decode::IMAPResult,
+    decode::{IMAPResult, ParseMode, ResponseParseOptions},
     extensions::enable::enable_data,
-    fetch::msg_att,
+    fetch::{msg_att, msg_att_other_value_limited},
     flag::flag_perm,
     mailbox::mailbox_data,
 };
@@ -45,7 +49,9 @@ pub(crate) fn greeting(input: &[u8]) -> IMAPResult<&[u8], Greeting> {
         sp,
         alt((
             resp_cond_auth,
-            map(resp_cond_bye, |resp_text| (GreetingKind::Bye, resp_text)),
+            map(resp_cond_bye(ParseMode::Strict), |resp_text| {
+                (GreetingKind::Bye, resp_text)
+            }),
         )),
         crlf,
     ));
@@ -68,7 +74,7 @@ pub(crate) fn resp_cond_auth(
             value(GreetingKind::PreAuth, tag_no_case(b"PREAUTH")),
         )),
         sp,
-        resp_text,
+        resp_text(ParseMode::Strict),
     ));
 
     let (remaining, (kind, _, resp_text)) = parser(input)?;
@@ -77,43 +83,87 @@ pub(crate) fn resp_cond_auth(
 }
 
 /// `resp-text = ["[" resp-text-code "]" SP] text`
-pub(crate) fn resp_text(input: &[u8]) -> IMAPResult<&[u8], (Option<Code>, Text)> {
-    // When the text starts with "[", we insist to parse a code.
-    // Otherwise, a broken code could be interpreted as text.
-    let (_, start) = opt(tag(b"["))(input)?;
-
-    if start.is_some() {
-        tuple((
-            preceded(
-                tag(b"["),
-                map(
-                    alt((
-                        terminated(resp_text_code, tag(b"]")),
-                        map(
-                            terminated(
-                                take_while(|b: u8| b != b']' && b != b'\r' && b != b'\n'),
-                                tag(b"]"),
+///
+/// In lax mode, a missing or empty `text` is rectified to "...".
+pub(crate) fn resp_text(
+    mode: ParseMode,
+) -> impl Fn(&[u8]) -> IMAPResult<&[u8], (Option<Code>, Text)> {
+    move |input: &[u8]| {
+        // When the text starts with "[", we insist to parse a code.
+        // Otherwise, a broken code could be interpreted as text.
+        let (_, start) = opt(tag(b"["))(input)?;
+
+        if start.is_some() {
+            tuple((
+                preceded(
+                    tag(b"["),
+                    map(
+                        alt((
+                            terminated(resp_text_code, tag(b"]")),
+                            map(
+                                terminated(
+                                    take_while(|b: u8| b != b']' && b != b'\r' && b != b'\n'),
+                                    tag(b"]"),
+                                ),
+                                |bytes: &[u8]| Code::Other(CodeOther::unvalidated(bytes)),
                             ),
-                            |bytes: &[u8]| Code::Other(CodeOther::unvalidated(bytes)),
-                        ),
-                    )),
-                    Some,
+                        )),
+                        Some,
+                    ),
                 ),
-            ),
-            #[cfg(not(feature = "quirk_missing_text"))]
-            preceded(sp, text),
-            #[cfg(feature = "quirk_missing_text")]
-            alt((
-                preceded(sp, text),
-                map(peek(crlf), |_| {
-                    log::warn!("Rectified missing `text` to \"...\"");
+                |input| match mode {
+                    ParseMode::Strict => {
+                        #[cfg(not(feature = "quirk_missing_text"))]
+                        return preceded(sp, text)(input);
+                        #[cfg(feature = "quirk_missing_text")]
+                        return alt((preceded(sp, text), missing_text(mode)))(input);
+                    }
+                    ParseMode::Lax => {
+                        alt((preceded(sp, text), preceded(opt(sp), missing_text(mode))))(input)
+                    }
+                },
+            ))(input)
+        } else {
+            match mode {
+                ParseMode::Strict => map(text, |text| (None, text))(input),
+                ParseMode::Lax => map(alt((text, missing_text(mode))), |text| (None, text))(input),
+            }
+        }
+    }
+}
 
-                    Text::unvalidated("...")
-                }),
-            )),
-        ))(input)
-    } else {
-        map(text, |text| (None, text))(input)
+/// Rectify a missing `text` (followed by a line ending) to "...".
+fn missing_text(mode: ParseMode) -> impl Fn(&[u8]) -> IMAPResult<&[u8], Text> {
+    move |input: &[u8]| {
+        map(peek(line_ending(mode)), |_| {
+            log::warn!("Rectified missing `text` to \"...\"");
+
+            Text::unvalidated("...")
+        })(input)
+    }
+}
+
+/// `SP resp-text`
+///
+/// In lax mode, the `SP` may be missing before a code or a missing `text`.
+fn sp_resp_text(mode: ParseMode) -> impl Fn(&[u8]) -> IMAPResult<&[u8], (Option<Code>, Text)> {
+    move |input: &[u8]| match mode {
+        ParseMode::Strict => preceded(sp, resp_text(mode))(input),
+        ParseMode::Lax => alt((
+            preceded(sp, resp_text(mode)),
+            preceded(peek(tag(b"[")), resp_text(mode)),
+            map(missing_text(mode), |text| (None, text)),
+        ))(input),
+    }
+}
+
+/// `CRLF`
+///
+/// In lax mode, a bare `LF` is accepted, too.
+fn line_ending(mode: ParseMode) -> impl Fn(&[u8]) -> IMAPResult<&[u8], ()> {
+    move |input: &[u8]| match mode {
+        ParseMode::Strict => value((), crlf)(input),
+        ParseMode::Lax => value((), crlf_relaxed)(input),
     }
 }
 
@@ -205,9 +255,34 @@ pub(crate) fn resp_text_code(input: &[u8]) -> IMAPResult<&[u8], Code> {
         resp_code_copy,
         #[cfg(feature = "ext_uidplus")]
         value(Code::UidNotSticky, tag_no_case(b"UIDNOTSTICKY")),
+        #[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
+        map_opt(
+            preceded(tag_no_case(b"REFERRAL "), separated_list1(sp, referral_url)),
+            |urls: Vec<&[u8]>| {
+                let urls = urls
+                    .into_iter()
+                    .map(|url| ImapUrl::try_from(std::str::from_utf8(url).ok()?).ok())
+                    .collect::<Option<Vec<_>>>()?;
+
+                Vec1::try_from(urls).ok().map(Code::Referral)
+            },
+        ),
     ))(input)
 }
 
+/// Take an IMAP URL (RFC 2192) up to the closing `]` of the response code.
+///
+/// Note: An IPv6 host is enclosed in brackets, e.g., `imap://[::1]/INBOX`.
+#[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
+fn referral_url(input: &[u8]) -> IMAPResult<&[u8], &[u8]> {
+    let is_url_char = |b| is_text_char(b) && b != b'[' && b != b']' && b != b' ';
+
+    recognize(many1_count(alt((
+        take_while1(is_url_char),
+        recognize(tuple((tag(b"["), take_while(is_url_char), tag(b"]")))),
+    ))))(input)
+}
+
 /// `capability-data = "CAPABILITY" *(SP capability) SP "IMAP4rev1" *(SP capability)`
 ///
 /// Servers MUST implement the STARTTLS, AUTH=PLAIN, and LOGINDISABLED capabilities
@@ -232,74 +307,94 @@ pub(crate) fn capability(input: &[u8]) -> IMAPResult<&[u8], Capability> {
 }
 
 /// `resp-cond-bye = "BYE" SP resp-text`
-pub(crate) fn resp_cond_bye(input: &[u8]) -> IMAPResult<&[u8], (Option<Code>, Text)> {
-    let mut parser = tuple((tag_no_case(b"BYE"), sp, resp_text));
+pub(crate) fn resp_cond_bye(
+    mode: ParseMode,
+) -> impl Fn(&[u8]) -> IMAPResult<&[u8], (Option<Code>, Text)> {
+    move |input: &[u8]| {
+        let mut parser = tuple((tag_no_case(b"BYE"), sp_resp_text(mode)));
 
-    let (remaining, (_, _, resp_text)) = parser(input)?;
+        let (remaining, (_, resp_text)) = parser(input)?;
 
-    Ok((remaining, resp_text))
+        Ok((remaining, resp_text))
+    }
 }
 
 // ----- response -----
 
 /// `response = *(continue-req / response-data) response-done`
-pub(crate) fn response(input: &[u8]) -> IMAPResult<&[u8], Response> {
-    // Divert from standard here for better usability.
-    // response_data already contains the bye response, thus
-    // response_done could also be response_tagged.
-    //
-    // However, I will keep it as it is for now.
-    alt((
-        map(continue_req, Response::CommandContinuationRequest),
-        response_data,
-        map(response_done, Response::Status),
-    ))(input)
+pub(crate) fn response(
+    options: &ResponseParseOptions,
+) -> impl Fn(&[u8]) -> IMAPResult<&[u8], Response> {
+    let options = *options;
+
+    move |input: &[u8]| {
+        let mode = options.mode;
+
+        // Divert from standard here for better usability.
+        // response_data already contains the bye response, thus
+        // response_done could also be response_tagged.
+        //
+        // However, I will keep it as it is for now.
+        alt((
+            map(continue_req(mode), Response::CommandContinuationRequest),
+            response_data(&options),
+            map(response_done(mode), Response::Status),
+        ))(input)
+    }
 }
 
 /// `continue-req = "+" SP (resp-text / base64) CRLF`
-pub(crate) fn continue_req(input: &[u8]) -> IMAPResult<&[u8], CommandContinuationRequest> {
-    // We can't map the output of `resp_text` directly to `Continue::basic()` because we might end
-    // up with a subset of `Text` that is valid base64 and will panic on `unwrap()`. Thus, we first
-    // let the parsing finish and only later map to `Continue`.
+pub(crate) fn continue_req(
+    mode: ParseMode,
+) -> impl Fn(&[u8]) -> IMAPResult<&[u8], CommandContinuationRequest> {
+    move |input: &[u8]| {
+        // We can't map the output of `resp_text` directly to `Continue::basic()` because we might end
+        // up with a subset of `Text` that is valid base64 and will panic on `unwrap()`. Thus, we first
+        // let the parsing finish and only later map to `Continue`.
 
-    // A helper struct to postpone the unification to `Continue` in the `alt` combinator below.
-    enum Either<A, B> {
-        Base64(A),
-        Basic(B),
-    }
+        // A helper struct to postpone the unification to `Continue` in the `alt` combinator below.
+        enum Either<A, B> {
+            Base64(A),
+            Basic(B),
+        }
 
-    let mut parser = tuple((
-        tag(b"+ "),
-        alt((
-            #[cfg(not(feature = "quirk_crlf_relaxed"))]
-            map(
-                map_res(take_until("\r\n"), |input| _base64.decode(input)),
-                Either::Base64,
-            ),
-            #[cfg(feature = "quirk_crlf_relaxed")]
-            map(
-                map_res(take_until("\n"), |input: &[u8]| {
-                    if !input.is_empty() && input[input.len().saturating_sub(1)] == b'\r' {
-                        _base64.decode(&input[..input.len().saturating_sub(1)])
-                    } else {
-                        _base64.decode(input)
-                    }
-                }),
-                Either::Base64,
-            ),
-            map(resp_text, Either::Basic),
-        )),
-        crlf,
-    ));
+        let mut parser = tuple((
+            tag(b"+ "),
+            alt((
+                map(continue_req_base64(mode), Either::Base64),
+                map(resp_text(mode), Either::Basic),
+            )),
+            line_ending(mode),
+        ));
 
-    let (remaining, (_, either, _)) = parser(input)?;
+        let (remaining, (_, either, _)) = parser(input)?;
 
-    let continue_request = match either {
-        Either::Base64(data) => CommandContinuationRequest::base64(data),
-        Either::Basic((code, text)) => CommandContinuationRequest::basic(code, text).unwrap(),
-    };
+        let continue_request = match either {
+            Either::Base64(data) => CommandContinuationRequest::base64(data),
+            Either::Basic((code, text)) => CommandContinuationRequest::basic(code, text).unwrap(),
+        };
 
-    Ok((remaining, continue_request))
+        Ok((remaining, continue_request))
+    }
+}
+
+/// `base64` (until the line ending)
+///
+/// In lax mode, the line may also end with a bare `LF`.
+fn continue_req_base64(mode: ParseMode) -> impl Fn(&[u8]) -> IMAPResult<&[u8], Vec<u8>> {
+    move |input: &[u8]| {
+        if mode == ParseMode::Strict && !cfg!(feature = "quirk_crlf_relaxed") {
+            return map_res(take_until("\r\n"), |input| _base64.decode(input))(input);
+        }
+
+        map_res(take_until("\n"), |input: &[u8]| {
+            if !input.is_empty() && input[input.len().saturating_sub(1)] == b'\r' {
+                _base64.decode(&input[..input.len().saturating_sub(1)])
+            } else {
+                _base64.decode(input)
+            }
+        })(input)
+    }
 }
 
 /// ```abnf
@@ -309,117 +404,224 @@ pub(crate) fn continue_req(input: &[u8]) -> IMAPResult<&[u8], CommandContinuatio
 ///                    mailbox-data /
 ///                    message-data /
 ///                    capability-data /
-///                    id_response ; (See RFC 2971)
+///                    id_response / ; (See RFC 2971)
+///                    xlist-data    ; (legacy)
 ///                  ) CRLF
 /// ```
-pub(crate) fn response_data(input: &[u8]) -> IMAPResult<&[u8], Response> {
-    let mut parser = tuple((
-        tag(b"*"),
-        sp,
-        alt((
-            map(resp_cond_state, |(kind, code, text)| {
-                Response::Status(Status::Untagged(StatusBody { kind, code, text }))
-            }),
-            map(resp_cond_bye, |(code, text)| {
-                Response::Status(Status::Bye(Bye { code, text }))
-            }),
-            map(mailbox_data, Response::Data),
-            map(message_data, Response::Data),
-            map(capability_data, |caps| {
-                Response::Data(Data::Capability(caps))
-            }),
-            map(enable_data, Response::Data),
+pub(crate) fn response_data(
+    options: &ResponseParseOptions,
+) -> impl Fn(&[u8]) -> IMAPResult<&[u8], Response> {
+    let options = *options;
+
+    move |input: &[u8]| {
+        let mode = options.mode;
+
+        let mut parser = tuple((
+            tag(b"*"),
+            sp,
+            alt((
+                map(resp_cond_state(mode), |(kind, code, text)| {
+                    Response::Status(Status::Untagged(StatusBody { kind, code, text }))
+                }),
+                map(resp_cond_bye(mode), |(code, text)| {
+                    Response::Status(Status::Bye(Bye { code, text }))
+                }),
+                map(mailbox_data(&options), Response::Data),
+                map(message_data(&options), Response::Data),
+                map(capability_data, |caps| {
+                    Response::Data(Data::Capability(caps))
+                }),
+                map(enable_data, Response::Data),
+                #[cfg(feature = "ext_id")]
+                map(id_response, |parameters| {
+                    Response::Data(Data::Id { parameters })
+                }),
+                #[cfg(feature = "legacy")]
+                map(xlist_data, Response::Data),
+                map(data_other(&options), Response::Data),
+            )),
+            line_ending(mode),
+        ));
+
+        let (remaining, (_, _, response, _)) = parser(input)?;
+
+        Ok((remaining, response))
+    }
+}
+
+/// `data-other = atom [SP msg-att-other-value *(SP msg-att-other-value)]`
+///
+/// Note: This is not part of the IMAP grammar. It's a fallback for unknown untagged responses,
+/// e.g., `* XLIST ...`. The value is not interpreted but only skipped, honoring parenthesized
+/// lists, quoted strings, and literals, to know where the response ends. Nested lists are
+/// limited to `max_body_depth`.
+pub(crate) fn data_other(
+    options: &ResponseParseOptions,
+) -> impl Fn(&[u8]) -> IMAPResult<&[u8], Data> {
+    fn is_known(name: &Atom) -> bool {
+        // Keep in sync with `response_data`. Only list responses that have a parser, e.g.,
+        // `ACL` or `ESEARCH` must stay `Data::Other` until they are implemented.
+        const KNOWN: &[&str] = &[
+            "CAPABILITY",
+            "ENABLED",
+            "FLAGS",
             #[cfg(feature = "ext_id")]
-            map(id_response, |parameters| {
-                Response::Data(Data::Id { parameters })
-            }),
-        )),
-        crlf,
-    ));
+            "ID",
+            "LIST",
+            "LSUB",
+            #[cfg(feature = "ext_metadata")]
+            "METADATA",
+            "QUOTA",
+            "QUOTAROOT",
+            "SEARCH",
+            #[cfg(feature = "ext_sort_thread")]
+            "SORT",
+            "STATUS",
+            #[cfg(feature = "ext_sort_thread")]
+            "THREAD",
+            #[cfg(feature = "legacy")]
+            "XLIST",
+        ];
+
+        // A status keyword glued to other characters, e.g., `OKAY`, is a malformed status
+        // response rather than an unknown one.
+        const STATUS: &[&str] = &["BAD", "BYE", "NO", "OK", "PREAUTH"];
+
+        let name = name.inner();
+
+        // Message data (e.g. `* 1 FETCH ...`) and malformed known responses must not be parsed
+        // as unknown responses.
+        name.bytes().all(|b| b.is_ascii_digit())
+            || KNOWN.iter().any(|known| known.eq_ignore_ascii_case(name))
+            || STATUS.iter().any(|status| {
+                name.len() >= status.len()
+                    && name.as_bytes()[..status.len()].eq_ignore_ascii_case(status.as_bytes())
+            })
+    }
+
+    let max_depth = options.max_body_depth;
 
-    let (remaining, (_, _, response, _)) = parser(input)?;
+    move |input: &[u8]| {
+        let mut parser = tuple((
+            verify(atom, |name| !is_known(name)),
+            opt(preceded(
+                sp,
+                recognize(separated_list1(sp, |input| {
+                    msg_att_other_value_limited(input, max_depth)
+                })),
+            )),
+        ));
+
+        let (remaining, (name, value)) = parser(input)?;
 
-    Ok((remaining, response))
+        Ok((
+            remaining,
+            Data::Other(DataOther::unvalidated(name, value.unwrap_or_default())),
+        ))
+    }
 }
 
 /// `resp-cond-state = ("OK" / "NO" / "BAD") SP resp-text`
 ///
 /// Status condition
-pub(crate) fn resp_cond_state(input: &[u8]) -> IMAPResult<&[u8], (StatusKind, Option<Code>, Text)> {
-    let mut parser = tuple((
-        alt((
-            value(StatusKind::Ok, tag_no_case("OK")),
-            value(StatusKind::No, tag_no_case("NO")),
-            value(StatusKind::Bad, tag_no_case("BAD")),
-        )),
-        sp,
-        resp_text,
-    ));
+#[allow(clippy::type_complexity)]
+pub(crate) fn resp_cond_state(
+    mode: ParseMode,
+) -> impl Fn(&[u8]) -> IMAPResult<&[u8], (StatusKind, Option<Code>, Text)> {
+    move |input: &[u8]| {
+        let mut parser = tuple((
+            alt((
+                value(StatusKind::Ok, tag_no_case("OK")),
+                value(StatusKind::No, tag_no_case("NO")),
+                value(StatusKind::Bad, tag_no_case("BAD")),
+            )),
+            sp_resp_text(mode),
+        ));
 
-    let (remaining, (kind, _, (maybe_code, text))) = parser(input)?;
+        let (remaining, (kind, (maybe_code, text))) = parser(input)?;
 
-    Ok((remaining, (kind, maybe_code, text)))
+        Ok((remaining, (kind, maybe_code, text)))
+    }
 }
 
 /// `response-done = response-tagged / response-fatal`
-pub(crate) fn response_done(input: &[u8]) -> IMAPResult<&[u8], Status> {
-    alt((response_tagged, response_fatal))(input)
+pub(crate) fn response_done(mode: ParseMode) -> impl Fn(&[u8]) -> IMAPResult<&[u8], Status> {
+    move |input: &[u8]| alt((response_tagged(mode), response_fatal(mode)))(input)
 }
 
 /// `response-tagged = tag SP resp-cond-state CRLF`
-pub(crate) fn response_tagged(input: &[u8]) -> IMAPResult<&[u8], Status> {
-    let mut parser = tuple((tag_imap, sp, resp_cond_state, crlf));
-
-    let (remaining, (tag, _, (kind, code, text), _)) = parser(input)?;
-
-    Ok((
-        remaining,
-        Status::Tagged(Tagged {
-            tag,
-            body: StatusBody { kind, code, text },
-        }),
-    ))
+pub(crate) fn response_tagged(mode: ParseMode) -> impl Fn(&[u8]) -> IMAPResult<&[u8], Status> {
+    move |input: &[u8]| {
+        let mut parser = tuple((tag_imap, sp, resp_cond_state(mode), line_ending(mode)));
+
+        let (remaining, (tag, _, (kind, code, text), _)) = parser(input)?;
+
+        Ok((
+            remaining,
+            Status::Tagged(Tagged {
+                tag,
+                body: StatusBody { kind, code, text },
+            }),
+        ))
+    }
 }
 
 /// `response-fatal = "*" SP resp-cond-bye CRLF`
 ///
 /// Server closes connection immediately
-pub(crate) fn response_fatal(input: &[u8]) -> IMAPResult<&[u8], Status> {
-    let mut parser = tuple((tag(b"*"), sp, resp_cond_bye, crlf));
+pub(crate) fn response_fatal(mode: ParseMode) -> impl Fn(&[u8]) -> IMAPResult<&[u8], Status> {
+    move |input: &[u8]| {
+        let mut parser = tuple((tag(b"*"), sp, resp_cond_bye(mode), line_ending(mode)));
 
-    let (remaining, (_, _, (code, text), _)) = parser(input)?;
+        let (remaining, (_, _, (code, text), _)) = parser(input)?;
 
-    Ok((remaining, Status::Bye(Bye { code, text })))
+        Ok((remaining, Status::Bye(Bye { code, text })))
+    }
 }
 
 /// `message-data = nz-number SP ("EXPUNGE" / ("FETCH" SP msg-att))`
-pub(crate) fn message_data(input: &[u8]) -> IMAPResult<&[u8], Data> {
-    let (remaining, seq) = terminated(nz_number, sp)(input)?;
+pub(crate) fn message_data(
+    options: &ResponseParseOptions,
+) -> impl Fn(&[u8]) -> IMAPResult<&[u8], Data> {
+    let max_body_depth = options.max_body_depth;
 
-    alt((
-        map(tag_no_case(b"EXPUNGE"), move |_| Data::Expunge(seq)),
-        map(
-            tuple((tag_no_case(b"FETCH"), sp, msg_att)),
-            move |(_, _, items)| Data::Fetch { seq, items },
-        ),
-    ))(remaining)
+    move |input: &[u8]| {
+        let (remaining, seq) = terminated(nz_number, sp)(input)?;
+
+        alt((
+            map(tag_no_case(b"EXPUNGE"), move |_| Data::Expunge(seq)),
+            map(
+                tuple((tag_no_case(b"FETCH"), sp, msg_att(max_body_depth))),
+                move |(_, _, items)| Data::Fetch { seq, items },
+            ),
+        ))(remaining)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::num::NonZeroU32;
+    use std::{borrow::Cow, num::NonZeroU32};
 
     use imap_types::{
         body::{
             BasicFields, Body, BodyExtension, BodyStructure, Disposition, Language, Location,
             SinglePartExtensionData, SpecificFields,
         },
-        core::{IString, NString, QuotedChar, Tag},
-        flag::FlagNameAttribute,
+        core::{IString, Literal, NString, QuotedChar, Tag},
+        fetch::MessageDataItem,
+        flag::{Flag, FlagNameAttribute, FlagPerm},
+        mailbox::Mailbox,
+        response::Tagged,
+        utils::escape_byte_string,
     };
 
     use super::*;
-    use crate::testing::{kat_inverse_greeting, kat_inverse_response, known_answer_test_encode};
+    use crate::{
+        decode::{Decoder, ResponseDecodeError},
+        encode::Encoder,
+        testing::{kat_inverse_greeting, kat_inverse_response, known_answer_test_encode},
+        ResponseCodec,
+    };
 
     #[test]
     fn test_kat_inverse_greeting() {
@@ -452,9 +654,36 @@ mod tests {
                 b"".as_ref(),
                 Greeting::new(GreetingKind::PreAuth, Some(Code::Alert), "hello").unwrap(),
             ),
+            (
+                b"* OK [CAPABILITY IMAP4REV1 IDLE] ready\r\n".as_ref(),
+                b"".as_ref(),
+                Greeting::ok_with_capabilities(
+                    Vec1::try_from(vec![Capability::Imap4Rev1, Capability::Idle]).unwrap(),
+                    "ready",
+                )
+                .unwrap(),
+            ),
         ]);
     }
 
+    #[test]
+    fn test_parse_greeting_with_capabilities() {
+        let (rem, got) = greeting(b"* OK [CAPABILITY IMAP4rev1 idle X-FOO] ready\r\n").unwrap();
+        assert!(rem.is_empty());
+
+        let expected = Greeting::ok_with_capabilities(
+            Vec1::try_from(vec![
+                Capability::Imap4Rev1,
+                Capability::Idle,
+                Capability::try_from("X-FOO").unwrap(),
+            ])
+            .unwrap(),
+            "ready",
+        )
+        .unwrap();
+        assert_eq!(expected, got);
+    }
+
     #[test]
     fn test_kat_inverse_response_data() {
         kat_inverse_response(&[
@@ -472,6 +701,51 @@ mod tests {
                     mailbox: "bbb".try_into().unwrap(),
                 }),
             ),
+            (
+                b"* LIST () \"/\" INBOX\r\n",
+                b"",
+                Response::Data(Data::List {
+                    items: vec![],
+                    delimiter: Some(QuotedChar::try_from('/').unwrap()),
+                    mailbox: Mailbox::Inbox,
+                }),
+            ),
+            (
+                b"* LIST (\\Noselect) NIL foo\r\n",
+                b"",
+                Response::Data(Data::List {
+                    items: vec![FlagNameAttribute::Noselect],
+                    delimiter: None,
+                    mailbox: "foo".try_into().unwrap(),
+                }),
+            ),
+            (
+                b"* LSUB () \"/\" INBOX\r\n",
+                b"",
+                Response::Data(Data::Lsub {
+                    items: vec![],
+                    delimiter: Some(QuotedChar::try_from('/').unwrap()),
+                    mailbox: Mailbox::Inbox,
+                }),
+            ),
+            (
+                b"* LSUB (\\Noselect) NIL foo\r\n",
+                b"",
+                Response::Data(Data::Lsub {
+                    items: vec![FlagNameAttribute::Noselect],
+                    delimiter: None,
+                    mailbox: "foo".try_into().unwrap(),
+                }),
+            ),
+            (
+                b"* LIST (\\Noselect \\Marked) \".\" foo.bar\r\n",
+                b"",
+                Response::Data(Data::List {
+                    items: vec![FlagNameAttribute::Noselect, FlagNameAttribute::Marked],
+                    delimiter: Some(QuotedChar::try_from('.').unwrap()),
+                    mailbox: "foo.bar".try_into().unwrap(),
+                }),
+            ),
             (
                 b"* SEARCH 1 2 3 42\r\n",
                 b"",
@@ -496,6 +770,39 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_parse_response_zero_counts() {
+        // EXISTS and RECENT can be 0 ...
+        kat_inverse_response(&[
+            (
+                b"* 0 EXISTS\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Exists(0)),
+            ),
+            (b"* 0 RECENT\r\n", b"", Response::Data(Data::Recent(0))),
+        ]);
+
+        // ... but sequence numbers are 1-based.
+        for mode in [ParseMode::Strict, ParseMode::Lax] {
+            assert!(response(&ResponseParseOptions {
+                mode,
+                ..Default::default()
+            })(b"* 0 EXPUNGE\r\n")
+            .is_err());
+            assert!(response(&ResponseParseOptions {
+                mode,
+                ..Default::default()
+            })(b"* 0 FETCH (UID 1)\r\n")
+            .is_err());
+        }
+        assert!(Data::expunge(0).is_err());
+        assert!(serde_json::from_str::<Data>(r#"{"Expunge":0}"#).is_err());
+        assert_eq!(
+            serde_json::from_str::<Data>(r#"{"Expunge":1}"#).unwrap(),
+            Data::expunge(1).unwrap()
+        );
+    }
+
     #[test]
     fn test_kat_inverse_response_status() {
         kat_inverse_response(&[
@@ -597,29 +904,53 @@ mod tests {
         ]);
     }
 
-    /*
-    // TODO(#184)
     #[test]
     fn test_kat_inverse_continue() {
-        kat_inverse_continue(&[
+        kat_inverse_response(&[
             (
                 b"+ \x01\r\n".as_ref(),
                 b"".as_ref(),
-                Continue::basic(None, "\x01").unwrap(),
+                Response::CommandContinuationRequest(
+                    CommandContinuationRequest::basic(None, "\x01").unwrap(),
+                ),
             ),
             (
-                b"+ hello\r\n".as_ref(),
-                b"".as_ref(),
-                Continue::basic(None, "hello").unwrap(),
+                b"+ hello\r\n",
+                b"",
+                Response::CommandContinuationRequest(
+                    CommandContinuationRequest::basic(None, "hello").unwrap(),
+                ),
             ),
             (
-                b"+ [READ-WRITE] hello\r\n",
+                b"+ [READ-WRITE] Ready\r\n",
                 b"",
-                Continue::basic(Some(Code::ReadWrite), "hello").unwrap(),
+                Response::CommandContinuationRequest(
+                    CommandContinuationRequest::basic(Some(Code::ReadWrite), "Ready").unwrap(),
+                ),
+            ),
+            (
+                b"+ [ALERT] Send literal\r\n",
+                b"",
+                Response::CommandContinuationRequest(
+                    CommandContinuationRequest::basic(Some(Code::Alert), "Send literal").unwrap(),
+                ),
+            ),
+            (
+                b"+ \r\n",
+                b"",
+                Response::CommandContinuationRequest(CommandContinuationRequest::base64(
+                    b"".as_ref(),
+                )),
+            ),
+            (
+                b"+ AP8QYmluYXJ5\r\n",
+                b"",
+                Response::CommandContinuationRequest(CommandContinuationRequest::base64(
+                    b"\x00\xff\x10binary".as_ref(),
+                )),
             ),
         ]);
     }
-    */
 
     #[test]
     fn test_encode_body_structure() {
@@ -700,6 +1031,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_encode_capability_lists() {
+        known_answer_test_encode((
+            Response::Data(Data::capability(Capability::base_imap4rev1()).unwrap()),
+            b"* CAPABILITY IMAP4REV1\r\n",
+        ));
+
+        #[cfg(feature = "starttls")]
+        known_answer_test_encode((
+            Response::Data(Data::capability(Capability::recommended_tls()).unwrap()),
+            b"* CAPABILITY IMAP4REV1 STARTTLS LOGINDISABLED AUTH=PLAIN\r\n",
+        ));
+    }
+
+    #[test]
+    fn test_parse_capability() {
+        let tests = [
+            (b"IMAP4rev1 ".as_ref(), Capability::Imap4Rev1),
+            (b"IDLE ", Capability::Idle),
+            #[cfg(feature = "starttls")]
+            (b"STARTTLS ", Capability::StartTls),
+            #[cfg(feature = "starttls")]
+            (b"starttls ", Capability::StartTls),
+            #[cfg(feature = "starttls")]
+            (b"LOGINDISABLED ", Capability::LoginDisabled),
+        ];
+
+        for (test, expected) in tests {
+            let (rem, got) = capability(test).unwrap();
+            assert_eq!(rem, b" ");
+            assert_eq!(got, expected);
+        }
+    }
+
+    #[test]
+    fn test_capability_other_casing() {
+        let (rem, got) = capability(b"XPIG-Latin ").unwrap();
+        assert_eq!(rem, b" ");
+        match &got {
+            Capability::Other(other) => assert_eq!(other.inner().inner(), "XPIG-Latin"),
+            _ => panic!("expected unknown capability"),
+        }
+
+        kat_inverse_response(&[(
+            b"* CAPABILITY IMAP4REV1 XPIG-Latin\r\n".as_ref(),
+            b"".as_ref(),
+            Response::Data(Data::capability(vec![Capability::Imap4Rev1, got]).unwrap()),
+        )]);
+    }
+
     #[test]
     fn test_parse_response_negative() {
         let tests = [
@@ -708,7 +1089,7 @@ mod tests {
         ];
 
         for test in tests {
-            assert!(response(test).is_err());
+            assert!(response(&ResponseParseOptions::default())(test).is_err());
         }
     }
 
@@ -716,33 +1097,608 @@ mod tests {
     fn test_parse_resp_text_quirk() {
         #[cfg(not(feature = "quirk_missing_text"))]
         {
-            assert!(resp_text(b"[IMAP4rev1]\r\n").is_err());
-            assert!(resp_text(b"[IMAP4rev1]\r\n").is_err());
-            assert!(resp_text(b"[IMAP4rev1] \r\n").is_err());
-            assert!(resp_text(b"[IMAP4rev1]  \r\n").is_ok());
+            assert!(resp_text(ParseMode::Strict)(b"[IMAP4rev1]\r\n").is_err());
+            assert!(resp_text(ParseMode::Strict)(b"[IMAP4rev1]\r\n").is_err());
+            assert!(resp_text(ParseMode::Strict)(b"[IMAP4rev1] \r\n").is_err());
+            assert!(resp_text(ParseMode::Strict)(b"[IMAP4rev1]  \r\n").is_ok());
         }
 
         #[cfg(feature = "quirk_missing_text")]
         {
-            assert!(resp_text(b"[IMAP4rev1]\r\n").is_ok());
-            assert!(resp_text(b"[IMAP4rev1] \r\n").is_err());
-            assert!(resp_text(b"[IMAP4rev1]  \r\n").is_ok());
+            assert!(resp_text(ParseMode::Strict)(b"[IMAP4rev1]\r\n").is_ok());
+            assert!(resp_text(ParseMode::Strict)(b"[IMAP4rev1] \r\n").is_err());
+            assert!(resp_text(ParseMode::Strict)(b"[IMAP4rev1]  \r\n").is_ok());
+        }
+    }
+
+    #[test]
+    fn test_parse_status_borrows_tag_and_text() {
+        let (rem, got) = response(&ResponseParseOptions::default())(b"A1 OK done\r\n").unwrap();
+        assert!(rem.is_empty());
+
+        let (tag, body) = match got {
+            Response::Status(Status::Tagged(Tagged { tag, body })) => (tag, body),
+            _ => panic!("expected tagged status"),
+        };
+        assert!(matches!(tag.clone().into_inner(), Cow::Borrowed("A1")));
+        assert!(matches!(
+            body.text.clone().into_inner(),
+            Cow::Borrowed("done")
+        ));
+
+        #[cfg(feature = "bounded-static")]
+        {
+            use bounded_static::IntoBoundedStatic;
+            use imap_types::core::Text;
+
+            let tag: Tag<'static> = tag.into_static();
+            let text: Text<'static> = body.text.into_static();
+            assert_eq!(tag.inner(), "A1");
+            assert_eq!(text.inner(), "done");
+        }
+    }
+
+    #[test]
+    fn test_parse_resp_text_brackets_in_text() {
+        let tests = [
+            (
+                b"[ALERT] foo [bar]\r\n".as_ref(),
+                Some(Code::Alert),
+                "foo [bar]",
+            ),
+            (
+                b"[ALERT] Maintenance [see wiki]\r\n",
+                Some(Code::Alert),
+                "Maintenance [see wiki]",
+            ),
+            (b"[ALERT] [bar] foo\r\n", Some(Code::Alert), "[bar] foo"),
+            (
+                b"[XFOO] a] b\r\n",
+                Some(Code::Other(CodeOther::unvalidated(b"XFOO".as_ref()))),
+                "a] b",
+            ),
+            (b"foo [ALERT] bar\r\n", None, "foo [ALERT] bar"),
+        ];
+
+        for (test, expected_code, expected_text) in tests {
+            let (rem, (code, text)) = resp_text(ParseMode::Strict)(test).unwrap();
+            assert_eq!(rem, b"\r\n");
+            assert_eq!(code, expected_code);
+            assert_eq!(text.inner(), expected_text);
+        }
+    }
+
+    #[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
+    #[test]
+    fn test_kat_inverse_greeting_referral() {
+        use imap_types::extensions::referrals::ImapUrl;
+
+        kat_inverse_greeting(&[(
+            b"* BYE [REFERRAL imap://host/] Server moved\r\n".as_ref(),
+            b"".as_ref(),
+            Greeting::bye(
+                Some(Code::Referral(Vec1::from(ImapUrl::new("host").unwrap()))),
+                "Server moved",
+            )
+            .unwrap(),
+        )]);
+
+        let (_, got) = greeting(b"* BYE [REFERRAL imap://host:1143/] Server moved\r\n").unwrap();
+        assert!(got.is_bye());
+        let url = got.referral().unwrap();
+        assert_eq!(url.host(), "host");
+        assert_eq!(url.port(), Some(1143));
+        assert_eq!(url.mailbox(), None);
+
+        let (_, got) = greeting(b"* BYE Server moved\r\n").unwrap();
+        assert_eq!(got.referral(), None);
+
+        let (_, got) = response(&ResponseParseOptions::default())(
+            b"* BYE [REFERRAL imap://host/] Server moved\r\n",
+        )
+        .unwrap();
+        match got {
+            Response::Status(Status::Bye(bye)) => {
+                assert_eq!(bye.referral(), Some(&ImapUrl::new("host").unwrap()));
+                assert_eq!(
+                    bye.code.as_ref(),
+                    Some(&Code::Referral(Vec1::from(ImapUrl::new("host").unwrap())))
+                );
+            }
+            _ => panic!("expected BYE"),
+        }
+    }
+
+    #[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
+    #[test]
+    fn test_kat_inverse_response_referral() {
+        use imap_types::extensions::referrals::ImapUrl;
+
+        kat_inverse_response(&[
+            (
+                b"A NO [REFERRAL imap://alice@host:1143/Archive%202024;UIDVALIDITY=1/;UID=2] ...\r\n"
+                    .as_ref(),
+                b"".as_ref(),
+                Response::Status(
+                    Status::no(
+                        Some(Tag::try_from("A").unwrap()),
+                        Some(Code::Referral(Vec1::from(
+                            ImapUrl::new("host")
+                                .unwrap()
+                                .with_user("alice")
+                                .with_port(1143)
+                                .with_mailbox("Archive 2024")
+                                .with_uid_validity(NonZeroU32::new(1).unwrap())
+                                .with_uid(NonZeroU32::new(2).unwrap()),
+                        ))),
+                        "...",
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"* BYE [REFERRAL imap://host/INBOX] ...\r\n",
+                b"",
+                Response::Status(
+                    Status::bye(
+                        Some(Code::Referral(Vec1::from(
+                            ImapUrl::new("host").unwrap().with_mailbox("INBOX"),
+                        ))),
+                        "...",
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"* BYE [REFERRAL imap://;AUTH=*@[::1]:143/INBOX] ...\r\n",
+                b"",
+                Response::Status(
+                    Status::bye(
+                        Some(Code::Referral(Vec1::from(
+                            ImapUrl::new("[::1]")
+                                .unwrap()
+                                .with_auth("*")
+                                .with_port(143)
+                                .with_mailbox("INBOX"),
+                        ))),
+                        "...",
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"* BYE [REFERRAL imap://host1/ imap://host2/] ...\r\n",
+                b"",
+                Response::Status(
+                    Status::bye(
+                        Some(Code::Referral(
+                            Vec1::try_from(vec![
+                                ImapUrl::new("host1").unwrap(),
+                                ImapUrl::new("host2").unwrap(),
+                            ])
+                            .unwrap(),
+                        )),
+                        "...",
+                    )
+                    .unwrap(),
+                ),
+            ),
+            // Unsupported URLs are kept as is.
+            (
+                b"* BYE [REFERRAL imap://host/INBOX/;SECTION=1] ...\r\n",
+                b"",
+                Response::Status(
+                    Status::bye(
+                        Some(Code::Other(CodeOther::unvalidated(
+                            b"REFERRAL imap://host/INBOX/;SECTION=1".as_ref(),
+                        ))),
+                        "...",
+                    )
+                    .unwrap(),
+                ),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_code_other_round_trip() {
+        let tests = [
+            b"XFOO".as_ref(),
+            b"XFOO a b c",
+            b"XFOO (a (b) c)",
+            b"XFOO ((( ",
+            b"XFOO [a [b",
+            b"XFOO  a  b ",
+            b"XFOO \"a ) b\"",
+            b"XFOO {3}",
+            b"XFOO \\",
+            b"UIDNEXT abc",
+        ];
+
+        for test in tests {
+            let line = [b"* OK [".as_ref(), test, b"] text\r\n"].concat();
+
+            let (rem, (code, text)) = resp_text(ParseMode::Strict)(&line[5..]).unwrap();
+            assert_eq!(rem, b"\r\n");
+            assert_eq!(text.inner(), "text");
+            assert_eq!(code, Some(Code::Other(CodeOther::unvalidated(test))));
+
+            // Parse -> serialize -> parse
+            let (rem, got) = response(&ResponseParseOptions::default())(&line).unwrap();
+            assert!(rem.is_empty());
+            known_answer_test_encode((got.clone(), line.as_slice()));
+            let encoded = ResponseCodec::default().encode(&got).dump();
+            let (rem, got_again) = response(&ResponseParseOptions::default())(&encoded).unwrap();
+            assert!(rem.is_empty());
+            assert_eq!(got, got_again);
         }
     }
 
     #[test]
     fn test_parse_resp_space_quirk() {
-        assert!(response_data(b"* STATUS INBOX (MESSAGES 100 UNSEEN 0)\r\n").is_ok());
-        assert!(response_data(b"* STATUS INBOX (MESSAGES 100 UNSEEN 0)  \r\n").is_err());
+        assert!(response_data(&ResponseParseOptions::default())(
+            b"* STATUS INBOX (MESSAGES 100 UNSEEN 0)\r\n"
+        )
+        .is_ok());
+        assert!(response_data(&ResponseParseOptions::default())(
+            b"* STATUS INBOX (MESSAGES 100 UNSEEN 0)  \r\n"
+        )
+        .is_err());
 
         #[cfg(not(feature = "quirk_trailing_space"))]
         {
-            assert!(response_data(b"* STATUS INBOX (MESSAGES 100 UNSEEN 0) \r\n").is_err());
+            assert!(response_data(&ResponseParseOptions::default())(
+                b"* STATUS INBOX (MESSAGES 100 UNSEEN 0) \r\n"
+            )
+            .is_err());
         }
 
         #[cfg(feature = "quirk_trailing_space")]
         {
-            assert!(response_data(b"* STATUS INBOX (MESSAGES 100 UNSEEN 0) \r\n").is_ok());
+            assert!(response_data(&ResponseParseOptions::default())(
+                b"* STATUS INBOX (MESSAGES 100 UNSEEN 0) \r\n"
+            )
+            .is_ok());
+        }
+    }
+
+    #[test]
+    fn test_parse_resp_space_quirk_empty() {
+        let tests = [
+            (b"* SEARCH\r\n".as_ref(), Data::Search(vec![])),
+            (b"* SEARCH \r\n".as_ref(), Data::Search(vec![])),
+            (
+                b"* SEARCH 1 2 \r\n".as_ref(),
+                Data::Search(vec![1.try_into().unwrap(), 2.try_into().unwrap()]),
+            ),
+            (b"* FLAGS ()\r\n".as_ref(), Data::Flags(vec![])),
+            (b"* FLAGS () \r\n".as_ref(), Data::Flags(vec![])),
+            (
+                b"* STATUS box ()\r\n".as_ref(),
+                Data::Status {
+                    mailbox: Mailbox::try_from("box").unwrap(),
+                    items: vec![].into(),
+                },
+            ),
+            (
+                b"* STATUS box () \r\n".as_ref(),
+                Data::Status {
+                    mailbox: Mailbox::try_from("box").unwrap(),
+                    items: vec![].into(),
+                },
+            ),
+        ];
+
+        for (input, expected) in tests {
+            let got = response_data(&ResponseParseOptions::default())(input);
+
+            if cfg!(feature = "quirk_trailing_space") || !input.ends_with(b" \r\n") {
+                let (rem, got) = got.unwrap();
+                assert!(rem.is_empty());
+                assert_eq!(got, Response::Data(expected));
+            } else {
+                assert!(got.is_err());
+            }
         }
     }
+
+    #[test]
+    fn test_parse_response_mode() {
+        let tests = [
+            // Missing text
+            (
+                b"* OK\r\n".as_ref(),
+                Response::Status(Status::ok(None, None, "...").unwrap()),
+            ),
+            // Empty text
+            (
+                b"A1 OK \r\n".as_ref(),
+                Response::Status(
+                    Status::ok(Some(Tag::try_from("A1").unwrap()), None, "...").unwrap(),
+                ),
+            ),
+            // Missing space before code
+            (
+                b"* OK[ALERT] Hello\r\n".as_ref(),
+                Response::Status(Status::ok(None, Some(Code::Alert), "Hello").unwrap()),
+            ),
+            (
+                b"* BYE[ALERT] Bye\r\n".as_ref(),
+                Response::Status(Status::bye(Some(Code::Alert), "Bye").unwrap()),
+            ),
+        ];
+
+        for (test, expected) in tests {
+            assert!(response(&ResponseParseOptions::default())(test).is_err());
+
+            let (rem, got) = response(&ResponseParseOptions {
+                mode: ParseMode::Lax,
+                ..Default::default()
+            })(test)
+            .unwrap();
+            assert!(rem.is_empty());
+            assert_eq!(expected, got);
+        }
+    }
+
+    #[test]
+    fn test_parse_flags_vs_permanentflags() {
+        // `\*` is only allowed in PERMANENTFLAGS ...
+        let (rem, got) = response(&ResponseParseOptions::default())(
+            b"* OK [PERMANENTFLAGS (\\* \\Seen)] Limited\r\n",
+        )
+        .unwrap();
+        assert!(rem.is_empty());
+        assert_eq!(
+            got,
+            Response::Status(
+                Status::ok(
+                    None,
+                    Some(Code::PermanentFlags(vec![
+                        FlagPerm::Asterisk,
+                        FlagPerm::Flag(Flag::Seen),
+                    ])),
+                    "Limited",
+                )
+                .unwrap()
+            )
+        );
+
+        // ... but not in FLAGS.
+        for mode in [ParseMode::Strict, ParseMode::Lax] {
+            assert!(response(&ResponseParseOptions {
+                mode,
+                ..Default::default()
+            })(b"* FLAGS (\\* \\Seen)\r\n")
+            .is_err());
+            assert!(response(&ResponseParseOptions {
+                mode,
+                ..Default::default()
+            })(b"* FLAGS (\\Seen \\*)\r\n")
+            .is_err());
+        }
+
+        // Same for the types.
+        assert_eq!(FlagPerm::try_from("\\*"), Ok(FlagPerm::Asterisk));
+        assert_eq!(FlagPerm::try_from("\\Seen"), Ok(FlagPerm::Flag(Flag::Seen)));
+        assert!(Flag::try_from("\\*").is_err());
+    }
+
+    #[test]
+    fn test_kat_inverse_response_data_other() {
+        let other = |name: &'static str, value: &'static [u8]| {
+            Response::Data(Data::Other(DataOther::unvalidated(
+                Atom::try_from(name).unwrap(),
+                value,
+            )))
+        };
+
+        kat_inverse_response(&[
+            (b"* XFOO\r\n".as_ref(), b"".as_ref(), other("XFOO", b"")),
+            #[cfg(not(feature = "legacy"))]
+            (
+                b"* XLIST (\\Inbox) \"/\" INBOX\r\n",
+                b"",
+                other("XLIST", b"(\\Inbox) \"/\" INBOX"),
+            ),
+            #[cfg(not(feature = "legacy"))]
+            (
+                b"* XLIST (\\HasNoChildren) \"/\" {5}\r\nA\"B C\r\n",
+                b"",
+                other("XLIST", b"(\\HasNoChildren) \"/\" {5}\r\nA\"B C"),
+            ),
+        ]);
+
+        // Malformed known responses are still rejected ...
+        for test in [
+            b"* FLAGS (\\Seen\r\n".as_ref(),
+            b"* SEARCH a\r\n",
+            b"* 1 XFOO\r\n",
+        ] {
+            assert!(response(&ResponseParseOptions::default())(test).is_err());
+        }
+
+        // ... and literals are still announced.
+        assert_eq!(
+            ResponseCodec::default().decode(b"* XLIST () \"/\" {5}\r\n"),
+            Err(ResponseDecodeError::LiteralFound { length: 5 })
+        );
+    }
+
+    #[test]
+    fn test_parse_data_other_known_names() {
+        let tests = [
+            (b"XFOO 1\r\n".as_ref(), false),
+            (b"NAMESPACE NIL NIL NIL\r\n", false),
+            (b"OKAY\r\n", true),
+            (b"nope\r\n", true),
+            (b"PREAUTHED\r\n", true),
+            (b"flags ()\r\n", true),
+            (b"QUOTAROOT INBOX\r\n", true),
+            // Not implemented (even with `ext_acl` or `ext_esearch`).
+            (b"ACL INBOX alice lr\r\n", false),
+            (b"LISTRIGHTS INBOX alice lr\r\n", false),
+            (b"MYRIGHTS INBOX lr\r\n", false),
+            (b"ESEARCH (TAG \"A\") ALL 1\r\n", false),
+            (b"ID NIL\r\n", cfg!(feature = "ext_id")),
+            (b"METADATA INBOX (/a b)\r\n", cfg!(feature = "ext_metadata")),
+            (b"SORT 1\r\n", cfg!(feature = "ext_sort_thread")),
+            (b"THREAD (1)\r\n", cfg!(feature = "ext_sort_thread")),
+            (b"XLIST () \"/\" INBOX\r\n", cfg!(feature = "legacy")),
+        ];
+
+        for (test, known) in tests {
+            match data_other(&ResponseParseOptions::default())(test) {
+                Ok((rem, got)) => {
+                    assert!(
+                        !known,
+                        "{} must not be `Data::Other`",
+                        escape_byte_string(test)
+                    );
+                    assert_eq!(rem, b"\r\n");
+                    assert!(matches!(got, Data::Other(_)));
+                }
+                Err(_) => assert!(known, "{} must be `Data::Other`", escape_byte_string(test)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_response_unimplemented_extension_data() {
+        // Responses to commands of `ext_acl` and `ext_esearch` must still be readable.
+        let tests = [
+            (b"* ACL INBOX alice lrs\r\n".as_ref(), "ACL"),
+            (b"* LISTRIGHTS INBOX alice la r s\r\n", "LISTRIGHTS"),
+            (b"* MYRIGHTS INBOX lrs\r\n", "MYRIGHTS"),
+            (b"* ESEARCH (TAG \"A1\") MIN 2\r\n", "ESEARCH"),
+        ];
+
+        for (test, name) in tests {
+            let (rem, got) = ResponseCodec::default().decode(test).unwrap();
+            assert!(rem.is_empty());
+            assert!(
+                matches!(got, Response::Data(Data::Other(other)) if other.name().as_ref() == name)
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_data_other_depth_limit() {
+        let test = b"XFOO ((1))\r\n";
+
+        let options = ResponseParseOptions::default();
+        assert!(data_other(&options)(test).is_ok());
+
+        let options = ResponseParseOptions {
+            max_body_depth: 1,
+            ..Default::default()
+        };
+        assert!(data_other(&options)(test).is_err());
+    }
+
+    #[test]
+    fn test_parse_response_mode_lax_is_not_too_lax() {
+        let tests = [
+            b"* OKAY\r\n".as_ref(),
+            b"* OK\r\r\n".as_ref(),
+            b"A1OK Hello\r\n".as_ref(),
+        ];
+
+        for test in tests {
+            assert!(response(&ResponseParseOptions::default())(test).is_err());
+            assert!(response(&ResponseParseOptions {
+                mode: ParseMode::Lax,
+                ..Default::default()
+            })(test)
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn test_parse_response_mode_bare_lf() {
+        let tests = [
+            (
+                b"* 1 EXISTS\n".as_ref(),
+                Response::Data(Data::Exists(1)),
+                b"* 1 EXISTS\r\n".as_ref(),
+            ),
+            (
+                b"A1 NO Failed\n".as_ref(),
+                Response::Status(
+                    Status::no(Some(Tag::try_from("A1").unwrap()), None, "Failed").unwrap(),
+                ),
+                b"A1 NO Failed\r\n".as_ref(),
+            ),
+            (
+                b"* BYE Bye\n".as_ref(),
+                Response::Status(Status::bye(None, "Bye").unwrap()),
+                b"* BYE Bye\r\n".as_ref(),
+            ),
+            (
+                b"+ Ready\n".as_ref(),
+                Response::CommandContinuationRequest(
+                    CommandContinuationRequest::basic(None, "Ready").unwrap(),
+                ),
+                b"+ Ready\r\n".as_ref(),
+            ),
+            (
+                b"+ dGVzdA==\n".as_ref(),
+                Response::CommandContinuationRequest(CommandContinuationRequest::base64(
+                    b"test".as_ref(),
+                )),
+                b"+ dGVzdA==\r\n".as_ref(),
+            ),
+            (
+                b"* 1 FETCH (RFC822.SIZE 5)\n".as_ref(),
+                Response::Data(Data::Fetch {
+                    seq: NonZeroU32::new(1).unwrap(),
+                    items: Vec1::from(MessageDataItem::Rfc822Size(5)),
+                }),
+                b"* 1 FETCH (RFC822.SIZE 5)\r\n".as_ref(),
+            ),
+        ];
+
+        for (test, expected, encoded) in tests {
+            #[cfg(not(feature = "quirk_crlf_relaxed"))]
+            assert!(response(&ResponseParseOptions::default())(test).is_err());
+
+            let (rem, got) = response(&ResponseParseOptions {
+                mode: ParseMode::Lax,
+                ..Default::default()
+            })(test)
+            .unwrap();
+            assert!(rem.is_empty());
+            assert_eq!(expected, got);
+
+            // We always emit `\r\n`.
+            known_answer_test_encode((got, encoded));
+        }
+    }
+
+    #[test]
+    fn test_parse_response_mode_bare_lf_literal() {
+        // Literals require `\r\n` after `{n}` even in lax mode.
+        #[cfg(not(feature = "quirk_crlf_relaxed"))]
+        assert!(response(&ResponseParseOptions {
+            mode: ParseMode::Lax,
+            ..Default::default()
+        })(b"* 1 FETCH (RFC822 {5}\nhello)\n")
+        .is_err());
+
+        let (rem, got) = response(&ResponseParseOptions {
+            mode: ParseMode::Lax,
+            ..Default::default()
+        })(b"* 1 FETCH (RFC822 {5}\r\nhello)\n")
+        .unwrap();
+        assert!(rem.is_empty());
+        assert_eq!(
+            Response::Data(Data::Fetch {
+                seq: NonZeroU32::new(1).unwrap(),
+                items: Vec1::from(MessageDataItem::Rfc822(NString(Some(IString::Literal(
+                    Literal::try_from(b"hello".as_ref()).unwrap()
+                ))))),
+            }),
+            got
+        );
+    }
 }
@@ -12,6 +12,8 @@ use nom::{
     sequence::{delimited, tuple},
 };
 
+#[cfg(feature = "ext_esearch")]
+use crate::extensions::esearch::search_return_opts;
 use crate::{
     core::{astring, atom, charset, number},
     datetime::date,
@@ -20,14 +22,23 @@ use crate::{
     sequence::sequence_set,
 };
 
-/// `search = "SEARCH" [SP "CHARSET" SP charset] 1*(SP search-key)`
+/// ```abnf
+/// search = "SEARCH" [search-return-opts] [SP "CHARSET" SP charset] 1*(SP search-key)
+///                   ^^^^^^^^^^^^^^^^^^^^
+///                   |
+///                   RFC 4731
+/// ```
 ///
 /// Note: CHARSET argument MUST be registered with IANA
 ///
 /// errata id: 261
 pub(crate) fn search(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let (remaining, _) = tag_no_case(b"SEARCH")(input)?;
+
+    #[cfg(feature = "ext_esearch")]
+    let (remaining, return_options) = search_return_opts(remaining)?;
+
     let mut parser = tuple((
-        tag_no_case(b"SEARCH"),
         opt(map(
             tuple((sp, tag_no_case(b"CHARSET"), sp, charset)),
             |(_, _, _, charset)| charset,
@@ -36,13 +47,15 @@ pub(crate) fn search(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
         map(separated_list1(sp, search_key(9)), Vec1::unvalidated),
     ));
 
-    let (remaining, (_, charset, _, criteria)) = parser(input)?;
+    let (remaining, (charset, _, criteria)) = parser(remaining)?;
 
     Ok((
         remaining,
         CommandBody::Search {
             charset,
             criteria,
+            #[cfg(feature = "ext_esearch")]
+            return_options,
             uid: false,
         },
     ))
@@ -240,13 +253,15 @@ pub(crate) fn search_criteria(input: &[u8]) -> IMAPResult<&[u8], (Charset, Vec1<
 #[cfg(test)]
 mod tests {
     use imap_types::{
-        core::{AString, Atom},
+        command::Command,
+        core::{AString, Atom, Charset, Tag},
         datetime::NaiveDate,
+        response::{Code, Response, Status},
         sequence::{Sequence, SequenceSet},
     };
 
     use super::*;
-    use crate::testing::known_answer_test_encode;
+    use crate::testing::{kat_inverse_command, kat_inverse_response, known_answer_test_encode};
 
     #[test]
     fn test_parse_search() {
@@ -265,6 +280,8 @@ mod tests {
                         .try_into()
                         .unwrap()
                 ))))),
+                #[cfg(feature = "ext_esearch")]
+                return_options: vec![],
                 uid: false,
             }
         );
@@ -307,11 +324,62 @@ mod tests {
             ]
             .try_into()
             .unwrap())),
+            #[cfg(feature = "ext_esearch")]
+            return_options: vec![],
             uid: false,
         };
         assert_eq!(val, expected);
     }
 
+    #[test]
+    fn test_kat_inverse_command_search_charset() {
+        kat_inverse_command(&[
+            (
+                "A1 SEARCH CHARSET UTF-8 TEXT {4}\r\nmöp\r\n".as_bytes(),
+                b"".as_ref(),
+                Command::new(
+                    "A1",
+                    CommandBody::search(
+                        Some(Charset::try_from("UTF-8").unwrap()),
+                        Vec1::from(SearchKey::Text(AString::try_from("möp").unwrap())),
+                        false,
+                    ),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A1 SEARCH TEXT moep\r\n",
+                b"",
+                Command::new(
+                    "A1",
+                    CommandBody::search(
+                        None,
+                        Vec1::from(SearchKey::Text(AString::try_from("moep").unwrap())),
+                        false,
+                    ),
+                )
+                .unwrap(),
+            ),
+        ]);
+
+        // The server rejects an unsupported charset with a tagged NO and BADCHARSET.
+        kat_inverse_response(&[(
+            b"A1 NO [BADCHARSET (UTF-8 US-ASCII)] unsupported charset\r\n".as_ref(),
+            b"".as_ref(),
+            Response::Status(
+                Status::no(
+                    Some(Tag::try_from("A1").unwrap()),
+                    Some(Code::badcharset(vec![
+                        Charset::try_from("UTF-8").unwrap(),
+                        Charset::try_from("US-ASCII").unwrap(),
+                    ])),
+                    "unsupported charset",
+                )
+                .unwrap(),
+            ),
+        )]);
+    }
+
     #[test]
     fn test_parse_search_key() {
         assert!(search_key(1)(b"1:5|").is_ok());
@@ -10,6 +10,8 @@ use nom::{
     sequence::tuple,
 };
 
+#[cfg(feature = "ext_searchres")]
+use crate::extensions::searchres::seq_last_command;
 use crate::{core::nz_number, decode::IMAPResult};
 
 /// `sequence-set = (seq-number / seq-range) ["," sequence-set]`
@@ -31,6 +33,8 @@ use crate::{core::nz_number, decode::IMAPResult};
 /// Simplified:
 ///
 /// `sequence-set = (seq-number / seq-range) *("," (seq-number / seq-range))`
+///
+/// Note: With `ext_searchres`, `seq-last-command` (`$`) is accepted as a further alternative.
 pub(crate) fn sequence_set(input: &[u8]) -> IMAPResult<&[u8], SequenceSet> {
     map(
         separated_list1(
@@ -39,6 +43,8 @@ pub(crate) fn sequence_set(input: &[u8]) -> IMAPResult<&[u8], SequenceSet> {
                 // Ordering is important!
                 map(seq_range, |(from, to)| Sequence::Range(from, to)),
                 map(seq_number, Sequence::Single),
+                #[cfg(feature = "ext_searchres")]
+                seq_last_command,
             )),
         ),
         |set| SequenceSet(Vec1::unvalidated(set)),
@@ -109,6 +115,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_sequence_set_asterisk_round_trip() {
+        let tests = [
+            (b"*".as_ref(), Sequence::Single(SeqOrUid::Asterisk)),
+            (
+                b"1:*",
+                Sequence::Range(SeqOrUid::Value(1.try_into().unwrap()), SeqOrUid::Asterisk),
+            ),
+            (
+                b"*:1",
+                Sequence::Range(SeqOrUid::Asterisk, SeqOrUid::Value(1.try_into().unwrap())),
+            ),
+            (
+                b"*:*",
+                Sequence::Range(SeqOrUid::Asterisk, SeqOrUid::Asterisk),
+            ),
+        ];
+
+        for (test, expected) in tests {
+            let input = [test, b" "].concat();
+            let (rem, got) = sequence_set(&input).unwrap();
+            assert_eq!(rem, b" ");
+            assert_eq!(SequenceSet::from(expected), got);
+
+            let mut ctx = EncodeContext::new();
+            got.encode_ctx(&mut ctx).unwrap();
+            assert_eq!(test, ctx.dump());
+        }
+    }
+
     #[test]
     fn test_parse_sequence_set() {
         let (rem, val) = sequence_set(b"1:*?").unwrap();
@@ -118,6 +154,26 @@ mod tests {
         println!("{:?}, {:?}", rem, val);
     }
 
+    #[cfg(not(feature = "ext_searchres"))]
+    #[test]
+    fn test_parse_sequence_set_searchres_disabled() {
+        use crate::{decode::Decoder, CommandCodec};
+
+        // Without `ext_searchres`, `$` (RFC 5182) is rejected in all contexts.
+        assert!(sequence_set(b"$ ").is_err());
+        assert_eq!(sequence_set(b"1,$ ").unwrap().0, b",$ ");
+
+        for test in [
+            b"A STORE $ +FLAGS (\\Seen)\r\n".as_ref(),
+            b"A FETCH $ FLAGS\r\n",
+            b"A COPY $ INBOX\r\n",
+            b"A UID FETCH $ FLAGS\r\n",
+            b"A SEARCH $\r\n",
+        ] {
+            assert!(CommandCodec::default().decode(test).is_err());
+        }
+    }
+
     #[test]
     fn test_parse_seq_number() {
         // Must not be 0.
@@ -83,7 +83,6 @@ macro_rules! impl_kat_inverse {
 impl_kat_inverse! {kat_inverse_greeting, GreetingCodec, Greeting}
 impl_kat_inverse! {kat_inverse_command, CommandCodec, Command}
 impl_kat_inverse! {kat_inverse_response, ResponseCodec, Response}
-//impl_kat_inverse! {kat_inverse_continue, ContinueCodec, Continue}
 impl_kat_inverse! {kat_inverse_authenticate_data, AuthenticateDataCodec, AuthenticateData}
 impl_kat_inverse! {kat_inverse_done, IdleDoneCodec, IdleDone}
 
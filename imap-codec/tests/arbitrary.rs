@@ -0,0 +1,76 @@
+//! Encode arbitrary messages and check that they can be decoded again.
+//!
+//! This mirrors the `*_to_bytes_and_back` fuzz targets with a fixed seed, so that every test run
+//! exercises the encoders. Use [`reproduce`] to replay a (minimized) crash from `cargo fuzz`.
+
+use arbitrary::{Arbitrary, Error, Unstructured};
+use imap_codec::{
+    decode::Decoder,
+    encode::Encoder,
+    imap_types::response::{Greeting, Response},
+    GreetingCodec, ResponseCodec,
+};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+macro_rules! to_bytes_and_back {
+    ($codec:ident, $input:expr) => {{
+        let input = $input;
+        let buffer = $codec::default().encode(&input).dump();
+
+        let (rem, parsed) = $codec::default()
+            .decode(&buffer)
+            .unwrap_or_else(|_| panic!("failed to decode {input:?} from {buffer:?}"));
+        assert!(rem.is_empty());
+        // Note: `quirk_id_empty_to_nil` encodes `ID ()` as `ID NIL` on purpose.
+        #[cfg(not(feature = "quirk_id_empty_to_nil"))]
+        assert_eq!(input, parsed);
+        assert_eq!(buffer, $codec::default().encode(&parsed).dump());
+    }};
+}
+
+macro_rules! impl_test_to_bytes_and_back {
+    ($codec:ident, $object:ident) => {
+        let mut rng = SmallRng::seed_from_u64(1337);
+        let mut data = [0u8; 256];
+
+        // Randomize.
+        rng.try_fill(&mut data).unwrap();
+        let mut unstructured = Unstructured::new(&data);
+
+        let mut count = 0;
+        while count < 1_000 {
+            match $object::arbitrary(&mut unstructured) {
+                Ok(input) => {
+                    to_bytes_and_back!($codec, input);
+                    count += 1;
+                }
+                Err(Error::NotEnoughData | Error::IncorrectFormat) => {
+                    // Randomize.
+                    rng.try_fill(&mut data).unwrap();
+                    unstructured = Unstructured::new(&data);
+                }
+                Err(error) => panic!("{error}"),
+            }
+        }
+    };
+}
+
+/// Replay a crash found by a `*_to_bytes_and_back` fuzz target.
+///
+/// Copy the (minimized) artifact into `data` and call the function from a test, e.g.,
+/// `reproduce(include_bytes!("../fuzz/artifacts/response_to_bytes_and_back/crash-..."))`.
+#[allow(dead_code)]
+fn reproduce(data: &[u8]) {
+    let input = Response::arbitrary_take_rest(Unstructured::new(data)).unwrap();
+    to_bytes_and_back!(ResponseCodec, input);
+}
+
+#[test]
+fn test_arbitrary_greeting_to_bytes_and_back() {
+    impl_test_to_bytes_and_back!(GreetingCodec, Greeting);
+}
+
+#[test]
+fn test_arbitrary_response_to_bytes_and_back() {
+    impl_test_to_bytes_and_back!(ResponseCodec, Response);
+}
@@ -0,0 +1,59 @@
+//! Check the seed corpora in `fuzz/seeds` with the same invariant as the fuzz targets.
+
+use std::{fs, path::PathBuf};
+
+use imap_codec::{decode::Decoder, encode::Encoder, CommandCodec, GreetingCodec};
+
+fn seeds(target: &str) -> Vec<(PathBuf, Vec<u8>)> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("fuzz/seeds")
+        .join(target);
+
+    let mut seeds: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let data = fs::read(&path).unwrap();
+            (path, data)
+        })
+        .collect();
+    seeds.sort();
+    assert!(!seeds.is_empty());
+
+    seeds
+}
+
+macro_rules! check_seeds {
+    ($codec:ident, $target:literal) => {
+        for (path, input) in seeds($target) {
+            // Seeds named `invalid_*` are malformed on purpose.
+            let invalid = path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with("invalid_");
+
+            match $codec::default().decode(&input) {
+                Ok((_, parsed1)) => {
+                    assert!(!invalid, "{}", path.display());
+
+                    let output = $codec::default().encode(&parsed1).dump();
+                    let (rem, parsed2) = $codec::default().decode(&output).unwrap();
+                    assert!(rem.is_empty(), "{}", path.display());
+                    assert_eq!(parsed1, parsed2, "{}", path.display());
+                }
+                Err(_) => assert!(invalid, "{}", path.display()),
+            }
+        }
+    };
+}
+
+#[test]
+fn test_fuzz_seeds_command() {
+    check_seeds!(CommandCodec, "command");
+}
+
+#[test]
+fn test_fuzz_seeds_greeting() {
+    check_seeds!(GreetingCodec, "greeting");
+}
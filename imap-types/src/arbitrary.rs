@@ -1,6 +1,10 @@
 use arbitrary::{Arbitrary, Unstructured};
 use chrono::{FixedOffset, TimeZone};
 
+#[cfg(feature = "ext_acl")]
+use crate::extensions::acl::Rights;
+#[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
+use crate::extensions::referrals::ImapUrl;
 use crate::{
     auth::AuthMechanism,
     body::{
@@ -12,14 +16,15 @@ use crate::{
     },
     datetime::{DateTime, NaiveDate},
     extensions::{enable::CapabilityEnable, quota::Resource},
+    fetch::MessageDataItemOther,
     flag::{Flag, FlagNameAttribute},
     mailbox::{ListCharString, Mailbox, MailboxOther},
     response::{
-        Bye, Capability, Code, CodeOther, CommandContinuationRequestBasic, Greeting, GreetingKind,
-        Status, StatusBody, StatusKind, Tagged,
+        Bye, Capability, Code, CodeOther, CommandContinuationRequestBasic, DataOther, Greeting,
+        GreetingKind, Status, StatusBody, StatusKind, Tagged,
     },
     search::SearchKey,
-    sequence::SequenceSet,
+    sequence::{SequenceKind, SequenceSet, TypedSequenceSet},
 };
 #[cfg(not(feature = "arbitrary_simplified"))]
 use crate::{body::MultiPartExtensionData, envelope::Envelope};
@@ -70,9 +75,17 @@ impl_arbitrary_try_from! { MailboxOther<'a>, AString<'a> }
 impl_arbitrary_try_from! { CapabilityEnable<'a>, &str }
 impl_arbitrary_try_from! { Resource<'a>, &str }
 impl_arbitrary_try_from! { AuthMechanism<'a>, &str }
+#[cfg(feature = "ext_acl")]
+impl_arbitrary_try_from! { Rights<'a>, &str }
 impl_arbitrary_try_from_t! { Vec1<T>, Vec<T> }
 impl_arbitrary_try_from_t! { Vec2<T>, Vec<T> }
 
+impl<'a, K: SequenceKind> Arbitrary<'a> for TypedSequenceSet<K> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self::new(SequenceSet::arbitrary(u)?))
+    }
+}
+
 impl<'a> Arbitrary<'a> for CommandContinuationRequestBasic<'a> {
     fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
         Self::new(Option::<Code>::arbitrary(u)?, Text::arbitrary(u)?)
@@ -178,6 +191,56 @@ impl<'a> Arbitrary<'a> for CodeOther<'a> {
     }
 }
 
+impl<'a> Arbitrary<'a> for DataOther<'a> {
+    fn arbitrary(_: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        // `DataOther` is a fallback and should usually not be created.
+        Ok(DataOther::unvalidated(
+            Atom::unvalidated("IMAP-CODEC-DATA-OTHER"),
+            b"NIL".as_ref(),
+        ))
+    }
+}
+
+impl<'a> Arbitrary<'a> for MessageDataItemOther<'a> {
+    fn arbitrary(_: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        // `MessageDataItemOther` is a fallback and should usually not be created.
+        Ok(MessageDataItemOther::unvalidated(
+            Atom::unvalidated("IMAP-CODEC-ITEM-OTHER"),
+            b"NIL".as_ref(),
+        ))
+    }
+}
+
+#[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
+impl<'a> Arbitrary<'a> for ImapUrl<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let host = *u.choose(&["localhost", "imap.example.org", "127.0.0.1", "[::1]"])?;
+        let mut url = ImapUrl::new(host).unwrap();
+
+        // Note: Empty components are not representable.
+        if let Some(user) = Option::<&str>::arbitrary(u)?.filter(|user| !user.is_empty()) {
+            url = url.with_user(user);
+        }
+        if let Some(auth) = Option::<&str>::arbitrary(u)?.filter(|auth| !auth.is_empty()) {
+            url = url.with_auth(auth);
+        }
+        if let Some(port) = Option::<u16>::arbitrary(u)? {
+            url = url.with_port(port);
+        }
+        if let Some(mailbox) = Option::<&str>::arbitrary(u)?.filter(|mailbox| !mailbox.is_empty()) {
+            url = url.with_mailbox(mailbox);
+        }
+        if let Some(uid_validity) = Option::<std::num::NonZeroU32>::arbitrary(u)? {
+            url = url.with_uid_validity(uid_validity);
+        }
+        if let Some(uid) = Option::<std::num::NonZeroU32>::arbitrary(u)? {
+            url = url.with_uid(uid);
+        }
+
+        Ok(url)
+    }
+}
+
 impl<'a> Arbitrary<'a> for SearchKey<'a> {
     fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
         #[cfg(not(feature = "arbitrary_simplified"))]
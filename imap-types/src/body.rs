@@ -24,6 +24,32 @@ pub struct Body<'a> {
     pub specific: SpecificFields<'a>,
 }
 
+impl<'a> Body<'a> {
+    /// Get the (lowercased) media type and subtype, e.g., `("text", "plain")`.
+    pub fn media_type(&self) -> (String, String) {
+        fn lowercase(value: &IString) -> String {
+            String::from_utf8_lossy(value.as_ref()).to_ascii_lowercase()
+        }
+
+        match &self.specific {
+            SpecificFields::Basic { r#type, subtype } => (lowercase(r#type), lowercase(subtype)),
+            SpecificFields::Message { .. } => ("message".into(), "rfc822".into()),
+            SpecificFields::Text { subtype, .. } => ("text".into(), lowercase(subtype)),
+        }
+    }
+
+    /// Get the value of the `charset` parameter (if any), e.g., `UTF-8`.
+    ///
+    /// The parameter name is matched case-insensitively. The value is returned as-is.
+    pub fn charset(&self) -> Option<String> {
+        self.basic
+            .parameter_list
+            .iter()
+            .find(|(key, _)| key.as_ref().eq_ignore_ascii_case(b"charset"))
+            .map(|(_, value)| String::from_utf8_lossy(value.as_ref()).into_owned())
+    }
+}
+
 /// Basic fields of a non-multipart body part.
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
@@ -251,6 +277,115 @@ pub enum BodyStructure<'a> {
     },
 }
 
+impl<'a> BodyStructure<'a> {
+    /// Iterate over all leaf parts in depth-first order.
+    ///
+    /// Every leaf is yielded together with its part number, e.g., `[1, 2]` for `1.2`. Part numbers
+    /// follow RFC 3501, section 6.4.5:
+    ///
+    /// * A non-multipart message only has a part `1`.
+    /// * The parts of a multipart are numbered `1`, `2`, ... (prefixed with the number of the
+    ///   multipart itself when nested).
+    /// * A `message/rfc822` part is a container. The parts of the encapsulated message are
+    ///   numbered as if it were a top-level message, prefixed with the number of the
+    ///   `message/rfc822` part.
+    pub fn walk(&self) -> Walk<'_, 'a> {
+        let start = match self {
+            BodyStructure::Single { .. } => vec![1],
+            BodyStructure::Multi { .. } => vec![],
+        };
+
+        Walk {
+            stack: vec![(start, self)],
+        }
+    }
+
+    /// Find a (non-multipart) part by its part number, e.g., `[1, 2]` for `1.2`.
+    ///
+    /// Part numbers are interpreted as described in [`BodyStructure::walk`]. In contrast to
+    /// `walk`, this also finds `message/rfc822` parts.
+    pub fn find_part(&self, path: &[u32]) -> Option<&Body<'a>> {
+        fn find_in_message<'s, 'a>(
+            body_structure: &'s BodyStructure<'a>,
+            path: &[u32],
+        ) -> Option<&'s Body<'a>> {
+            let (number, rest) = path.split_first()?;
+
+            match body_structure {
+                BodyStructure::Single { .. } if *number == 1 => find_in_part(body_structure, rest),
+                BodyStructure::Single { .. } => None,
+                BodyStructure::Multi { .. } => find_in_part(body_structure, path),
+            }
+        }
+
+        fn find_in_part<'s, 'a>(
+            body_structure: &'s BodyStructure<'a>,
+            path: &[u32],
+        ) -> Option<&'s Body<'a>> {
+            match (body_structure, path.split_first()) {
+                (BodyStructure::Single { body, .. }, None) => Some(body),
+                (BodyStructure::Single { body, .. }, Some(_)) => match &body.specific {
+                    SpecificFields::Message { body_structure, .. } => {
+                        find_in_message(body_structure, path)
+                    }
+                    _ => None,
+                },
+                (BodyStructure::Multi { .. }, None) => None,
+                (BodyStructure::Multi { bodies, .. }, Some((number, rest))) => {
+                    let index = usize::try_from(number.checked_sub(1)?).ok()?;
+                    find_in_part(bodies.as_ref().get(index)?, rest)
+                }
+            }
+        }
+
+        if path.is_empty() {
+            return None;
+        }
+
+        find_in_message(self, path)
+    }
+}
+
+/// Iterator over the leaf parts of a [`BodyStructure`].
+///
+/// See [`BodyStructure::walk`].
+#[derive(Debug, Clone)]
+pub struct Walk<'s, 'a> {
+    stack: Vec<(Vec<u32>, &'s BodyStructure<'a>)>,
+}
+
+impl<'s, 'a> Iterator for Walk<'s, 'a> {
+    type Item = (Vec<u32>, &'s Body<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((path, body_structure)) = self.stack.pop() {
+            match body_structure {
+                BodyStructure::Single { body, .. } => match &body.specific {
+                    SpecificFields::Message { body_structure, .. } => {
+                        let path = match **body_structure {
+                            BodyStructure::Single { .. } => [path.as_slice(), &[1]].concat(),
+                            BodyStructure::Multi { .. } => path,
+                        };
+
+                        self.stack.push((path, body_structure));
+                    }
+                    _ => return Some((path, body)),
+                },
+                BodyStructure::Multi { bodies, .. } => {
+                    // Reversed, so that the first part is popped first.
+                    for (index, body_structure) in bodies.as_ref().iter().enumerate().rev() {
+                        let number = index as u32 + 1;
+                        self.stack
+                            .push(([path.as_slice(), &[number]].concat(), body_structure));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
 /// The extension data of a non-multipart body part.
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
@@ -346,3 +481,202 @@ pub enum BodyExtension<'a> {
     /// List.
     List(Vec1<BodyExtension<'a>>),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn basic() -> BasicFields<'static> {
+        BasicFields {
+            parameter_list: vec![],
+            id: NString(None),
+            description: NString(None),
+            content_transfer_encoding: IString::try_from("7bit").unwrap(),
+            size: 0,
+        }
+    }
+
+    fn single(specific: SpecificFields<'static>) -> BodyStructure<'static> {
+        BodyStructure::Single {
+            body: Body {
+                basic: basic(),
+                specific,
+            },
+            extension_data: None,
+        }
+    }
+
+    fn text(subtype: &'static str) -> BodyStructure<'static> {
+        single(SpecificFields::Text {
+            subtype: IString::try_from(subtype).unwrap(),
+            number_of_lines: 0,
+        })
+    }
+
+    fn image(subtype: &'static str) -> BodyStructure<'static> {
+        single(SpecificFields::Basic {
+            r#type: IString::try_from("image").unwrap(),
+            subtype: IString::try_from(subtype).unwrap(),
+        })
+    }
+
+    fn message(body_structure: BodyStructure<'static>) -> BodyStructure<'static> {
+        single(SpecificFields::Message {
+            envelope: Box::new(Envelope {
+                date: NString(None),
+                subject: NString(None),
+                from: vec![],
+                sender: vec![],
+                reply_to: vec![],
+                to: vec![],
+                cc: vec![],
+                bcc: vec![],
+                in_reply_to: NString(None),
+                message_id: NString(None),
+            }),
+            body_structure: Box::new(body_structure),
+            number_of_lines: 0,
+        })
+    }
+
+    fn multi(subtype: &'static str, bodies: Vec<BodyStructure<'static>>) -> BodyStructure<'static> {
+        BodyStructure::Multi {
+            bodies: Vec1::try_from(bodies).unwrap(),
+            subtype: IString::try_from(subtype).unwrap(),
+            extension_data: None,
+        }
+    }
+
+    fn subtype<'a>(body: &'a Body) -> &'a [u8] {
+        match &body.specific {
+            SpecificFields::Basic { subtype, .. } | SpecificFields::Text { subtype, .. } => {
+                subtype.as_ref()
+            }
+            SpecificFields::Message { .. } => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_walk() {
+        let tests = [
+            (text("plain"), vec![(vec![1], "plain")]),
+            (message(text("plain")), vec![(vec![1, 1], "plain")]),
+            (
+                multi(
+                    "mixed",
+                    vec![
+                        text("plain"),
+                        message(multi(
+                            "alternative",
+                            vec![
+                                text("plain"),
+                                multi("related", vec![text("html"), image("png")]),
+                            ],
+                        )),
+                        message(text("plain")),
+                        image("jpeg"),
+                    ],
+                ),
+                vec![
+                    (vec![1], "plain"),
+                    (vec![2, 1], "plain"),
+                    (vec![2, 2, 1], "html"),
+                    (vec![2, 2, 2], "png"),
+                    (vec![3, 1], "plain"),
+                    (vec![4], "jpeg"),
+                ],
+            ),
+        ];
+
+        for (test, expected) in tests {
+            let got: Vec<_> = test
+                .walk()
+                .map(|(path, body)| (path, std::str::from_utf8(subtype(body)).unwrap()))
+                .collect();
+            assert_eq!(got, expected);
+        }
+    }
+
+    #[test]
+    fn test_find_part() {
+        let body_structure = multi(
+            "mixed",
+            vec![
+                multi("alternative", vec![text("plain"), text("HTML")]),
+                message(multi("mixed", vec![text("plain"), image("png")])),
+                message(text("plain")),
+            ],
+        );
+
+        let tests = [
+            (vec![1, 1], Some(("text", "plain"))),
+            (vec![1, 2], Some(("text", "html"))),
+            (vec![2], Some(("message", "rfc822"))),
+            (vec![2, 1], Some(("text", "plain"))),
+            (vec![2, 2], Some(("image", "png"))),
+            (vec![3], Some(("message", "rfc822"))),
+            (vec![3, 1], Some(("text", "plain"))),
+            // Multiparts
+            (vec![1], None),
+            // Out of range
+            (vec![], None),
+            (vec![0], None),
+            (vec![4], None),
+            (vec![1, 3], None),
+            (vec![1, 1, 1], None),
+            (vec![3, 2], None),
+        ];
+
+        for (path, expected) in tests {
+            let got = body_structure.find_part(&path).map(Body::media_type);
+            assert_eq!(
+                got,
+                expected.map(|(r#type, subtype)| (r#type.to_owned(), subtype.to_owned())),
+                "{path:?}"
+            );
+        }
+
+        // A non-multipart message only has a part `1`.
+        let body_structure = text("plain");
+        assert!(body_structure.find_part(&[1]).is_some());
+        assert!(body_structure.find_part(&[2]).is_none());
+        assert!(body_structure.find_part(&[1, 1]).is_none());
+
+        // `walk` and `find_part` agree.
+        let body_structure = multi("mixed", vec![text("plain"), message(image("gif"))]);
+        for (path, body) in body_structure.walk() {
+            assert_eq!(body_structure.find_part(&path), Some(body));
+        }
+    }
+    #[test]
+    fn test_charset() {
+        let mut body = Body {
+            basic: basic(),
+            specific: SpecificFields::Text {
+                subtype: IString::try_from("plain").unwrap(),
+                number_of_lines: 0,
+            },
+        };
+        assert_eq!(body.charset(), None);
+
+        body.basic.parameter_list = vec![(
+            IString::try_from("format").unwrap(),
+            IString::try_from("flowed").unwrap(),
+        )];
+        assert_eq!(body.charset(), None);
+
+        for key in ["charset", "CHARSET", "ChArSeT"] {
+            body.basic.parameter_list = vec![
+                (
+                    IString::try_from("format").unwrap(),
+                    IString::try_from("flowed").unwrap(),
+                ),
+                (
+                    IString::try_from(key).unwrap(),
+                    IString::try_from("ISO-8859-1").unwrap(),
+                ),
+            ];
+            assert_eq!(body.charset(), Some("ISO-8859-1".to_owned()));
+        }
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! See <https://tools.ietf.org/html/rfc3501#section-6>.
 
-use std::borrow::Cow;
+use std::{borrow::Cow, convert::Infallible};
 
 #[cfg(feature = "arbitrary")]
 use arbitrary::Arbitrary;
@@ -13,15 +13,21 @@ use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "ext_id")]
 use crate::core::{IString, NString};
+#[cfg(feature = "ext_acl")]
+use crate::extensions::acl::ModRights;
 #[cfg(feature = "ext_binary")]
 use crate::extensions::binary::LiteralOrLiteral8;
+#[cfg(feature = "ext_esearch")]
+use crate::extensions::esearch::SearchReturnOption;
 #[cfg(feature = "ext_metadata")]
 use crate::extensions::metadata::{Entry, EntryValue, GetMetadataOption};
 #[cfg(feature = "ext_sort_thread")]
 use crate::extensions::{sort::SortCriterion, thread::ThreadingAlgorithm};
 use crate::{
     auth::AuthMechanism,
-    command::error::{AppendError, CopyError, ListError, LoginError, RenameError},
+    command::error::{
+        AppendError, AppendLimitError, CopyError, ListError, LoginError, RenameError, StoreError,
+    },
     core::{AString, Charset, Literal, Tag, Vec1},
     datetime::DateTime,
     extensions::{compress::CompressionAlgorithm, enable::CapabilityEnable, quota::QuotaSet},
@@ -30,7 +36,7 @@ use crate::{
     mailbox::{ListMailbox, Mailbox},
     search::SearchKey,
     secret::Secret,
-    sequence::SequenceSet,
+    sequence::{SequenceKind, SequenceSet, TypedSequenceSet},
     status::StatusDataItemName,
 };
 
@@ -138,6 +144,10 @@ pub enum CommandBody<'a> {
     /// the connection.  The server MUST send a BYE untagged response
     /// before the (tagged) OK response, and then close the network
     /// connection.
+    ///
+    /// Note: There is no dedicated response type for this exchange. The BYE is received as an
+    /// [`Status::Bye`](crate::response::Status::Bye) and the completion result as a
+    /// [`Status::Tagged`](crate::response::Status::Tagged).
     Logout,
 
     // ----- Not Authenticated State (https://tools.ietf.org/html/rfc3501#section-6.2) -----
@@ -166,6 +176,10 @@ pub enum CommandBody<'a> {
     /// the-middle attacks which alter the capabilities list prior to
     /// STARTTLS.  The server MAY advertise different capabilities after
     /// STARTTLS.
+    ///
+    /// Note: The codecs don't do TLS. After the tagged OK, the transport must be upgraded by the
+    /// caller. Any data that was received (but not yet processed) before the upgrade must be
+    /// discarded to prevent command injection.
     #[cfg(feature = "starttls")]
     #[cfg_attr(docsrs, doc(cfg(feature = "starttls")))]
     StartTLS,
@@ -1051,10 +1065,20 @@ pub enum CommandBody<'a> {
     /// "XXXXXX" is a placeholder for what would be 6 octets of
     /// 8-bit data in an actual transaction.
     Search {
-        /// Charset.
+        /// Charset, e.g., `CHARSET UTF-8`.
+        ///
+        /// If the server doesn't support the charset, it responds with a tagged NO and
+        /// [`Code::BadCharset`](crate::response::Code::BadCharset).
         charset: Option<Charset<'a>>,
         /// Criteria.
         criteria: Vec1<SearchKey<'a>>,
+        /// Result options (RFC 4731).
+        ///
+        /// An empty list means a "plain" SEARCH. Note: `RETURN ()` is parsed as `RETURN (ALL)`,
+        /// which is equivalent.
+        #[cfg(feature = "ext_esearch")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "ext_esearch")))]
+        return_options: Vec<SearchReturnOption>,
         /// Use UID variant.
         uid: bool,
     },
@@ -1460,17 +1484,95 @@ pub enum CommandBody<'a> {
     },
 
     #[cfg(feature = "ext_metadata")]
+    /// SETMETADATA command (RFC 5464).
+    ///
+    /// A `NIL` value removes the entry.
     SetMetadata {
+        /// Mailbox (or `""` for server annotations).
         mailbox: Mailbox<'a>,
+        /// Entries and their values.
         entry_values: Vec1<EntryValue<'a>>,
     },
 
     #[cfg(feature = "ext_metadata")]
+    /// GETMETADATA command (RFC 5464).
     GetMetadata {
+        /// Options, e.g., `MAXSIZE` and `DEPTH`.
         options: Vec<GetMetadataOption>,
+        /// Mailbox (or `""` for server annotations).
         mailbox: Mailbox<'a>,
+        /// Requested entries.
         entries: Vec1<Entry<'a>>,
     },
+
+    #[cfg(feature = "ext_acl")]
+    /// SETACL command (RFC 4314).
+    ///
+    /// Changes the access control list of a mailbox.
+    SetAcl {
+        /// Mailbox.
+        mailbox: Mailbox<'a>,
+        /// Identifier, e.g., a user name or "anyone".
+        identifier: AString<'a>,
+        /// Added, removed, or replaced rights.
+        mod_rights: ModRights<'a>,
+    },
+
+    #[cfg(feature = "ext_acl")]
+    /// DELETEACL command (RFC 4314).
+    ///
+    /// Removes an identifier from the access control list of a mailbox.
+    DeleteAcl {
+        /// Mailbox.
+        mailbox: Mailbox<'a>,
+        /// Identifier.
+        identifier: AString<'a>,
+    },
+
+    #[cfg(feature = "ext_acl")]
+    /// GETACL command (RFC 4314).
+    ///
+    /// Requests the access control list of a mailbox.
+    GetAcl {
+        /// Mailbox.
+        mailbox: Mailbox<'a>,
+    },
+
+    #[cfg(feature = "ext_acl")]
+    /// LISTRIGHTS command (RFC 4314).
+    ///
+    /// Requests the rights that may be granted to an identifier.
+    ListRights {
+        /// Mailbox.
+        mailbox: Mailbox<'a>,
+        /// Identifier.
+        identifier: AString<'a>,
+    },
+
+    #[cfg(feature = "ext_acl")]
+    /// MYRIGHTS command (RFC 4314).
+    ///
+    /// Requests the rights the user has on a mailbox.
+    MyRights {
+        /// Mailbox.
+        mailbox: Mailbox<'a>,
+    },
+
+    #[cfg(feature = "legacy")]
+    /// XLIST command (legacy, e.g., older Gmail servers).
+    ///
+    /// Takes the same arguments as LIST but returns XLIST responses that include special-use
+    /// attributes, e.g., `\Inbox` or `\AllMail`. Superseded by LIST-EXTENDED ([RFC 5258]) and
+    /// SPECIAL-USE ([RFC 6154]).
+    ///
+    /// [RFC 5258]: https://datatracker.ietf.org/doc/html/rfc5258
+    /// [RFC 6154]: https://datatracker.ietf.org/doc/html/rfc6154
+    XList {
+        /// Reference.
+        reference: Mailbox<'a>,
+        /// Mailbox (wildcard).
+        mailbox_wildcard: ListMailbox<'a>,
+    },
 }
 
 impl<'a> CommandBody<'a> {
@@ -1550,6 +1652,22 @@ impl<'a> CommandBody<'a> {
         })
     }
 
+    /// Construct the CREATE command for the destination mailbox of an APPEND, COPY, or MOVE.
+    ///
+    /// Use this when the server rejected one of these commands with `NO [TRYCREATE]`
+    /// (see [`Status::is_trycreate`](crate::response::Status::is_trycreate)) and retry the
+    /// original command after the mailbox was created. Returns `None` for other commands.
+    pub fn create_for(&self) -> Option<CommandBody<'a>> {
+        match self {
+            Self::Append { mailbox, .. }
+            | Self::Copy { mailbox, .. }
+            | Self::Move { mailbox, .. } => Some(CommandBody::Create {
+                mailbox: mailbox.clone(),
+            }),
+            _ => None,
+        }
+    }
+
     /// Construct a DELETE command.
     pub fn delete<M>(mailbox: M) -> Result<Self, M::Error>
     where
@@ -1658,11 +1776,63 @@ impl<'a> CommandBody<'a> {
         })
     }
 
+    /// Check that an APPEND message doesn't exceed the server's `APPENDLIMIT` (RFC 7889).
+    ///
+    /// Use this before sending the command. The server would reject a larger message anyway,
+    /// but only after the client started sending the literal. Other commands are always accepted.
+    pub fn check_append_limit(&self, limit: u32) -> Result<(), AppendLimitError> {
+        if let CommandBody::Append { message, .. } = self {
+            #[cfg(not(feature = "ext_binary"))]
+            let size = message.data().len();
+            #[cfg(feature = "ext_binary")]
+            let size = match message {
+                LiteralOrLiteral8::Literal(literal) => literal.data().len(),
+                LiteralOrLiteral8::Literal8(literal8) => literal8.data.len(),
+            };
+
+            if size > limit as usize {
+                return Err(AppendLimitError { size, limit });
+            }
+        }
+
+        Ok(())
+    }
+
     /// Construct a SEARCH command.
     pub fn search(charset: Option<Charset<'a>>, criteria: Vec1<SearchKey<'a>>, uid: bool) -> Self {
         CommandBody::Search {
             charset,
             criteria,
+            #[cfg(feature = "ext_esearch")]
+            return_options: vec![],
+            uid,
+        }
+    }
+
+    /// Construct a SEARCH or UID SEARCH command.
+    ///
+    /// `K` is the kind of the numbers in the result, i.e., [`Seq`](crate::sequence::Seq) or
+    /// [`Uid`](crate::sequence::Uid). Use `SearchKey::from` to search for a [`TypedSequenceSet`].
+    pub fn search_typed<K>(charset: Option<Charset<'a>>, criteria: Vec1<SearchKey<'a>>) -> Self
+    where
+        K: SequenceKind,
+    {
+        Self::search(charset, criteria, K::UID)
+    }
+
+    /// Construct an extended SEARCH command with result options (RFC 4731).
+    #[cfg(feature = "ext_esearch")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_esearch")))]
+    pub fn esearch(
+        charset: Option<Charset<'a>>,
+        criteria: Vec1<SearchKey<'a>>,
+        return_options: Vec<SearchReturnOption>,
+        uid: bool,
+    ) -> Self {
+        CommandBody::Search {
+            charset,
+            criteria,
+            return_options,
             uid,
         }
     }
@@ -1682,18 +1852,37 @@ impl<'a> CommandBody<'a> {
         })
     }
 
+    /// Construct a FETCH or UID FETCH command from a [`TypedSequenceSet`].
+    pub fn fetch_typed<K, I>(sequence_set: TypedSequenceSet<K>, macro_or_item_names: I) -> Self
+    where
+        K: SequenceKind,
+        I: Into<MacroOrMessageDataItemNames<'a>>,
+    {
+        CommandBody::Fetch {
+            sequence_set: sequence_set.into(),
+            macro_or_item_names: macro_or_item_names.into(),
+            uid: K::UID,
+        }
+    }
+
     /// Construct a STORE command.
+    ///
+    /// Fails when `flags` contains `\Recent` (see [`Flag::is_recent`]).
     pub fn store<S>(
         sequence_set: S,
         kind: StoreType,
         response: StoreResponse,
         flags: Vec<Flag<'a>>,
         uid: bool,
-    ) -> Result<Self, S::Error>
+    ) -> Result<Self, StoreError<S::Error>>
     where
         S: TryInto<SequenceSet>,
     {
-        let sequence_set = sequence_set.try_into()?;
+        let sequence_set = sequence_set.try_into().map_err(StoreError::Sequence)?;
+
+        if flags.iter().any(Flag::is_recent) {
+            return Err(StoreError::Recent);
+        }
 
         Ok(CommandBody::Store {
             sequence_set,
@@ -1704,6 +1893,31 @@ impl<'a> CommandBody<'a> {
         })
     }
 
+    /// Construct a STORE or UID STORE command from a [`TypedSequenceSet`].
+    ///
+    /// Fails when `flags` contains `\Recent` (see [`Flag::is_recent`]).
+    pub fn store_typed<K>(
+        sequence_set: TypedSequenceSet<K>,
+        kind: StoreType,
+        response: StoreResponse,
+        flags: Vec<Flag<'a>>,
+    ) -> Result<Self, StoreError<Infallible>>
+    where
+        K: SequenceKind,
+    {
+        if flags.iter().any(Flag::is_recent) {
+            return Err(StoreError::Recent);
+        }
+
+        Ok(CommandBody::Store {
+            sequence_set: sequence_set.into(),
+            kind,
+            response,
+            flags,
+            uid: K::UID,
+        })
+    }
+
     /// Construct a COPY command.
     pub fn copy<S, M>(
         sequence_set: S,
@@ -1721,6 +1935,19 @@ impl<'a> CommandBody<'a> {
         })
     }
 
+    /// Construct a COPY or UID COPY command from a [`TypedSequenceSet`].
+    pub fn copy_typed<K, M>(sequence_set: TypedSequenceSet<K>, mailbox: M) -> Result<Self, M::Error>
+    where
+        K: SequenceKind,
+        M: TryInto<Mailbox<'a>>,
+    {
+        Ok(CommandBody::Copy {
+            sequence_set: sequence_set.into(),
+            mailbox: mailbox.try_into()?,
+            uid: K::UID,
+        })
+    }
+
     /// Get the name of the command.
     pub fn name(&self) -> &'static str {
         match self {
@@ -1769,6 +1996,18 @@ impl<'a> CommandBody<'a> {
             Self::SetMetadata { .. } => "SETMETADATA",
             #[cfg(feature = "ext_metadata")]
             Self::GetMetadata { .. } => "GETMETADATA",
+            #[cfg(feature = "ext_acl")]
+            Self::SetAcl { .. } => "SETACL",
+            #[cfg(feature = "ext_acl")]
+            Self::DeleteAcl { .. } => "DELETEACL",
+            #[cfg(feature = "ext_acl")]
+            Self::GetAcl { .. } => "GETACL",
+            #[cfg(feature = "ext_acl")]
+            Self::ListRights { .. } => "LISTRIGHTS",
+            #[cfg(feature = "ext_acl")]
+            Self::MyRights { .. } => "MYRIGHTS",
+            #[cfg(feature = "legacy")]
+            Self::XList { .. } => "XLIST",
         }
     }
 }
@@ -1809,6 +2048,23 @@ pub mod error {
         Data(D),
     }
 
+    #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+    #[error("Message size {size} exceeds APPENDLIMIT {limit}")]
+    pub struct AppendLimitError {
+        /// Size of the message.
+        pub size: usize,
+        /// Limit advertised by the server.
+        pub limit: u32,
+    }
+
+    #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+    pub enum StoreError<S> {
+        #[error("Invalid sequence: {0}")]
+        Sequence(S),
+        #[error("The \\Recent flag can't be stored")]
+        Recent,
+    }
+
     #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
     pub enum CopyError<S, M> {
         #[error("Invalid sequence: {0}")]
@@ -1836,7 +2092,7 @@ mod tests {
         mailbox::{ListMailbox, Mailbox},
         search::SearchKey,
         secret::Secret,
-        sequence::{SeqOrUid, Sequence, SequenceSet},
+        sequence::{SeqOrUid, Sequence, SequenceSet, UidSequenceSet},
         status::StatusDataItemName,
     };
     #[cfg(feature = "ext_binary")]
@@ -2140,6 +2396,8 @@ mod tests {
                 CommandBody::Search {
                     charset: None,
                     criteria: Vec1::from(SearchKey::Recent),
+                    #[cfg(feature = "ext_esearch")]
+                    return_options: vec![],
                     uid: true,
                 },
                 "SEARCH",
@@ -2216,4 +2474,66 @@ mod tests {
             assert_eq!(test.name(), expected);
         }
     }
+
+    #[test]
+    fn test_check_append_limit() {
+        let append = CommandBody::append(Mailbox::Inbox, vec![], None, "Hello, World!").unwrap();
+
+        assert_eq!(append.check_append_limit(13), Ok(()));
+        assert_eq!(append.check_append_limit(u32::MAX), Ok(()));
+        assert_eq!(
+            append.check_append_limit(12),
+            Err(AppendLimitError {
+                size: 13,
+                limit: 12
+            })
+        );
+
+        #[cfg(feature = "ext_binary")]
+        {
+            let append = CommandBody::Append {
+                mailbox: Mailbox::Inbox,
+                flags: vec![],
+                date: None,
+                message: LiteralOrLiteral8::Literal8(Literal8 {
+                    data: b"Hello\x00World\x00".as_ref().into(),
+                    mode: LiteralMode::Sync,
+                }),
+            };
+
+            assert_eq!(append.check_append_limit(12), Ok(()));
+            assert!(append.check_append_limit(11).is_err());
+        }
+
+        assert_eq!(CommandBody::Noop.check_append_limit(0), Ok(()));
+    }
+
+    #[test]
+    fn test_store_recent() {
+        let recent = Flag::try_from("\\Recent").unwrap();
+
+        assert_eq!(
+            CommandBody::store(
+                "1",
+                StoreType::Add,
+                StoreResponse::Answer,
+                vec![Flag::Seen, recent.clone()],
+                false,
+            ),
+            Err(StoreError::Recent)
+        );
+        assert_eq!(
+            CommandBody::store_typed(
+                UidSequenceSet::try_from("1").unwrap(),
+                StoreType::Remove,
+                StoreResponse::Silent,
+                vec![recent],
+            ),
+            Err(StoreError::Recent)
+        );
+        assert!(matches!(
+            CommandBody::store("0", StoreType::Add, StoreResponse::Answer, vec![], false),
+            Err(StoreError::Sequence(_))
+        ));
+    }
 }
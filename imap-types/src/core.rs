@@ -137,6 +137,13 @@ impl<'a> Atom<'a> {
         self.0
     }
 
+    /// Compares the atom with `other`, ignoring ASCII case.
+    ///
+    /// Note: `PartialEq` is exact, i.e., `CAPABILITY` and `capability` are different atoms.
+    pub fn eq_ignore_case(&self, other: &str) -> bool {
+        self.0.eq_ignore_ascii_case(other)
+    }
+
     /// Constructs an atom without validation.
     ///
     /// # Warning: IMAP conformance
@@ -393,6 +400,43 @@ pub enum IString<'a> {
 }
 
 impl<'a> IString<'a> {
+    /// Maximum length of a string represented as a quoted string by [`IString::from_bytes`].
+    pub const MAX_QUOTED_LENGTH: usize = 1024;
+
+    /// Create a string and choose its representation automatically.
+    ///
+    /// Short text without `CR`, `LF`, or 8-bit bytes is represented as a quoted string.
+    /// Everything else, i.e., the empty string, strings longer than [`Self::MAX_QUOTED_LENGTH`],
+    /// and strings with characters not allowed in a quoted string, is represented as a literal.
+    ///
+    /// Note: This fails when `value` contains a NUL byte, which is not allowed in either form.
+    pub fn from_bytes<C>(value: C) -> Result<Self, ValidationError>
+    where
+        C: Into<Cow<'a, [u8]>>,
+    {
+        let value = value.into();
+
+        let quotable = !value.is_empty()
+            && value.len() <= Self::MAX_QUOTED_LENGTH
+            && Quoted::validate(&value).is_ok();
+
+        if quotable {
+            let quoted = match value {
+                Cow::Borrowed(value) => Quoted::try_from(value)?,
+                Cow::Owned(value) => Quoted::try_from(value)?,
+            };
+
+            Ok(Self::Quoted(quoted))
+        } else {
+            let literal = match value {
+                Cow::Borrowed(value) => Literal::try_from(value)?,
+                Cow::Owned(value) => Literal::try_from(value)?,
+            };
+
+            Ok(Self::Literal(literal))
+        }
+    }
+
     pub fn into_inner(self) -> Cow<'a, [u8]> {
         match self {
             Self::Literal(literal) => literal.into_inner(),
@@ -1047,6 +1091,10 @@ impl<'a> Tag<'a> {
         self.0.as_ref()
     }
 
+    pub fn into_inner(self) -> Cow<'a, str> {
+        self.0
+    }
+
     /// Constructs a tag without validation.
     ///
     /// # Warning: IMAP conformance
@@ -1666,6 +1714,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_atom_eq_ignore_case() {
+        let atom = Atom::try_from("CAPABILITY").unwrap();
+
+        assert!(atom.eq_ignore_case("CAPABILITY"));
+        assert!(atom.eq_ignore_case("capability"));
+        assert!(atom.eq_ignore_case("CaPaBiLiTy"));
+        assert!(!atom.eq_ignore_case("CAPABILITIES"));
+        assert!(!atom.eq_ignore_case(""));
+
+        assert_eq!(atom, Atom::try_from("CAPABILITY").unwrap());
+        assert_ne!(atom, Atom::try_from("capability").unwrap());
+    }
+
     #[test]
     fn test_conversion_atom_ext() {
         #[allow(clippy::type_complexity)]
@@ -1957,4 +2019,33 @@ mod tests {
         assert!(VecN::<u8, 2>::try_from(vec![1]).is_err());
         assert!(VecN::<u8, 2>::try_from(vec![1, 2]).is_ok());
     }
+
+    #[test]
+    fn test_istring_from_bytes() {
+        let long = vec![b'A'; IString::MAX_QUOTED_LENGTH + 1];
+
+        let tests: Vec<(&[u8], bool)> = vec![
+            (b"", false),
+            (b"hi", true),
+            (b"Hello \"World\"", true),
+            (b"\\", true),
+            (b"a\r\nb", false),
+            (b"a\nb", false),
+            (b"\xff", false),
+            (&long[..IString::MAX_QUOTED_LENGTH], true),
+            (&long, false),
+        ];
+
+        for (test, quoted) in tests {
+            let got = IString::from_bytes(test).unwrap();
+            assert_eq!(quoted, matches!(got, IString::Quoted(_)));
+            assert_eq!(test, got.as_ref());
+
+            let got = IString::from_bytes(test.to_vec()).unwrap();
+            assert_eq!(quoted, matches!(got, IString::Quoted(_)));
+            assert_eq!(test, got.as_ref());
+        }
+
+        assert!(IString::from_bytes(b"a\x00b".as_ref()).is_err());
+    }
 }
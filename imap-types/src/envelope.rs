@@ -4,6 +4,7 @@
 use arbitrary::Arbitrary;
 #[cfg(feature = "bounded-static")]
 use bounded_static::ToStatic;
+use chrono::{DateTime as ChronoDateTime, FixedOffset};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -26,6 +27,26 @@ pub struct Envelope<'a> {
     pub message_id: NString<'a>,
 }
 
+impl<'a> Envelope<'a> {
+    /// Parse the `date` field as an RFC 2822 date (best effort).
+    ///
+    /// The `date` field is a copy of the `Date:` header and is often malformed in real-world
+    /// messages. Thus, it is kept as a string and this returns `None` when the date can't be parsed.
+    pub fn parsed_date(&self) -> Option<ChronoDateTime<FixedOffset>> {
+        let date = std::str::from_utf8(self.date.0.as_ref()?.as_ref())
+            .ok()?
+            .trim();
+
+        if let Ok(parsed) = ChronoDateTime::parse_from_rfc2822(date) {
+            return Some(parsed);
+        }
+
+        // Retry without a trailing comment, e.g., `... +0000 (UTC)`.
+        let (date, _) = date.rsplit_once('(')?;
+        ChronoDateTime::parse_from_rfc2822(date.trim_end()).ok()
+    }
+}
+
 /// An address structure describes an electronic mail address.
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
@@ -53,3 +74,59 @@ pub struct Address<'a> {
     /// Host name
     pub host: NString<'a>,
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn envelope(date: NString<'static>) -> Envelope<'static> {
+        Envelope {
+            date,
+            subject: NString(None),
+            from: vec![],
+            sender: vec![],
+            reply_to: vec![],
+            to: vec![],
+            cc: vec![],
+            bcc: vec![],
+            in_reply_to: NString(None),
+            message_id: NString(None),
+        }
+    }
+
+    #[test]
+    fn test_envelope_parsed_date() {
+        let expected = FixedOffset::east_opt(2 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2023, 7, 17, 13, 37, 0)
+            .unwrap();
+
+        let tests = [
+            "Mon, 17 Jul 2023 13:37:00 +0200",
+            "17 Jul 2023 13:37:00 +0200",
+            "  Mon, 17 Jul 2023 13:37:00 +0200  ",
+            "Mon, 17 Jul 2023 13:37:00 +0200 (CEST)",
+        ];
+
+        for test in tests {
+            let got = envelope(NString::try_from(test).unwrap()).parsed_date();
+            assert_eq!(got, Some(expected), "{test}");
+        }
+
+        let tests = [
+            "",
+            "garbage",
+            "2023-07-17 13:37:00",
+            "Mon, 32 Jul 2023 13:37:00 +0200",
+        ];
+
+        for test in tests {
+            let got = envelope(NString::try_from(test).unwrap()).parsed_date();
+            assert_eq!(got, None, "{test}");
+        }
+
+        assert_eq!(envelope(NString(None)).parsed_date(), None);
+    }
+}
@@ -1,14 +1,20 @@
 //! IMAP extensions.
 
+#[cfg(feature = "ext_acl")]
+pub mod acl;
 #[cfg(feature = "ext_binary")]
 pub mod binary;
 pub mod compress;
 pub mod enable;
+#[cfg(feature = "ext_esearch")]
+pub mod esearch;
 pub mod idle;
 #[cfg(feature = "ext_metadata")]
 pub mod metadata;
 pub mod r#move;
 pub mod quota;
+#[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
+pub mod referrals;
 #[cfg(feature = "ext_sort_thread")]
 pub mod sort;
 #[cfg(feature = "ext_sort_thread")]
@@ -0,0 +1,189 @@
+//! IMAP4 Access Control List (ACL) Extension
+//!
+//! This extends ...
+//!
+//! * [`Capability`](crate::response::Capability) with a new variant:
+//!
+//!     - [`Capability::Acl`](crate::response::Capability::Acl)
+//!
+//! * [`CommandBody`](crate::command::CommandBody) with new variants:
+//!
+//!     - [`CommandBody::SetAcl`](crate::command::CommandBody::SetAcl)
+//!     - [`CommandBody::DeleteAcl`](crate::command::CommandBody::DeleteAcl)
+//!     - [`CommandBody::GetAcl`](crate::command::CommandBody::GetAcl)
+//!     - [`CommandBody::ListRights`](crate::command::CommandBody::ListRights)
+//!     - [`CommandBody::MyRights`](crate::command::CommandBody::MyRights)
+
+use std::{
+    borrow::Cow,
+    fmt::{Display, Formatter},
+};
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ValidationError, ValidationErrorKind};
+
+/// A set of rights, e.g., `lrswi`.
+///
+/// Every right is a lower-case letter or a digit (RFC 4314, section 2.1). The set may be empty.
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rights<'a>(Cow<'a, str>);
+
+impl<'a> Rights<'a> {
+    pub fn validate(value: impl AsRef<[u8]>) -> Result<(), ValidationError> {
+        let value = value.as_ref();
+
+        if let Some(at) = value
+            .iter()
+            .position(|b| !(b.is_ascii_lowercase() || b.is_ascii_digit()))
+        {
+            return Err(ValidationError::new(ValidationErrorKind::InvalidByteAt {
+                byte: value[at],
+                at,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Check if the given right is included.
+    pub fn contains(&self, right: char) -> bool {
+        self.0.contains(right)
+    }
+
+    pub fn inner(&self) -> &str {
+        self.0.as_ref()
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Rights<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        Self::validate(value)?;
+
+        Ok(Self(Cow::Borrowed(value)))
+    }
+}
+
+impl<'a> TryFrom<String> for Rights<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::validate(&value)?;
+
+        Ok(Self(Cow::Owned(value)))
+    }
+}
+
+impl<'a> AsRef<str> for Rights<'a> {
+    fn as_ref(&self) -> &str {
+        self.0.as_ref()
+    }
+}
+
+impl<'a> Display for Rights<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0.as_ref())
+    }
+}
+
+/// Modification of rights as used in SETACL.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ModRights<'a> {
+    /// Add the rights to the existing ones (`+rights`).
+    Add(Rights<'a>),
+    /// Remove the rights from the existing ones (`-rights`).
+    Remove(Rights<'a>),
+    /// Replace the existing rights (`rights`).
+    Replace(Rights<'a>),
+}
+
+impl<'a> ModRights<'a> {
+    pub fn rights(&self) -> &Rights<'a> {
+        match self {
+            Self::Add(rights) | Self::Remove(rights) | Self::Replace(rights) => rights,
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for ModRights<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        if let Some(rights) = value.strip_prefix('+') {
+            Ok(Self::Add(Rights::try_from(rights)?))
+        } else if let Some(rights) = value.strip_prefix('-') {
+            Ok(Self::Remove(Rights::try_from(rights)?))
+        } else {
+            Ok(Self::Replace(Rights::try_from(value)?))
+        }
+    }
+}
+
+impl<'a> TryFrom<String> for ModRights<'a> {
+    type Error = ValidationError;
+
+    fn try_from(mut value: String) -> Result<Self, Self::Error> {
+        if value.starts_with('+') {
+            value.remove(0);
+            Ok(Self::Add(Rights::try_from(value)?))
+        } else if value.starts_with('-') {
+            value.remove(0);
+            Ok(Self::Remove(Rights::try_from(value)?))
+        } else {
+            Ok(Self::Replace(Rights::try_from(value)?))
+        }
+    }
+}
+
+impl<'a> Display for ModRights<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Add(rights) => write!(f, "+{rights}"),
+            Self::Remove(rights) => write!(f, "-{rights}"),
+            Self::Replace(rights) => write!(f, "{rights}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion_mod_rights() {
+        let tests = [
+            ("+lrs", ModRights::Add(Rights::try_from("lrs").unwrap())),
+            ("-w", ModRights::Remove(Rights::try_from("w").unwrap())),
+            (
+                "lrswi0",
+                ModRights::Replace(Rights::try_from("lrswi0").unwrap()),
+            ),
+            ("", ModRights::Replace(Rights::try_from("").unwrap())),
+        ];
+
+        for (test, expected) in tests {
+            let got = ModRights::try_from(test).unwrap();
+            assert_eq!(expected, got);
+            assert_eq!(test, got.to_string());
+
+            let got = ModRights::try_from(test.to_owned()).unwrap();
+            assert_eq!(expected, got);
+        }
+
+        for test in ["+-l", "LRS", "l r", "\"l\""] {
+            assert!(ModRights::try_from(test).is_err());
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! IMAP4 Extension for Returning SEARCH Results in Extended Format (ESEARCH)
+//!
+//! This extends ...
+//!
+//! * [`CommandBody::Search`](crate::command::CommandBody::Search) with `return_options`.
+
+use std::fmt::{Display, Formatter};
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Result option of an extended SEARCH (`RETURN (...)`).
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchReturnOption {
+    /// Return the lowest message number/UID that satisfies the SEARCH criteria.
+    Min,
+    /// Return the highest message number/UID that satisfies the SEARCH criteria.
+    Max,
+    /// Return all message numbers/UIDs that satisfy the SEARCH criteria.
+    All,
+    /// Return the number of messages that satisfy the SEARCH criteria.
+    Count,
+}
+
+impl Display for SearchReturnOption {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Min => "MIN",
+            Self::Max => "MAX",
+            Self::All => "ALL",
+            Self::Count => "COUNT",
+        })
+    }
+}
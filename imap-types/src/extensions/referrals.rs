@@ -0,0 +1,499 @@
+//! IMAP4 Login Referrals (RFC 2221) and IMAP4 Mailbox Referrals (RFC 2193)
+//!
+//! These extensions define a new type ...
+//!
+//! * [`ImapUrl`]
+//!
+//! ... and extend ...
+//!
+//! * the [`Code`](crate::response::Code) enum with a new variant [`Code::Referral`](crate::response::Code#variant.Referral).
+
+use std::{
+    borrow::Cow,
+    fmt::{Display, Formatter},
+    num::NonZeroU32,
+};
+
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::extensions::referrals::error::ImapUrlError;
+
+/// An IMAP URL (see RFC 2192), e.g., `imap://alice@imap.example.org:143/INBOX;UIDVALIDITY=42/;UID=7`.
+///
+/// Supported are the user (and `;AUTH=` mechanism), host, port, mailbox, as well as the
+/// `;UIDVALIDITY=` and `;UID=` parameters. The user, the mechanism, and the mailbox are stored
+/// percent-decoded and percent-encoded during serialization.
+///
+/// # Example
+///
+/// ```
+/// use std::num::NonZeroU32;
+///
+/// use imap_types::extensions::referrals::ImapUrl;
+///
+/// let url =
+///     ImapUrl::try_from("imap://alice@imap.example.org/Sent%20Items;UIDVALIDITY=42").unwrap();
+///
+/// assert_eq!(url.host(), "imap.example.org");
+/// assert_eq!(url.mailbox(), Some("Sent Items"));
+/// assert_eq!(url.uid_validity(), NonZeroU32::new(42));
+/// ```
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImapUrl<'a> {
+    user: Option<Cow<'a, str>>,
+    auth: Option<Cow<'a, str>>,
+    host: Cow<'a, str>,
+    port: Option<u16>,
+    mailbox: Option<Cow<'a, str>>,
+    uid_validity: Option<NonZeroU32>,
+    uid: Option<NonZeroU32>,
+}
+
+impl<'a> ImapUrl<'a> {
+    /// Create an IMAP URL pointing to a server, i.e., `imap://<host>/`.
+    pub fn new<H>(host: H) -> Result<Self, ImapUrlError>
+    where
+        H: Into<Cow<'a, str>>,
+    {
+        let host = host.into();
+
+        if !is_host(&host) {
+            return Err(ImapUrlError::InvalidHost);
+        }
+
+        Ok(Self {
+            user: None,
+            auth: None,
+            host,
+            port: None,
+            mailbox: None,
+            uid_validity: None,
+            uid: None,
+        })
+    }
+
+    pub fn with_user<U>(mut self, user: U) -> Self
+    where
+        U: Into<Cow<'a, str>>,
+    {
+        self.user = Some(user.into());
+        self
+    }
+
+    /// Set the authentication mechanism, e.g., `*` (any mechanism) or `GSSAPI`.
+    pub fn with_auth<A>(mut self, auth: A) -> Self
+    where
+        A: Into<Cow<'a, str>>,
+    {
+        self.auth = Some(auth.into());
+        self
+    }
+
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    pub fn with_mailbox<M>(mut self, mailbox: M) -> Self
+    where
+        M: Into<Cow<'a, str>>,
+    {
+        self.mailbox = Some(mailbox.into());
+        self
+    }
+
+    pub fn with_uid_validity(mut self, uid_validity: NonZeroU32) -> Self {
+        self.uid_validity = Some(uid_validity);
+        self
+    }
+
+    pub fn with_uid(mut self, uid: NonZeroU32) -> Self {
+        self.uid = Some(uid);
+        self
+    }
+
+    pub fn user(&self) -> Option<&str> {
+        self.user.as_deref()
+    }
+
+    pub fn auth(&self) -> Option<&str> {
+        self.auth.as_deref()
+    }
+
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    pub fn port(&self) -> Option<u16> {
+        self.port
+    }
+
+    pub fn mailbox(&self) -> Option<&str> {
+        self.mailbox.as_deref()
+    }
+
+    pub fn uid_validity(&self) -> Option<NonZeroU32> {
+        self.uid_validity
+    }
+
+    pub fn uid(&self) -> Option<NonZeroU32> {
+        self.uid
+    }
+}
+
+impl<'a> TryFrom<&'a str> for ImapUrl<'a> {
+    type Error = ImapUrlError;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        let rest = strip_prefix_ignore_case(value, "imap://").ok_or(ImapUrlError::MissingScheme)?;
+        let (server, path) = rest.split_once('/').ok_or(ImapUrlError::MissingPath)?;
+
+        // `imap://[user][;AUTH=<auth>]@<host>[:<port>]/`
+        let (user_auth, host_port) = match server.split_once('@') {
+            Some((user_auth, host_port)) => (Some(user_auth), host_port),
+            None => (None, server),
+        };
+
+        let (user, auth) = match user_auth {
+            Some(user_auth) => match find_ignore_case(user_auth, ";AUTH=") {
+                Some(index) => (
+                    &user_auth[..index],
+                    Some(decode(&user_auth[index + 6..], is_achar)?),
+                ),
+                None => (user_auth, None),
+            },
+            None => ("", None),
+        };
+
+        let user = match user {
+            "" if user_auth.is_some() && auth.is_none() => return Err(ImapUrlError::MissingUser),
+            "" => None,
+            user => Some(decode(user, is_achar)?),
+        };
+
+        let (host, port) = match host_port.rsplit_once(':') {
+            // Note: IPv6 addresses are enclosed in brackets.
+            Some((host, port)) if !port.ends_with(']') => {
+                if port.is_empty() || !port.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(ImapUrlError::InvalidPort);
+                }
+
+                (
+                    host,
+                    Some(port.parse().map_err(|_| ImapUrlError::InvalidPort)?),
+                )
+            }
+            _ => (host_port, None),
+        };
+
+        // `/[<mailbox>][;UIDVALIDITY=<uid_validity>][/;UID=<uid>]`
+        let (path, uid) = match rfind_ignore_case(path, "/;UID=") {
+            Some(index) => (&path[..index], Some(nz_number(&path[index + 6..])?)),
+            None => (path, None),
+        };
+
+        let (mailbox, uid_validity) = match find_ignore_case(path, ";UIDVALIDITY=") {
+            Some(index) => (&path[..index], Some(nz_number(&path[index + 13..])?)),
+            None => (path, None),
+        };
+
+        let mailbox = match mailbox {
+            "" => None,
+            mailbox => Some(decode(mailbox, is_bchar)?),
+        };
+
+        let mut url = Self::new(host)?;
+        url.user = user;
+        url.auth = auth;
+        url.port = port;
+        url.mailbox = mailbox;
+        url.uid_validity = uid_validity;
+        url.uid = uid;
+
+        Ok(url)
+    }
+}
+
+impl<'a> Display for ImapUrl<'a> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.write_str("imap://")?;
+
+        if self.user.is_some() || self.auth.is_some() {
+            if let Some(user) = &self.user {
+                encode(user, is_achar, f)?;
+            }
+
+            if let Some(auth) = &self.auth {
+                f.write_str(";AUTH=")?;
+                encode(auth, is_achar, f)?;
+            }
+
+            f.write_str("@")?;
+        }
+
+        f.write_str(&self.host)?;
+
+        if let Some(port) = self.port {
+            write!(f, ":{port}")?;
+        }
+
+        f.write_str("/")?;
+
+        if let Some(mailbox) = &self.mailbox {
+            encode(mailbox, is_bchar, f)?;
+        }
+
+        if let Some(uid_validity) = self.uid_validity {
+            write!(f, ";UIDVALIDITY={uid_validity}")?;
+        }
+
+        if let Some(uid) = self.uid {
+            write!(f, "/;UID={uid}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// `host = hostname / IPv4 / "[" IPv6 "]"` (simplified)
+fn is_host(host: &str) -> bool {
+    match host
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+    {
+        Some(ipv6) => {
+            !ipv6.is_empty()
+                && ipv6
+                    .bytes()
+                    .all(|b| b.is_ascii_hexdigit() || b == b':' || b == b'.')
+        }
+        None => {
+            !host.is_empty()
+                && host
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'.')
+        }
+    }
+}
+
+/// `achar = uchar / "&" / "=" / "~"`
+///
+/// With `uchar = unreserved / escape` (where escapes are handled separately).
+fn is_achar(byte: u8) -> bool {
+    // unreserved = alpha / digit / safe / extra
+    // safe       = "$" / "-" / "_" / "." / "+"
+    // extra      = "!" / "*" / "'" / "(" / ")" / ","
+    byte.is_ascii_alphanumeric() || b"$-_.+!*'(),&=~".contains(&byte)
+}
+
+/// `bchar = achar / ":" / "@" / "/"`
+fn is_bchar(byte: u8) -> bool {
+    is_achar(byte) || b":@/".contains(&byte)
+}
+
+fn nz_number(value: &str) -> Result<NonZeroU32, ImapUrlError> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ImapUrlError::InvalidNumber);
+    }
+
+    value.parse().map_err(|_| ImapUrlError::InvalidNumber)
+}
+
+fn decode(value: &str, allowed: fn(u8) -> bool) -> Result<Cow<'_, str>, ImapUrlError> {
+    if let Some(byte) = value.bytes().find(|b| *b != b'%' && !allowed(*b)) {
+        return Err(ImapUrlError::InvalidByte { byte });
+    }
+
+    if !value.contains('%') {
+        return Ok(Cow::Borrowed(value));
+    }
+
+    let mut decoded = Vec::with_capacity(value.len());
+    let mut bytes = value.bytes();
+
+    while let Some(byte) = bytes.next() {
+        if byte == b'%' {
+            let hex = [
+                bytes.next().ok_or(ImapUrlError::InvalidPercentEncoding)?,
+                bytes.next().ok_or(ImapUrlError::InvalidPercentEncoding)?,
+            ];
+            let hex =
+                std::str::from_utf8(&hex).map_err(|_| ImapUrlError::InvalidPercentEncoding)?;
+
+            decoded.push(
+                u8::from_str_radix(hex, 16).map_err(|_| ImapUrlError::InvalidPercentEncoding)?,
+            );
+        } else {
+            decoded.push(byte);
+        }
+    }
+
+    String::from_utf8(decoded)
+        .map(Cow::Owned)
+        .map_err(|_| ImapUrlError::InvalidPercentEncoding)
+}
+
+fn encode(value: &str, allowed: fn(u8) -> bool, f: &mut Formatter) -> std::fmt::Result {
+    for byte in value.bytes() {
+        if allowed(byte) {
+            write!(f, "{}", byte as char)?;
+        } else {
+            write!(f, "%{byte:02X}")?;
+        }
+    }
+
+    Ok(())
+}
+
+fn strip_prefix_ignore_case<'a>(value: &'a str, prefix: &str) -> Option<&'a str> {
+    match value.get(..prefix.len()) {
+        Some(start) if start.eq_ignore_ascii_case(prefix) => Some(&value[prefix.len()..]),
+        _ => None,
+    }
+}
+
+fn find_ignore_case(value: &str, pattern: &str) -> Option<usize> {
+    value
+        .to_ascii_uppercase()
+        .find(&pattern.to_ascii_uppercase())
+}
+
+fn rfind_ignore_case(value: &str, pattern: &str) -> Option<usize> {
+    value
+        .to_ascii_uppercase()
+        .rfind(&pattern.to_ascii_uppercase())
+}
+
+/// Error-related types.
+pub mod error {
+    use thiserror::Error;
+
+    #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+    pub enum ImapUrlError {
+        #[error("Missing `imap://` scheme")]
+        MissingScheme,
+        #[error("Missing `/` after server")]
+        MissingPath,
+        #[error("Missing user before `@`")]
+        MissingUser,
+        #[error("Invalid host")]
+        InvalidHost,
+        #[error("Invalid port")]
+        InvalidPort,
+        #[error("Invalid number")]
+        InvalidNumber,
+        #[error("Invalid byte b'\\x{byte:02x}'")]
+        InvalidByte { byte: u8 },
+        #[error("Invalid percent-encoding")]
+        InvalidPercentEncoding,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_imap_url() {
+        let url =
+            ImapUrl::try_from("imap://alice@imap.example.org:1143/INBOX;UIDVALIDITY=42/;UID=7")
+                .unwrap();
+
+        assert_eq!(url.user(), Some("alice"));
+        assert_eq!(url.auth(), None);
+        assert_eq!(url.host(), "imap.example.org");
+        assert_eq!(url.port(), Some(1143));
+        assert_eq!(url.mailbox(), Some("INBOX"));
+        assert_eq!(url.uid_validity(), NonZeroU32::new(42));
+        assert_eq!(url.uid(), NonZeroU32::new(7));
+
+        assert_eq!(
+            ImapUrl::new("imap.example.org")
+                .unwrap()
+                .with_user("alice")
+                .with_port(1143)
+                .with_mailbox("INBOX")
+                .with_uid_validity(NonZeroU32::new(42).unwrap())
+                .with_uid(NonZeroU32::new(7).unwrap()),
+            url
+        );
+    }
+
+    #[test]
+    fn test_imap_url_bare() {
+        let url = ImapUrl::try_from("imap://host/INBOX").unwrap();
+
+        assert_eq!(url, ImapUrl::new("host").unwrap().with_mailbox("INBOX"));
+        assert_eq!(url.to_string(), "imap://host/INBOX");
+    }
+
+    #[test]
+    fn test_imap_url_round_trip() {
+        let tests = [
+            "imap://host/",
+            "imap://host/INBOX",
+            "imap://host:143/INBOX",
+            "imap://[::1]:143/INBOX",
+            "imap://127.0.0.1/",
+            "imap://alice@host/",
+            "imap://;AUTH=*@host/",
+            "imap://alice;AUTH=GSSAPI@host/",
+            "imap://host/Sent%20Items",
+            "imap://host/Archive/2024",
+            "imap://host/%C3%A4%25;UIDVALIDITY=1",
+            "imap://host/INBOX/;UID=1",
+            "imap://al%40ice@host/INBOX;UIDVALIDITY=1/;UID=4294967295",
+        ];
+
+        for test in tests {
+            let url = ImapUrl::try_from(test).unwrap();
+            assert_eq!(test, url.to_string());
+        }
+
+        let url = ImapUrl::try_from("imap://host/%C3%A4%25").unwrap();
+        assert_eq!(url.mailbox(), Some("ä%"));
+    }
+
+    #[test]
+    fn test_imap_url_invalid() {
+        let tests = [
+            ("", ImapUrlError::MissingScheme),
+            ("http://host/", ImapUrlError::MissingScheme),
+            ("imap://host", ImapUrlError::MissingPath),
+            ("imap:///", ImapUrlError::InvalidHost),
+            ("imap://@host/", ImapUrlError::MissingUser),
+            ("imap://ho st/", ImapUrlError::InvalidHost),
+            ("imap://host:/", ImapUrlError::InvalidPort),
+            ("imap://host:99999/", ImapUrlError::InvalidPort),
+            (
+                "imap://host/INBOX;UIDVALIDITY=0",
+                ImapUrlError::InvalidNumber,
+            ),
+            ("imap://host/INBOX/;UID=x", ImapUrlError::InvalidNumber),
+            (
+                "imap://host/IN BOX",
+                ImapUrlError::InvalidByte { byte: b' ' },
+            ),
+            (
+                "imap://host/INBOX?SUBJECT%20x",
+                ImapUrlError::InvalidByte { byte: b'?' },
+            ),
+            (
+                "imap://host/INBOX/;SECTION=1",
+                ImapUrlError::InvalidByte { byte: b';' },
+            ),
+            ("imap://host/%", ImapUrlError::InvalidPercentEncoding),
+            ("imap://host/%zz", ImapUrlError::InvalidPercentEncoding),
+            ("imap://host/%FF", ImapUrlError::InvalidPercentEncoding),
+        ];
+
+        for (test, expected) in tests {
+            assert_eq!(ImapUrl::try_from(test), Err(expected), "{test}");
+        }
+    }
+}
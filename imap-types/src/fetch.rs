@@ -1,7 +1,8 @@
 //! Fetch-related types.
 
 use std::{
-    fmt::{Display, Formatter},
+    borrow::Cow,
+    fmt::{Debug, Display, Formatter},
     num::NonZeroU32,
 };
 
@@ -16,10 +17,11 @@ use serde::{Deserialize, Serialize};
 use crate::core::NString8;
 use crate::{
     body::BodyStructure,
-    core::{AString, NString, Vec1},
+    core::{AString, Atom, NString, Vec1},
     datetime::DateTime,
     envelope::Envelope,
     flag::FlagFetch,
+    response::Data,
 };
 
 /// Shorthands for commonly-used message data items.
@@ -199,6 +201,8 @@ pub enum MessageDataItemName<'a> {
     ///     peek: false,
     /// };
     /// ```
+    ///
+    /// Note: Removed in IMAP4rev2 (RFC 9051), but still sent by some clients.
     Rfc822,
 
     /// Functionally equivalent to `BODY.PEEK[HEADER]`.
@@ -208,6 +212,8 @@ pub enum MessageDataItemName<'a> {
     /// ```imap
     /// RFC822.HEADER
     /// ```
+    ///
+    /// Note: Removed in IMAP4rev2 (RFC 9051), but still sent by some clients.
     Rfc822Header,
 
     /// The [RFC-2822] size of a message.
@@ -220,9 +226,12 @@ pub enum MessageDataItemName<'a> {
     /// Functionally equivalent to `BODY[TEXT]`.
     ///
     /// Differs in the syntax of the resulting untagged FETCH data (`RFC822.TEXT` is returned).
+    ///
     /// ```imap
     /// RFC822.TEXT
     /// ```
+    ///
+    /// Note: Removed in IMAP4rev2 (RFC 9051), but still sent by some clients.
     Rfc822Text,
 
     /// The unique identifier for a message.
@@ -243,6 +252,42 @@ pub enum MessageDataItemName<'a> {
     BinarySize { section: Vec<NonZeroU32> },
 }
 
+impl<'a> MessageDataItemName<'a> {
+    /// Get the key of the expected `BODY[<section>]<<origin octet>>` response item.
+    ///
+    /// A requested `BODY.PEEK[1.2]<0.100>` is answered with `BODY[1.2]<0>`, i.e., the `.PEEK` is
+    /// dropped and the partial is reduced to its origin octet. Returns `None` when this isn't a
+    /// `BODY[...]` request.
+    pub fn body_ext_response_key(&self) -> Option<(Option<&Section<'a>>, Option<u32>)> {
+        match self {
+            Self::BodyExt {
+                section, partial, ..
+            } => Some((section.as_ref(), partial.map(|(offset, _)| offset))),
+            _ => None,
+        }
+    }
+
+    /// Get the `BODY[...]` equivalent of a legacy `RFC822*` item.
+    ///
+    /// `RFC822` becomes `BODY[]`, `RFC822.HEADER` becomes `BODY.PEEK[HEADER]`, and `RFC822.TEXT`
+    /// becomes `BODY[TEXT]`, i.e., the `\Seen` flag is set (or not) as before. Returns `None` for
+    /// all other items (including `RFC822.SIZE`).
+    pub fn body_ext_equivalent(&self) -> Option<MessageDataItemName<'static>> {
+        let (section, peek) = match self {
+            Self::Rfc822 => (None, false),
+            Self::Rfc822Header => (Some(Section::Header(None)), true),
+            Self::Rfc822Text => (Some(Section::Text(None)), false),
+            _ => return None,
+        };
+
+        Some(MessageDataItemName::BodyExt {
+            section,
+            partial: None,
+            peek,
+        })
+    }
+}
+
 /// Message data item.
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
@@ -377,6 +422,229 @@ pub enum MessageDataItem<'a> {
 
     #[cfg(feature = "ext_binary")]
     BinarySize { section: Vec<NonZeroU32>, size: u32 },
+
+    /// An (unknown) message data item, e.g., from a vendor extension.
+    Other(MessageDataItemOther<'a>),
+}
+
+impl<'a> MessageDataItem<'a> {
+    /// Check if this is the `BODY[...]` response item to the requested `name`.
+    ///
+    /// See [`MessageDataItemName::body_ext_response_key`]. Sections are compared with
+    /// [`Section::is_equivalent`].
+    pub fn is_body_ext_response_to(&self, name: &MessageDataItemName) -> bool {
+        match (self, name.body_ext_response_key()) {
+            (
+                Self::BodyExt {
+                    section, origin, ..
+                },
+                Some((expected_section, expected_origin)),
+            ) => {
+                let section_matches = match (section, expected_section) {
+                    (Some(section), Some(expected_section)) => {
+                        section.is_equivalent(expected_section)
+                    }
+                    (None, None) => true,
+                    _ => false,
+                };
+
+                section_matches && *origin == expected_origin
+            }
+            _ => false,
+        }
+    }
+}
+
+/// An (unknown) message data item.
+///
+/// The value is kept as it was received, i.e., including quotes, literal prefixes, or parentheses.
+///
+/// It's guaranteed that this type can't represent any message data item from [`MessageDataItem`].
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct MessageDataItemOther<'a> {
+    name: Atom<'a>,
+    value: Cow<'a, [u8]>,
+}
+
+// We want a more readable `Debug` implementation.
+impl<'a> Debug for MessageDataItemOther<'a> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        struct BStr<'a>(&'a Cow<'a, [u8]>);
+
+        impl<'a> Debug for BStr<'a> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                write!(
+                    f,
+                    "b\"{}\"",
+                    crate::utils::escape_byte_string(self.0.as_ref())
+                )
+            }
+        }
+
+        f.debug_struct("MessageDataItemOther")
+            .field("name", &self.name)
+            .field("value", &BStr(&self.value))
+            .finish()
+    }
+}
+
+impl<'a> MessageDataItemOther<'a> {
+    /// Constructs an unsupported message data item without validation.
+    ///
+    /// # Warning: IMAP conformance
+    ///
+    /// The caller must ensure that `name` is not the name of a supported message data item and
+    /// that `value` is valid. Failing to do so may create invalid/unparsable IMAP messages, or even
+    /// produce unintended protocol flows. Do not call this constructor with untrusted data.
+    #[cfg(feature = "unvalidated")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unvalidated")))]
+    pub fn unvalidated<V>(name: Atom<'a>, value: V) -> Self
+    where
+        V: Into<Cow<'a, [u8]>>,
+    {
+        Self {
+            name,
+            value: value.into(),
+        }
+    }
+
+    pub fn name(&self) -> &Atom<'a> {
+        &self.name
+    }
+
+    /// Returns the raw (serialized) value.
+    pub fn value(&self) -> &[u8] {
+        self.value.as_ref()
+    }
+}
+
+/// Source of message data used by [`fetch_response`] to answer a `FETCH`.
+///
+/// All methods return `None` by default, i.e., a source only needs to implement what it can
+/// answer.
+pub trait MessageSource<'a> {
+    /// Answer `UID`.
+    fn uid(&self) -> Option<NonZeroU32> {
+        None
+    }
+
+    /// Answer `FLAGS`.
+    fn flags(&self) -> Option<Vec<FlagFetch<'a>>> {
+        None
+    }
+
+    /// Answer `INTERNALDATE`.
+    fn internal_date(&self) -> Option<DateTime> {
+        None
+    }
+
+    /// Answer `RFC822.SIZE`.
+    fn rfc822_size(&self) -> Option<u32> {
+        None
+    }
+
+    /// Answer `ENVELOPE`.
+    fn envelope(&self) -> Option<Envelope<'a>> {
+        None
+    }
+
+    /// Answer `BODY` and `BODYSTRUCTURE`.
+    fn body_structure(&self) -> Option<BodyStructure<'a>> {
+        None
+    }
+
+    /// Answer `BODY[<section>]<<partial>>`, `RFC822`, `RFC822.HEADER`, and `RFC822.TEXT`.
+    ///
+    /// `section` is `None` for the entire message. When `partial` is given, only the requested
+    /// substring must be returned.
+    fn body_section(
+        &self,
+        section: Option<&Section<'a>>,
+        partial: Option<(u32, NonZeroU32)>,
+    ) -> Option<NString<'a>> {
+        let _ = (section, partial);
+        None
+    }
+}
+
+/// Build a `FETCH` response for the message `seq` by asking `source` for each requested item.
+///
+/// Items that `source` can't answer are skipped. Returns `None` when no item could be answered.
+///
+/// # Example
+///
+/// ```
+/// use std::num::NonZeroU32;
+///
+/// use imap_types::{
+///     fetch::{fetch_response, MessageDataItem, MessageDataItemName, MessageSource},
+///     response::Data,
+/// };
+///
+/// struct Message;
+///
+/// impl<'a> MessageSource<'a> for Message {
+///     fn rfc822_size(&self) -> Option<u32> {
+///         Some(1337)
+///     }
+/// }
+///
+/// let seq = NonZeroU32::new(1).unwrap();
+///
+/// assert_eq!(
+///     fetch_response(seq, &[MessageDataItemName::Rfc822Size], &Message),
+///     Some(Data::fetch(1, vec![MessageDataItem::Rfc822Size(1337)]).unwrap()),
+/// );
+/// ```
+pub fn fetch_response<'a>(
+    seq: NonZeroU32,
+    names: &[MessageDataItemName<'a>],
+    source: &impl MessageSource<'a>,
+) -> Option<Data<'a>> {
+    let items: Vec<_> = names
+        .iter()
+        .filter_map(|name| match name {
+            MessageDataItemName::Body => source.body_structure().map(MessageDataItem::Body),
+            MessageDataItemName::BodyExt {
+                section, partial, ..
+            } => source.body_section(section.as_ref(), *partial).map(|data| {
+                MessageDataItem::BodyExt {
+                    section: section.clone(),
+                    origin: partial.map(|(offset, _)| offset),
+                    data,
+                }
+            }),
+            MessageDataItemName::BodyStructure => {
+                source.body_structure().map(MessageDataItem::BodyStructure)
+            }
+            MessageDataItemName::Envelope => source.envelope().map(MessageDataItem::Envelope),
+            MessageDataItemName::Flags => source.flags().map(MessageDataItem::Flags),
+            MessageDataItemName::InternalDate => {
+                source.internal_date().map(MessageDataItem::InternalDate)
+            }
+            MessageDataItemName::Rfc822 => {
+                source.body_section(None, None).map(MessageDataItem::Rfc822)
+            }
+            MessageDataItemName::Rfc822Header => source
+                .body_section(Some(&Section::Header(None)), None)
+                .map(MessageDataItem::Rfc822Header),
+            MessageDataItemName::Rfc822Size => {
+                source.rfc822_size().map(MessageDataItem::Rfc822Size)
+            }
+            MessageDataItemName::Rfc822Text => source
+                .body_section(Some(&Section::Text(None)), None)
+                .map(MessageDataItem::Rfc822Text),
+            MessageDataItemName::Uid => source.uid().map(MessageDataItem::Uid),
+            #[cfg(feature = "ext_binary")]
+            MessageDataItemName::Binary { .. } | MessageDataItemName::BinarySize { .. } => None,
+        })
+        .collect();
+
+    Vec1::try_from(items)
+        .ok()
+        .map(|items| Data::Fetch { seq, items })
 }
 
 /// A part specifier is either a part number or one of the following:
@@ -448,6 +716,65 @@ pub enum Section<'a> {
     Mime(Part),
 }
 
+impl<'a> Section<'a> {
+    /// Canonicalize the section.
+    ///
+    /// The order of the field names in HEADER.FIELDS and HEADER.FIELDS.NOT is insignificant, and
+    /// field names are case-insensitive. Thus, the field names are sorted (case-insensitively) and
+    /// duplicates are removed. The first spelling of a field name is kept.
+    ///
+    /// Note: Part paths can't be empty (see [`Part`]), so `Header(None)` is the only way to express
+    /// the header of the message.
+    pub fn normalize(self) -> Self {
+        match self {
+            Self::HeaderFields(part, fields) => Self::HeaderFields(part, normalize_fields(fields)),
+            Self::HeaderFieldsNot(part, fields) => {
+                Self::HeaderFieldsNot(part, normalize_fields(fields))
+            }
+            other => other,
+        }
+    }
+
+    /// Compare two sections according to [`Section::normalize`].
+    ///
+    /// In contrast to `==`, this also ignores the case and representation (atom, quoted, or
+    /// literal) of field names. This is useful to match a returned section with a requested one.
+    pub fn is_equivalent(&self, other: &Section) -> bool {
+        fn fields_eq(a: &Vec1<AString>, b: &Vec1<AString>) -> bool {
+            let a = normalize_fields(a.clone());
+            let b = normalize_fields(b.clone());
+
+            a.as_ref().len() == b.as_ref().len()
+                && a.as_ref()
+                    .iter()
+                    .zip(b.as_ref())
+                    .all(|(a, b)| a.as_ref().eq_ignore_ascii_case(b.as_ref()))
+        }
+
+        match (self, other) {
+            (Section::HeaderFields(part_a, a), Section::HeaderFields(part_b, b))
+            | (Section::HeaderFieldsNot(part_a, a), Section::HeaderFieldsNot(part_b, b)) => {
+                part_a == part_b && fields_eq(a, b)
+            }
+            (a, b) => a == b,
+        }
+    }
+}
+
+fn normalize_fields(fields: Vec1<AString>) -> Vec1<AString> {
+    let mut fields = fields.into_inner();
+
+    fields.sort_by(|a, b| {
+        let a = a.as_ref().iter().map(u8::to_ascii_uppercase);
+        let b = b.as_ref().iter().map(u8::to_ascii_uppercase);
+        a.cmp(b)
+    });
+    fields.dedup_by(|a, b| a.as_ref().eq_ignore_ascii_case(b.as_ref()));
+
+    // Safety: `unwrap` can't panic because sorting and deduplication keep at least one element.
+    Vec1::try_from(fields).unwrap()
+}
+
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -480,3 +807,187 @@ pub enum PartSpecifier<'a> {
     Mime,
     Text,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::{IString, Quoted},
+        flag::Flag,
+    };
+
+    #[test]
+    fn test_body_ext_response_key() {
+        let section = Section::Part(Part(
+            Vec1::try_from(vec![
+                NonZeroU32::new(1).unwrap(),
+                NonZeroU32::new(2).unwrap(),
+            ])
+            .unwrap(),
+        ));
+
+        // `BODY.PEEK[1.2]<0.100>` ...
+        let request = MessageDataItemName::BodyExt {
+            section: Some(section.clone()),
+            partial: Some((0, NonZeroU32::new(100).unwrap())),
+            peek: true,
+        };
+        assert_eq!(
+            request.body_ext_response_key(),
+            Some((Some(&section), Some(0)))
+        );
+
+        // ... is answered with `BODY[1.2]<0>`.
+        let response = MessageDataItem::BodyExt {
+            section: Some(section.clone()),
+            origin: Some(0),
+            data: NString(None),
+        };
+        assert!(response.is_body_ext_response_to(&request));
+
+        // But not with `BODY[1.2]` or `BODY[]<0>`.
+        let response = MessageDataItem::BodyExt {
+            section: Some(section),
+            origin: None,
+            data: NString(None),
+        };
+        assert!(!response.is_body_ext_response_to(&request));
+        let response = MessageDataItem::BodyExt {
+            section: None,
+            origin: Some(0),
+            data: NString(None),
+        };
+        assert!(!response.is_body_ext_response_to(&request));
+
+        assert_eq!(MessageDataItemName::Uid.body_ext_response_key(), None);
+        assert!(!MessageDataItem::Uid(NonZeroU32::new(1).unwrap())
+            .is_body_ext_response_to(&MessageDataItemName::Uid));
+    }
+
+    #[test]
+    fn test_body_ext_equivalent() {
+        let tests = [
+            (MessageDataItemName::Rfc822, Some((None, false))),
+            (
+                MessageDataItemName::Rfc822Header,
+                Some((Some(Section::Header(None)), true)),
+            ),
+            (
+                MessageDataItemName::Rfc822Text,
+                Some((Some(Section::Text(None)), false)),
+            ),
+            (MessageDataItemName::Rfc822Size, None),
+            (MessageDataItemName::Flags, None),
+        ];
+
+        for (name, expected) in tests {
+            assert_eq!(
+                name.body_ext_equivalent(),
+                expected.map(|(section, peek)| MessageDataItemName::BodyExt {
+                    section,
+                    partial: None,
+                    peek,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_fetch_response() {
+        struct Message;
+
+        impl<'a> MessageSource<'a> for Message {
+            fn flags(&self) -> Option<Vec<FlagFetch<'a>>> {
+                Some(vec![
+                    FlagFetch::Flag(Flag::Seen),
+                    FlagFetch::Flag(Flag::Flagged),
+                ])
+            }
+
+            fn rfc822_size(&self) -> Option<u32> {
+                Some(1337)
+            }
+        }
+
+        let seq = NonZeroU32::new(42).unwrap();
+
+        assert_eq!(
+            fetch_response(
+                seq,
+                &[
+                    MessageDataItemName::Flags,
+                    MessageDataItemName::Envelope,
+                    MessageDataItemName::Rfc822Size,
+                ],
+                &Message
+            ),
+            Some(
+                Data::fetch(
+                    42,
+                    vec![
+                        MessageDataItem::Flags(vec![
+                            FlagFetch::Flag(Flag::Seen),
+                            FlagFetch::Flag(Flag::Flagged),
+                        ]),
+                        MessageDataItem::Rfc822Size(1337),
+                    ]
+                )
+                .unwrap()
+            )
+        );
+        assert_eq!(
+            fetch_response(seq, &[MessageDataItemName::Envelope], &Message),
+            None
+        );
+        assert_eq!(fetch_response(seq, &[], &Message), None);
+    }
+
+    #[test]
+    fn test_section_normalize() {
+        let fields = |fields: &[&'static str]| {
+            Vec1::try_from(
+                fields
+                    .iter()
+                    .map(|field| AString::try_from(*field).unwrap())
+                    .collect::<Vec<_>>(),
+            )
+            .unwrap()
+        };
+
+        let from_to = Section::HeaderFields(None, fields(&["FROM", "TO"]));
+        let to_from = Section::HeaderFields(None, fields(&["TO", "FROM"]));
+
+        // The order is insignificant ...
+        assert_ne!(from_to, to_from);
+        assert_eq!(from_to.clone().normalize(), to_from.clone().normalize());
+        assert!(from_to.is_equivalent(&to_from));
+
+        // ... as well as the case and duplicates.
+        let to_from_from = Section::HeaderFields(None, fields(&["to", "From", "FROM"]));
+        assert_eq!(
+            to_from_from.clone().normalize(),
+            Section::HeaderFields(None, fields(&["From", "to"]))
+        );
+        assert!(from_to.is_equivalent(&to_from_from));
+
+        // The representation is insignificant, too.
+        let quoted = Section::HeaderFields(
+            None,
+            Vec1::try_from(vec![
+                AString::try_from("FROM").unwrap(),
+                AString::String(IString::Quoted(Quoted::try_from("TO").unwrap())),
+            ])
+            .unwrap(),
+        );
+        assert_ne!(quoted, from_to);
+        assert!(quoted.is_equivalent(&to_from));
+
+        // But the part, the variant, and the set of fields are significant.
+        let part = Part(Vec1::from(NonZeroU32::new(1).unwrap()));
+        assert!(!from_to.is_equivalent(&Section::HeaderFields(Some(part), fields(&["FROM", "TO"]))));
+        assert!(!from_to.is_equivalent(&Section::HeaderFieldsNot(None, fields(&["FROM", "TO"]))));
+        assert!(!from_to.is_equivalent(&Section::HeaderFields(None, fields(&["FROM"]))));
+        assert!(Section::Header(None).is_equivalent(&Section::Header(None)));
+        assert!(!Section::Header(None).is_equivalent(&Section::Text(None)));
+    }
+}
@@ -63,9 +63,29 @@ impl<'a> Flag<'a> {
         }
     }
 
+    /// Returns `true` if this is the `\Recent` flag.
+    ///
+    /// `\Recent` is managed by the server and can't be set or cleared by a client. Thus, it has no
+    /// variant in [`Flag`], which is also used to build commands. In flag lists sent by the server
+    /// (e.g., FLAGS), it is kept as an extension flag. In FETCH responses, it is represented as
+    /// [`FlagFetch::Recent`]. [`CommandBody::store`](crate::command::CommandBody::store) rejects it.
+    pub fn is_recent(&self) -> bool {
+        matches!(self, Self::Extension(FlagExtension(atom)) if atom.as_ref().eq_ignore_ascii_case("recent"))
+    }
+
     pub fn keyword(atom: Atom<'a>) -> Self {
         Self::Keyword(atom)
     }
+
+    /// Create a keyword, e.g., `$MDNSent` or `NonJunk`.
+    ///
+    /// A keyword must be a valid `atom`. Thus, this fails for, e.g., `foo bar` or `\Seen`.
+    pub fn try_keyword<A>(keyword: A) -> Result<Self, A::Error>
+    where
+        A: TryInto<Atom<'a>>,
+    {
+        Ok(Self::Keyword(keyword.try_into()?))
+    }
 }
 
 impl<'a> TryFrom<&'a str> for Flag<'a> {
@@ -122,6 +142,19 @@ pub enum FlagPerm<'a> {
     Asterisk,
 }
 
+impl<'a> TryFrom<&'a str> for FlagPerm<'a> {
+    type Error = ValidationError;
+
+    /// Note: In contrast to [`Flag`], `\*` is accepted (as [`FlagPerm::Asterisk`]).
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        if value == "\\*" {
+            return Ok(Self::Asterisk);
+        }
+
+        Ok(Self::Flag(Flag::try_from(value)?))
+    }
+}
+
 /// Four name attributes are defined.
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -119,11 +119,15 @@
 //! |ext_id               |IMAP4 ID extension ([RFC 2971])                                                        |Unfinished|
 //! |ext_sort_thread      |Internet Message Access Protocol - SORT and THREAD Extensions ([RFC 5256] + [RFC 5957])|Unfinished|
 //! |ext_condstore_qresync|Quick Flag Changes Resynchronization and Quick Mailbox Resynchronization ([RFC 7162])  |Unfinished|
+//! |ext_esearch          |IMAP4 Extension for Returning SEARCH Results in Extended Format ([RFC 4731])           |Unfinished|
+//! |ext_searchres        |IMAP Extension for Referencing the Last SEARCH Result ([RFC 5182])                     |Unfinished|
 //! |ext_login_referrals  |IMAP4 Login Referrals ([RFC 2221])                                                     |Unfinished|
 //! |ext_mailbox_referrals|IMAP4 Mailbox Referrals ([RFC 2193])                                                   |Unfinished|
 //! |ext_binary           |IMAP4 Binary Content Extension ([RFC 3516])                                            |Unfinished|
 //! |ext_metadata         |The IMAP METADATA Extension ([RFC 5464])                                               |Unfinished|
+//! |ext_acl              |IMAP4 Access Control List (ACL) Extension ([RFC 4314])                                 |Unfinished|
 //! |starttls             |IMAP4rev1 ([RFC 3501]; section 6.2.1)                                                  |          |
+//! |legacy               |Legacy (non-standard) XLIST command and response                                        |          |
 //!
 //! STARTTLS is not an IMAP extension but feature-gated because it [should be avoided](https://nostarttls.secvuln.info/).
 //! For better performance and security, use "implicit TLS", i.e., IMAP-over-TLS on port 993, and don't use STARTTLS at all.
@@ -155,9 +159,12 @@
 //! [RFC 3501]: https://datatracker.ietf.org/doc/html/rfc3501
 //! [RFC 3516]: https://datatracker.ietf.org/doc/html/rfc3516
 //! [RFC 3691]: https://datatracker.ietf.org/doc/html/rfc3691
+//! [RFC 4314]: https://datatracker.ietf.org/doc/html/rfc4314
+//! [RFC 4731]: https://datatracker.ietf.org/doc/html/rfc4731
 //! [RFC 4959]: https://datatracker.ietf.org/doc/html/rfc4959
 //! [RFC 4978]: https://datatracker.ietf.org/doc/html/rfc4978
 //! [RFC 5161]: https://datatracker.ietf.org/doc/html/rfc5161
+//! [RFC 5182]: https://datatracker.ietf.org/doc/html/rfc5182
 //! [RFC 5256]: https://datatracker.ietf.org/doc/html/rfc5256
 //! [RFC 5464]: https://datatracker.ietf.org/doc/html/rfc5464
 //! [RFC 5957]: https://datatracker.ietf.org/doc/html/rfc5957
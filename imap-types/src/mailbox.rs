@@ -4,6 +4,11 @@ use std::{borrow::Cow, str::from_utf8};
 
 #[cfg(feature = "arbitrary")]
 use arbitrary::Arbitrary;
+use base64::{
+    alphabet::IMAP_MUTF7,
+    engine::{general_purpose::NO_PAD, GeneralPurpose},
+    Engine,
+};
 #[cfg(feature = "bounded-static")]
 use bounded_static::ToStatic;
 #[cfg(feature = "serde")]
@@ -12,7 +17,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     core::{impl_try_from, AString, IString},
     error::{ValidationError, ValidationErrorKind},
-    mailbox::error::MailboxOtherError,
+    mailbox::error::{MailboxNameError, MailboxOtherError, ModifiedUtf7Error},
     utils::indicators::is_list_char,
 };
 
@@ -98,6 +103,58 @@ pub enum ListMailbox<'a> {
     String(IString<'a>),
 }
 
+impl<'a> ListMailbox<'a> {
+    /// Check if the mailbox `name` matches this pattern (see RFC 3501, section 6.3.8).
+    ///
+    /// The character "*" matches zero or more characters. The character "%" is similar to "*",
+    /// but does not match the hierarchy `delimiter`. All other characters match themselves.
+    ///
+    /// The special name INBOX is matched case-insensitively, i.e., `inbox` matches `INBOX`.
+    ///
+    /// Note: The pattern is matched as is. Servers need to combine it with the reference
+    /// argument of the LIST command beforehand.
+    pub fn matches(&self, name: &str, delimiter: Option<char>) -> bool {
+        let pattern = String::from_utf8_lossy(match self {
+            Self::Token(token) => token.as_ref(),
+            Self::String(string) => string.as_ref(),
+        });
+
+        if name.eq_ignore_ascii_case("INBOX") {
+            wildcard_match(&pattern.to_ascii_uppercase(), "INBOX", delimiter)
+        } else {
+            wildcard_match(&pattern, name, delimiter)
+        }
+    }
+}
+
+/// Match `name` against a LIST `pattern` with "*" and "%" wildcards.
+///
+/// Note: Uses dynamic programming (instead of backtracking) to avoid exponential runtime for
+/// (malicious) patterns such as `*a*a*a*a*a*a*b`.
+fn wildcard_match(pattern: &str, name: &str, delimiter: Option<char>) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    // `next[j]` is true if `pattern[i + 1..]` matches `name[j..]`.
+    let mut next: Vec<bool> = (0..=name.len()).map(|j| j == name.len()).collect();
+
+    for p in pattern.iter().rev() {
+        let mut current = vec![false; name.len() + 1];
+
+        for j in (0..=name.len()).rev() {
+            current[j] = match p {
+                '*' => next[j] || (j < name.len() && current[j + 1]),
+                '%' => next[j] || (j < name.len() && Some(name[j]) != delimiter && current[j + 1]),
+                c => j < name.len() && name[j] == *c && next[j + 1],
+            };
+        }
+
+        next = current;
+    }
+
+    next[0]
+}
+
 impl<'a> TryFrom<&'a str> for ListMailbox<'a> {
     type Error = ValidationError;
 
@@ -195,6 +252,114 @@ impl<'a> From<AString<'a>> for Mailbox<'a> {
     }
 }
 
+impl<'a> Mailbox<'a> {
+    /// Create a mailbox from a Unicode name.
+    ///
+    /// Non-ASCII characters (and "&") are encoded using modified UTF-7 (see RFC 3501, section 5.1.3).
+    pub fn from_unicode(name: &str) -> Self {
+        let encoded = encode_modified_utf7(name);
+
+        // Safety: `unwrap` can't panic because modified UTF-7 is printable ASCII only, which can
+        // always be represented as an `AString`.
+        Self::from(AString::try_from(encoded).unwrap())
+    }
+
+    /// Create a mailbox from a Unicode name using the given encoding.
+    ///
+    /// Note: With [`MailboxNameEncoding::Utf8`], this fails when `name` contains a NUL character.
+    pub fn from_unicode_with(
+        name: &str,
+        encoding: MailboxNameEncoding,
+    ) -> Result<Self, ValidationError> {
+        match encoding {
+            MailboxNameEncoding::ModifiedUtf7 => Ok(Self::from_unicode(name)),
+            MailboxNameEncoding::Utf8 => Ok(Self::from(AString::try_from(name.to_owned())?)),
+        }
+    }
+
+    /// Decode the (modified UTF-7 encoded) name of this mailbox.
+    pub fn to_unicode(&self) -> Result<String, ModifiedUtf7Error> {
+        match self {
+            Self::Inbox => Ok(String::from("INBOX")),
+            Self::Other(other) => decode_modified_utf7(other.as_ref()),
+        }
+    }
+
+    /// Decode the name of this mailbox using the given encoding.
+    pub fn to_unicode_with(
+        &self,
+        encoding: MailboxNameEncoding,
+    ) -> Result<String, MailboxNameError> {
+        match encoding {
+            MailboxNameEncoding::ModifiedUtf7 => Ok(self.to_unicode()?),
+            MailboxNameEncoding::Utf8 => match self {
+                Self::Inbox => Ok(String::from("INBOX")),
+                Self::Other(other) => match from_utf8(other.as_ref()) {
+                    Ok(name) => Ok(name.to_owned()),
+                    Err(error) => Err(MailboxNameError::Utf8 {
+                        at: error.valid_up_to(),
+                    }),
+                },
+            },
+        }
+    }
+
+    /// Split the (decoded) name of this mailbox into its levels of hierarchy.
+    ///
+    /// For example, `a.b.c` is split into `["a", "b", "c"]` with delimiter `.`.
+    pub fn components(&self, delimiter: char) -> Result<Vec<String>, ModifiedUtf7Error> {
+        Ok(self
+            .to_unicode()?
+            .split(delimiter)
+            .map(ToOwned::to_owned)
+            .collect())
+    }
+
+    /// Get the parent of this mailbox, or `None` if this is a top-level mailbox.
+    ///
+    /// For example, the parent of `a.b.c` is `a.b` with delimiter `.`.
+    pub fn parent(&self, delimiter: char) -> Result<Option<Mailbox<'static>>, ModifiedUtf7Error> {
+        Ok(self
+            .to_unicode()?
+            .rsplit_once(delimiter)
+            .map(|(parent, _)| Mailbox::from_unicode(parent)))
+    }
+
+    /// Get the child `name` of this mailbox.
+    ///
+    /// For example, the child `c` of `a.b` is `a.b.c` with delimiter `.`.
+    pub fn child(
+        &self,
+        name: &str,
+        delimiter: char,
+    ) -> Result<Mailbox<'static>, ModifiedUtf7Error> {
+        let mut child = self.to_unicode()?;
+        child.push(delimiter);
+        child.push_str(name);
+
+        Ok(Mailbox::from_unicode(&child))
+    }
+}
+
+/// Encoding of mailbox names.
+///
+/// Mailbox names are encoded using modified UTF-7 by default. When `UTF8=ACCEPT` was enabled
+/// (see RFC 6855), mailbox names are sent as UTF-8 instead.
+///
+/// Note: UTF-8 is not yet supported in quoted strings. Thus, UTF-8 mailbox names are represented
+/// as literals.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MailboxNameEncoding {
+    /// Modified UTF-7 (see RFC 3501, section 5.1.3).
+    #[default]
+    ModifiedUtf7,
+    /// UTF-8 (see RFC 6855).
+    Utf8,
+}
+
 // We do not implement `AsRef<...>` for `Mailbox` because we want to enforce that a consumer
 // `match`es on `Mailbox::Inbox`/`Mailbox::Other`.
 
@@ -272,6 +437,94 @@ impl<'a> AsRef<[u8]> for MailboxOther<'a> {
     }
 }
 
+/// Modified base64 as defined in RFC 3501, section 5.1.3.
+const MODIFIED_BASE64: GeneralPurpose = GeneralPurpose::new(&IMAP_MUTF7, NO_PAD);
+
+/// Encode `unencoded` using modified UTF-7.
+fn encode_modified_utf7(unencoded: &str) -> String {
+    fn flush(encoded: &mut String, utf16: &mut Vec<u8>) {
+        if !utf16.is_empty() {
+            encoded.push('&');
+            encoded.push_str(&MODIFIED_BASE64.encode(&utf16));
+            encoded.push('-');
+            utf16.clear();
+        }
+    }
+
+    let mut encoded = String::with_capacity(unencoded.len());
+    let mut utf16 = Vec::new();
+
+    for c in unencoded.chars() {
+        match c {
+            '&' => {
+                flush(&mut encoded, &mut utf16);
+                encoded.push_str("&-");
+            }
+            '\x20'..='\x7e' => {
+                flush(&mut encoded, &mut utf16);
+                encoded.push(c);
+            }
+            _ => {
+                let mut buffer = [0u16; 2];
+                for unit in c.encode_utf16(&mut buffer) {
+                    utf16.extend_from_slice(&unit.to_be_bytes());
+                }
+            }
+        }
+    }
+
+    flush(&mut encoded, &mut utf16);
+
+    encoded
+}
+
+/// Decode modified UTF-7 encoded `encoded`.
+fn decode_modified_utf7(encoded: &[u8]) -> Result<String, ModifiedUtf7Error> {
+    let mut decoded = String::with_capacity(encoded.len());
+    let mut at = 0;
+
+    while at < encoded.len() {
+        match encoded[at] {
+            b'&' => {
+                let end = encoded[at..]
+                    .iter()
+                    .position(|b| *b == b'-')
+                    .map(|end| at + end)
+                    .ok_or(ModifiedUtf7Error::Unterminated { at })?;
+
+                if end == at + 1 {
+                    decoded.push('&');
+                } else {
+                    let utf16 = MODIFIED_BASE64
+                        .decode(&encoded[at + 1..end])
+                        .map_err(|_| ModifiedUtf7Error::InvalidBase64 { at })?;
+
+                    if utf16.len() % 2 != 0 {
+                        return Err(ModifiedUtf7Error::InvalidUtf16 { at });
+                    }
+
+                    let units = utf16
+                        .chunks_exact(2)
+                        .map(|unit| u16::from_be_bytes([unit[0], unit[1]]));
+
+                    for c in char::decode_utf16(units) {
+                        decoded.push(c.map_err(|_| ModifiedUtf7Error::InvalidUtf16 { at })?);
+                    }
+                }
+
+                at = end + 1;
+            }
+            byte @ 0x20..=0x7e => {
+                decoded.push(byte as char);
+                at += 1;
+            }
+            byte => return Err(ModifiedUtf7Error::InvalidByteAt { byte, at }),
+        }
+    }
+
+    Ok(decoded)
+}
+
 /// Error-related types.
 pub mod error {
     use thiserror::Error;
@@ -285,6 +538,26 @@ pub mod error {
         #[error("Reserved: Please use one of the typed variants")]
         Reserved,
     }
+
+    #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+    pub enum ModifiedUtf7Error {
+        #[error("Invalid byte b'\\x{byte:02x}' at index {at}")]
+        InvalidByteAt { byte: u8, at: usize },
+        #[error("Unterminated shift sequence at index {at}")]
+        Unterminated { at: usize },
+        #[error("Invalid base64 in shift sequence at index {at}")]
+        InvalidBase64 { at: usize },
+        #[error("Invalid UTF-16 in shift sequence at index {at}")]
+        InvalidUtf16 { at: usize },
+    }
+
+    #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+    pub enum MailboxNameError {
+        #[error(transparent)]
+        ModifiedUtf7(#[from] ModifiedUtf7Error),
+        #[error("Invalid UTF-8 at index {at}")]
+        Utf8 { at: usize },
+    }
 }
 
 #[cfg(test)]
@@ -320,6 +593,208 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_list_mailbox_matches() {
+        let tests = [
+            // Examples from RFC 3501, 6.3.8.
+            ("comp.*", "comp.mail.misc", Some('.'), true),
+            ("comp.*", "comp", Some('.'), false),
+            ("comp.%", "comp.mail", Some('.'), true),
+            ("comp.%", "comp.mail.misc", Some('.'), false),
+            ("%", "comp", Some('.'), true),
+            ("%", "comp.mail", Some('.'), false),
+            ("*", "comp.mail.misc", Some('.'), true),
+            ("*", "", Some('.'), true),
+            // Without a delimiter, "%" works like "*".
+            ("%", "comp.mail", None, true),
+            ("%.misc", "comp.mail.misc", Some('.'), false),
+            ("*.misc", "comp.mail.misc", Some('.'), true),
+            ("%/%", "a/b", Some('/'), true),
+            ("c%p*", "comp.mail", Some('.'), true),
+            ("foo", "foo", Some('/'), true),
+            ("foo", "Foo", Some('/'), false),
+            ("foo", "foobar", Some('/'), false),
+            ("", "foo", Some('/'), false),
+            // INBOX is case-insensitive ...
+            ("INBOX", "INBOX", Some('/'), true),
+            ("inbox", "INBOX", Some('/'), true),
+            ("InBoX", "inbox", Some('/'), true),
+            ("in%", "INBOX", Some('/'), true),
+            ("%", "INBOX", Some('/'), true),
+            // ... but other names are not.
+            ("inbox/%", "INBOX/foo", Some('/'), false),
+            ("INBOX/%", "INBOX/foo", Some('/'), true),
+            // No exponential runtime.
+            ("*a*a*a*a*a*a*a*a*a*a*a*a*b", &"a".repeat(100), None, false),
+        ];
+
+        for (pattern, name, delimiter, expected) in tests {
+            let pattern = ListMailbox::try_from(pattern).unwrap();
+            assert_eq!(
+                pattern.matches(name, delimiter),
+                expected,
+                "{pattern:?} {name:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_mailbox_case_sensitivity() {
+        // INBOX is case-insensitive ...
+        assert_eq!(
+            Mailbox::try_from("inbox").unwrap(),
+            Mailbox::try_from("INBOX").unwrap()
+        );
+        assert_eq!(
+            Mailbox::try_from("Inbox").unwrap(),
+            Mailbox::try_from("iNbOx").unwrap()
+        );
+
+        // ... but other names are not.
+        assert_ne!(
+            Mailbox::try_from("Sent").unwrap(),
+            Mailbox::try_from("sent").unwrap()
+        );
+        assert_ne!(
+            Mailbox::try_from("INBOX.Sent").unwrap(),
+            Mailbox::try_from("inbox.sent").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_mailbox_modified_utf7() {
+        let tests = [
+            ("INBOX", "INBOX"),
+            ("Sent", "Sent"),
+            ("Entwürfe", "Entw&APw-rfe"),
+            ("Tom & Jerry", "Tom &- Jerry"),
+            ("&", "&-"),
+            ("~peter/mail/台北/日本語", "~peter/mail/&U,BTFw-/&ZeVnLIqe-"),
+            ("😀", "&2D3eAA-"),
+            ("ü&ü", "&APw-&-&APw-"),
+        ];
+
+        for (unicode, encoded) in tests {
+            let mailbox = Mailbox::from_unicode(unicode);
+            assert_eq!(Mailbox::try_from(encoded).unwrap(), mailbox);
+            assert_eq!(unicode, mailbox.to_unicode().unwrap());
+        }
+
+        assert_eq!(Mailbox::Inbox, Mailbox::from_unicode("inbox"));
+    }
+
+    #[test]
+    fn test_mailbox_hierarchy() {
+        let mailbox = Mailbox::try_from("a.b.c").unwrap();
+        assert_eq!(mailbox.components('.').unwrap(), ["a", "b", "c"]);
+        assert_eq!(mailbox.components('/').unwrap(), ["a.b.c"]);
+        assert_eq!(
+            mailbox.parent('.').unwrap(),
+            Some(Mailbox::try_from("a.b").unwrap())
+        );
+        assert_eq!(
+            Mailbox::try_from("a.b").unwrap().child("c", '.').unwrap(),
+            mailbox
+        );
+
+        // Top-level mailboxes have no parent.
+        assert_eq!(Mailbox::try_from("a").unwrap().parent('.').unwrap(), None);
+        assert_eq!(Mailbox::Inbox.parent('/').unwrap(), None);
+
+        // INBOX is special.
+        assert_eq!(
+            Mailbox::try_from("INBOX/Sent")
+                .unwrap()
+                .parent('/')
+                .unwrap(),
+            Some(Mailbox::Inbox)
+        );
+        assert_eq!(
+            Mailbox::Inbox.child("Sent", '/').unwrap(),
+            Mailbox::try_from("INBOX/Sent").unwrap()
+        );
+
+        // Names are decoded (and encoded) ...
+        let mailbox = Mailbox::try_from("Entw&APw-rfe/&U,BTFw-").unwrap();
+        assert_eq!(mailbox.components('/').unwrap(), ["Entwürfe", "台北"]);
+        assert_eq!(
+            mailbox.parent('/').unwrap(),
+            Some(Mailbox::try_from("Entw&APw-rfe").unwrap())
+        );
+        assert_eq!(
+            Mailbox::try_from("Entw&APw-rfe")
+                .unwrap()
+                .child("台北", '/')
+                .unwrap(),
+            mailbox
+        );
+
+        // ... and invalid names are rejected.
+        assert!(Mailbox::try_from("&AP-/a")
+            .unwrap()
+            .components('/')
+            .is_err());
+    }
+
+    #[test]
+    fn test_mailbox_modified_utf7_failing() {
+        let tests = [
+            ("&", ModifiedUtf7Error::Unterminated { at: 0 }),
+            ("A&APw", ModifiedUtf7Error::Unterminated { at: 1 }),
+            ("&APw=-", ModifiedUtf7Error::InvalidBase64 { at: 0 }),
+            ("&AP-", ModifiedUtf7Error::InvalidBase64 { at: 0 }),
+            ("&2D0-", ModifiedUtf7Error::InvalidUtf16 { at: 0 }),
+            (
+                "Entwürfe",
+                ModifiedUtf7Error::InvalidByteAt { byte: 0xc3, at: 4 },
+            ),
+        ];
+
+        for (test, expected) in tests {
+            let got = Mailbox::try_from(test).unwrap().to_unicode();
+            assert_eq!(Err(expected), got);
+        }
+    }
+
+    #[test]
+    fn test_mailbox_encoding() {
+        let name = "Entwürfe";
+
+        let utf7 = Mailbox::from_unicode_with(name, MailboxNameEncoding::ModifiedUtf7).unwrap();
+        let utf8 = Mailbox::from_unicode_with(name, MailboxNameEncoding::Utf8).unwrap();
+        assert_eq!(Mailbox::try_from("Entw&APw-rfe").unwrap(), utf7);
+        assert_eq!(Mailbox::try_from(name).unwrap(), utf8);
+        assert_ne!(utf7, utf8);
+
+        assert_eq!(
+            name,
+            utf7.to_unicode_with(MailboxNameEncoding::ModifiedUtf7)
+                .unwrap()
+        );
+        assert_eq!(
+            name,
+            utf8.to_unicode_with(MailboxNameEncoding::Utf8).unwrap()
+        );
+
+        // Modified UTF-7 is valid UTF-8 but is not decoded in UTF-8 mode ...
+        assert_eq!(
+            "Entw&APw-rfe",
+            utf7.to_unicode_with(MailboxNameEncoding::Utf8).unwrap()
+        );
+        // ... and UTF-8 is not valid modified UTF-7.
+        assert!(utf8
+            .to_unicode_with(MailboxNameEncoding::ModifiedUtf7)
+            .is_err());
+
+        assert_eq!(
+            Err(MailboxNameError::Utf8 { at: 1 }),
+            Mailbox::try_from(b"A\xffB".as_ref())
+                .unwrap()
+                .to_unicode_with(MailboxNameEncoding::Utf8)
+        );
+        assert!(Mailbox::from_unicode_with("A\x00B", MailboxNameEncoding::Utf8).is_err());
+    }
+
     #[test]
     fn test_conversion_mailbox_failing() {
         let tests = ["\x00", "A\x00", "\x00A"];
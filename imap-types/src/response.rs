@@ -18,6 +18,8 @@ use serde::{Deserialize, Serialize};
 use crate::core::{IString, NString};
 #[cfg(feature = "ext_metadata")]
 use crate::extensions::metadata::{MetadataCode, MetadataResponse};
+#[cfg(any(feature = "ext_mailbox_referrals", feature = "ext_login_referrals"))]
+use crate::extensions::referrals::ImapUrl;
 #[cfg(feature = "ext_sort_thread")]
 use crate::extensions::sort::SortAlgorithm;
 #[cfg(feature = "ext_sort_thread")]
@@ -26,6 +28,7 @@ use crate::extensions::thread::{Thread, ThreadingAlgorithm};
 use crate::extensions::uidplus::UidSet;
 use crate::{
     auth::AuthMechanism,
+    command::CommandBody,
     core::{impl_try_from, AString, Atom, Charset, QuotedChar, Tag, Text, Vec1},
     error::ValidationError,
     extensions::{
@@ -34,7 +37,7 @@ use crate::{
         quota::{QuotaGet, Resource},
     },
     fetch::MessageDataItem,
-    flag::{Flag, FlagNameAttribute, FlagPerm},
+    flag::{Flag, FlagFetch, FlagNameAttribute, FlagPerm, StoreResponse},
     mailbox::Mailbox,
     response::error::{ContinueError, FetchError},
     status::StatusDataItem,
@@ -89,6 +92,71 @@ impl<'a> Greeting<'a> {
             text: text.try_into()?,
         })
     }
+
+    /// Construct an `OK` greeting announcing the server's capabilities, e.g.,
+    /// `* OK [CAPABILITY IMAP4REV1 STARTTLS] ready`.
+    ///
+    /// This saves clients a separate `CAPABILITY` command.
+    pub fn ok_with_capabilities(
+        capabilities: Vec1<Capability<'a>>,
+        text: &'a str,
+    ) -> Result<Self, ValidationError> {
+        Self::ok(Some(Code::Capability(capabilities)), text)
+    }
+
+    /// Was the greeting `OK`, i.e., is a login needed?
+    pub fn is_ok(&self) -> bool {
+        self.kind == GreetingKind::Ok
+    }
+
+    /// Was the greeting `PREAUTH`?
+    pub fn is_preauth(&self) -> bool {
+        self.kind == GreetingKind::PreAuth
+    }
+
+    /// Was the greeting `BYE`, i.e., did the server reject the connection?
+    pub fn is_bye(&self) -> bool {
+        self.kind == GreetingKind::Bye
+    }
+
+    /// Is the connection authenticated right away (`PREAUTH`)?
+    ///
+    /// If so, the client starts in the authenticated state and must not send LOGIN or
+    /// AUTHENTICATE.
+    pub fn authenticated_on_connect(&self) -> bool {
+        self.is_preauth()
+    }
+
+    /// Get the response code, e.g., an initial `CAPABILITY` or an `ALERT`.
+    pub fn code(&self) -> Option<&Code<'a>> {
+        self.code.as_ref()
+    }
+
+    /// Get the capabilities announced in the greeting (if any).
+    pub fn capabilities(&self) -> Option<&Vec1<Capability<'a>>> {
+        match &self.code {
+            Some(Code::Capability(capabilities)) => Some(capabilities),
+            _ => None,
+        }
+    }
+
+    /// Get the server the client should connect to instead (if any).
+    ///
+    /// A server may refuse a connection with, e.g., `* BYE [REFERRAL imap://host/] Server moved`
+    /// (RFC 2221).
+    ///
+    /// If the server sent multiple URLs, the first one is returned (see [`Greeting::code`]).
+    #[cfg(any(feature = "ext_mailbox_referrals", feature = "ext_login_referrals"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(feature = "ext_mailbox_referrals", feature = "ext_login_referrals")))
+    )]
+    pub fn referral(&self) -> Option<&ImapUrl<'a>> {
+        match &self.code {
+            Some(Code::Referral(urls)) => Some(urls.as_ref().first()?),
+            _ => None,
+        }
+    }
 }
 
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
@@ -133,6 +201,35 @@ pub enum Response<'a> {
     Status(Status<'a>),
 }
 
+impl<'a> Response<'a> {
+    /// Does the response complete a command, i.e., is it a tagged status response?
+    ///
+    /// Data, untagged status responses, and command continuation requests are intermediate.
+    pub fn is_completion(&self) -> bool {
+        self.tag().is_some()
+    }
+
+    /// Get the tag of a tagged status response.
+    pub fn tag(&self) -> Option<&Tag<'a>> {
+        match self {
+            Self::Status(Status::Tagged(Tagged { tag, .. })) => Some(tag),
+            _ => None,
+        }
+    }
+
+    /// Get the response code of a status response or command continuation request.
+    pub fn code(&self) -> Option<&Code<'a>> {
+        match self {
+            Self::Status(status) => status.code(),
+            Self::CommandContinuationRequest(CommandContinuationRequest::Basic(basic)) => {
+                basic.code()
+            }
+            Self::CommandContinuationRequest(CommandContinuationRequest::Base64(_))
+            | Self::Data(_) => None,
+        }
+    }
+}
+
 /// Status response.
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -226,6 +323,24 @@ pub struct Bye<'a> {
     pub text: Text<'a>,
 }
 
+impl<'a> Bye<'a> {
+    /// Get the server the client should connect to instead (if any), e.g.,
+    /// `* BYE [REFERRAL imap://host/] Server moved` (RFC 2221).
+    ///
+    /// If the server sent multiple URLs, the first one is returned.
+    #[cfg(any(feature = "ext_mailbox_referrals", feature = "ext_login_referrals"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(feature = "ext_mailbox_referrals", feature = "ext_login_referrals")))
+    )]
+    pub fn referral(&self) -> Option<&ImapUrl<'a>> {
+        match &self.code {
+            Some(Code::Referral(urls)) => Some(urls.as_ref().first()?),
+            _ => None,
+        }
+    }
+}
+
 impl<'a> Status<'a> {
     pub fn new<T>(
         tag: Option<Tag<'a>>,
@@ -249,6 +364,10 @@ impl<'a> Status<'a> {
     }
 
     // FIXME(API)
+    /// Create an `OK` status.
+    ///
+    /// Fails when `text` isn't a valid [`Text`], e.g., when it is empty or contains CR, LF, or NUL.
+    /// Use `.unwrap()` (or `.expect(...)`) when `text` is known to be valid.
     pub fn ok<T>(tag: Option<Tag<'a>>, code: Option<Code<'a>>, text: T) -> Result<Self, T::Error>
     where
         T: TryInto<Text<'a>>,
@@ -257,6 +376,10 @@ impl<'a> Status<'a> {
     }
 
     // FIXME(API)
+    /// Create a `NO` status.
+    ///
+    /// Fails when `text` isn't a valid [`Text`], e.g., when it is empty or contains CR, LF, or NUL.
+    /// Use `.unwrap()` (or `.expect(...)`) when `text` is known to be valid.
     pub fn no<T>(tag: Option<Tag<'a>>, code: Option<Code<'a>>, text: T) -> Result<Self, T::Error>
     where
         T: TryInto<Text<'a>>,
@@ -265,6 +388,10 @@ impl<'a> Status<'a> {
     }
 
     // FIXME(API)
+    /// Create a `BAD` status.
+    ///
+    /// Fails when `text` isn't a valid [`Text`], e.g., when it is empty or contains CR, LF, or NUL.
+    /// Use `.unwrap()` (or `.expect(...)`) when `text` is known to be valid.
     pub fn bad<T>(tag: Option<Tag<'a>>, code: Option<Code<'a>>, text: T) -> Result<Self, T::Error>
     where
         T: TryInto<Text<'a>>,
@@ -291,7 +418,7 @@ impl<'a> Status<'a> {
         }
     }
 
-    pub fn code(&self) -> Option<&Code> {
+    pub fn code(&self) -> Option<&Code<'a>> {
         match self {
             Self::Untagged(StatusBody { code, .. })
             | Self::Tagged(Tagged {
@@ -312,6 +439,40 @@ impl<'a> Status<'a> {
             | Self::Bye(Bye { text, .. }) => text,
         }
     }
+
+    /// Returns `true` if this is a `NO [TRYCREATE]` status.
+    ///
+    /// A server responds with `NO [TRYCREATE]` when an APPEND, COPY, or MOVE failed because the
+    /// destination mailbox doesn't exist. The client may then create the mailbox
+    /// (see [`CommandBody::create_for`]) and retry the original command:
+    ///
+    /// ```text
+    /// C: A1 APPEND foo {11}
+    /// C: Hello, IMAP
+    /// S: A1 NO [TRYCREATE] Mailbox doesn't exist
+    /// C: A2 CREATE foo
+    /// S: A2 OK CREATE completed
+    /// C: A3 APPEND foo {11}
+    /// C: Hello, IMAP
+    /// S: A3 OK APPEND completed
+    /// ```
+    pub fn is_trycreate(&self) -> bool {
+        matches!(
+            self,
+            Self::Untagged(StatusBody {
+                kind: StatusKind::No,
+                code: Some(Code::TryCreate),
+                ..
+            }) | Self::Tagged(Tagged {
+                body: StatusBody {
+                    kind: StatusKind::No,
+                    code: Some(Code::TryCreate),
+                    ..
+                },
+                ..
+            })
+        )
+    }
 }
 
 /// ## 7.2 - 7.4 Server and Mailbox Status; Mailbox Size; Message Status
@@ -549,6 +710,9 @@ pub enum Data<'a> {
         /// Sequence number.
         seq: NonZeroU32,
         /// Message data items.
+        ///
+        /// Note: `msg-att` requires at least one item. Thus, `* 23 FETCH ()` is rejected by the
+        /// parser and can't be constructed.
         items: Vec1<MessageDataItem<'a>>,
     },
 
@@ -583,6 +747,22 @@ pub enum Data<'a> {
         mailbox: Mailbox<'a>,
         items: MetadataResponse<'a>,
     },
+
+    #[cfg(feature = "legacy")]
+    /// XLIST response (legacy, e.g., older Gmail servers).
+    ///
+    /// Identical in format to the LIST response.
+    XList {
+        /// Name attributes
+        items: Vec<FlagNameAttribute<'a>>,
+        /// Hierarchy delimiter
+        delimiter: Option<QuotedChar>,
+        /// Name
+        mailbox: Mailbox<'a>,
+    },
+
+    /// An (unknown) untagged response, e.g., from a vendor extension.
+    Other(DataOther<'a>),
 }
 
 impl<'a> Data<'a> {
@@ -632,6 +812,179 @@ impl<'a> Data<'a> {
 
         Ok(Self::Fetch { seq, items })
     }
+
+    /// Construct a FETCH response to a `UID FETCH` command.
+    ///
+    /// A server must include the `UID` item in a `UID FETCH` response, even when it wasn't
+    /// requested. Thus, `UID <uid>` is prepended to `items` unless `items` already contain a
+    /// `UID` item. An existing `UID` item is set to `uid`, so that the response can't contradict
+    /// the caller.
+    pub fn uid_fetch<S>(
+        seq: S,
+        uid: NonZeroU32,
+        mut items: Vec<MessageDataItem<'a>>,
+    ) -> Result<Self, S::Error>
+    where
+        S: TryInto<NonZeroU32>,
+    {
+        let seq = seq.try_into()?;
+
+        let mut found = false;
+
+        for item in items.iter_mut() {
+            if let MessageDataItem::Uid(existing) = item {
+                *existing = uid;
+                found = true;
+            }
+        }
+
+        if !found {
+            items.insert(0, MessageDataItem::Uid(uid));
+        }
+
+        // Note: `items` contains at least the `UID` item.
+        Ok(Self::Fetch {
+            seq,
+            items: Vec1::try_from(items).unwrap(),
+        })
+    }
+
+    /// Get the number of messages of an `EXISTS` response.
+    pub fn as_exists(&self) -> Option<u32> {
+        match self {
+            Self::Exists(exists) => Some(*exists),
+            _ => None,
+        }
+    }
+
+    /// Get the number of messages of a `RECENT` response.
+    pub fn as_recent(&self) -> Option<u32> {
+        match self {
+            Self::Recent(recent) => Some(*recent),
+            _ => None,
+        }
+    }
+
+    /// Get the flags of a `FLAGS` response.
+    pub fn as_flags(&self) -> Option<&[Flag<'a>]> {
+        match self {
+            Self::Flags(flags) => Some(flags),
+            _ => None,
+        }
+    }
+
+    /// Get the sequence number and message data items of a `FETCH` response.
+    pub fn as_fetch(&self) -> Option<(NonZeroU32, &[MessageDataItem<'a>])> {
+        match self {
+            Self::Fetch { seq, items } => Some((*seq, items.as_ref())),
+            _ => None,
+        }
+    }
+
+    /// Get the flags of a `FETCH` response (if it contains a `FLAGS` item).
+    pub fn fetch_flags(&self) -> Option<&[FlagFetch<'a>]> {
+        self.as_fetch()?.1.iter().find_map(|item| match item {
+            MessageDataItem::Flags(flags) => Some(flags.as_ref()),
+            _ => None,
+        })
+    }
+
+    /// Get the UID of a `FETCH` response (if it contains a `UID` item).
+    pub fn fetch_uid(&self) -> Option<NonZeroU32> {
+        self.as_fetch()?.1.iter().find_map(|item| match item {
+            MessageDataItem::Uid(uid) => Some(*uid),
+            _ => None,
+        })
+    }
+
+    /// Check if this is a unilateral `FETCH` response, i.e., an update a server sends on its own,
+    /// e.g., because another client changed the flags of a message.
+    ///
+    /// `command` is the command in progress (if any). A `FETCH` response is solicited by `FETCH`
+    /// and `STORE` (but not `STORE.SILENT`). All other `FETCH` responses are unilateral and should
+    /// be applied to the local state, see [`Data::fetch_flags`] and [`Data::fetch_uid`].
+    ///
+    /// Note: Without a unique identifier (`UID`) a unilateral `FETCH` response for a message that
+    /// was also requested by the command in progress can't be told apart from the solicited one.
+    pub fn is_unilateral_fetch(&self, command: Option<&CommandBody>) -> bool {
+        if !matches!(self, Self::Fetch { .. }) {
+            return false;
+        }
+
+        !matches!(
+            command,
+            Some(CommandBody::Fetch { .. })
+                | Some(CommandBody::Store {
+                    response: StoreResponse::Answer,
+                    ..
+                })
+        )
+    }
+}
+
+/// An (unknown) untagged response.
+///
+/// The value is kept as it was received, i.e., including quotes, literal prefixes, or parentheses.
+///
+/// It's guaranteed that this type can't represent any response from [`Data`].
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct DataOther<'a> {
+    name: Atom<'a>,
+    value: Cow<'a, [u8]>,
+}
+
+// We want a more readable `Debug` implementation.
+impl<'a> Debug for DataOther<'a> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        struct BStr<'a>(&'a Cow<'a, [u8]>);
+
+        impl<'a> Debug for BStr<'a> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                write!(
+                    f,
+                    "b\"{}\"",
+                    crate::utils::escape_byte_string(self.0.as_ref())
+                )
+            }
+        }
+
+        f.debug_struct("DataOther")
+            .field("name", &self.name)
+            .field("value", &BStr(&self.value))
+            .finish()
+    }
+}
+
+impl<'a> DataOther<'a> {
+    /// Constructs an unsupported response without validation.
+    ///
+    /// # Warning: IMAP conformance
+    ///
+    /// The caller must ensure that `name` is not the name of a supported response and that
+    /// `value` is valid (or empty). Failing to do so may create invalid/unparsable IMAP messages,
+    /// or even produce unintended protocol flows. Do not call this constructor with untrusted data.
+    #[cfg(feature = "unvalidated")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unvalidated")))]
+    pub fn unvalidated<V>(name: Atom<'a>, value: V) -> Self
+    where
+        V: Into<Cow<'a, [u8]>>,
+    {
+        Self {
+            name,
+            value: value.into(),
+        }
+    }
+
+    pub fn name(&self) -> &Atom<'a> {
+        &self.name
+    }
+
+    /// Returns the raw (serialized) value, i.e., everything after the name (and space).
+    pub fn value(&self) -> &[u8] {
+        self.value.as_ref()
+    }
 }
 
 /// ## 7.5. Server Responses - Command Continuation Request
@@ -661,6 +1014,9 @@ impl<'a> Data<'a> {
 #[doc(alias = "ContinuationRequest")]
 pub enum CommandContinuationRequest<'a> {
     Basic(CommandContinuationRequestBasic<'a>),
+    /// Raw (decoded) data, e.g., a SASL challenge.
+    ///
+    /// The data is base64-encoded on the wire (and decoded when parsed).
     Base64(Cow<'a, [u8]>),
 }
 
@@ -674,6 +1030,9 @@ impl<'a> CommandContinuationRequest<'a> {
         )?))
     }
 
+    /// Create a continuation request with raw (not yet encoded) data, e.g., a SASL challenge.
+    ///
+    /// The data is base64-encoded during encoding.
     pub fn base64<'data: 'a, D>(data: D) -> Self
     where
         D: Into<Cow<'data, [u8]>>,
@@ -826,13 +1185,14 @@ pub enum Code<'a> {
     Unseen(NonZeroU32),
 
     /// IMAP4 Login Referrals (RFC 2221)
-    // TODO(misuse): the imap url is more complicated than that...
+    ///
+    /// Note: IMAP4 Mailbox Referrals (RFC 2193) allow one or more URLs.
     #[cfg(any(feature = "ext_mailbox_referrals", feature = "ext_login_referrals"))]
     #[cfg_attr(
         docsrs,
         doc(cfg(any(feature = "ext_mailbox_referrals", feature = "ext_login_referrals")))
     )]
-    Referral(Cow<'a, str>),
+    Referral(Vec1<ImapUrl<'a>>),
 
     CompressionActive,
 
@@ -921,6 +1281,10 @@ impl<'a> Code<'a> {
 
 /// An (unknown) code.
 ///
+/// Holds the raw bytes between `[` and `]`, i.e., the code's name and its arguments, and is
+/// serialized verbatim. Thus, whitespace, brackets, and parentheses in arguments survive a
+/// round-trip.
+///
 /// It's guaranteed that this type can't represent any code from [`Code`].
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -968,6 +1332,11 @@ impl<'a> CodeOther<'a> {
     }
 }
 
+/// Capability.
+///
+/// Known capabilities are matched case-insensitively and encoded in their canonical form, e.g.,
+/// `imap4rev1` becomes [`Capability::Imap4Rev1`] and is encoded as `IMAP4REV1`. Unknown
+/// capabilities ([`Capability::Other`]) keep their original casing.
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1029,6 +1398,9 @@ pub enum Capability<'a> {
     #[cfg(feature = "ext_uidplus")]
     /// UIDPLUS extension (RFC 4351)
     UidPlus,
+    #[cfg(feature = "ext_acl")]
+    /// ACL extension (RFC 4314)
+    Acl,
     /// Other/Unknown
     Other(CapabilityOther<'a>),
 }
@@ -1073,11 +1445,76 @@ impl<'a> Display for Capability<'a> {
             Self::Binary => write!(f, "BINARY"),
             #[cfg(feature = "ext_uidplus")]
             Self::UidPlus => write!(f, "UIDPLUS"),
+            #[cfg(feature = "ext_acl")]
+            Self::Acl => write!(f, "ACL"),
             Self::Other(other) => write!(f, "{}", other.0),
         }
     }
 }
 
+impl<'a> Capability<'a> {
+    /// Capabilities every IMAP4rev1 server must announce, i.e., `IMAP4REV1`.
+    pub fn base_imap4rev1() -> Vec<Capability<'a>> {
+        vec![Capability::Imap4Rev1]
+    }
+
+    /// Capabilities required by the security considerations of RFC 3501, i.e., `IMAP4REV1`,
+    /// `STARTTLS`, `LOGINDISABLED`, and `AUTH=PLAIN`.
+    ///
+    /// Note: `LOGINDISABLED` must only be announced as long as the connection is not protected.
+    #[cfg(feature = "starttls")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "starttls")))]
+    pub fn recommended_tls() -> Vec<Capability<'a>> {
+        vec![
+            Capability::Imap4Rev1,
+            Capability::StartTls,
+            Capability::LoginDisabled,
+            Capability::Auth(AuthMechanism::Plain),
+        ]
+    }
+}
+
+impl<'a> Vec1<Capability<'a>> {
+    /// Returns the capabilities in a canonical form.
+    ///
+    /// Capabilities are case-folded and deduplicated, `IMAP4REV1` comes first, followed by the
+    /// `AUTH=` mechanisms and all other capabilities (each in alphabetical order). This makes it
+    /// possible to compare capability announcements, e.g., across connections.
+    pub fn normalized(&self) -> Vec<Capability<'a>> {
+        fn rank(capability: &Capability) -> u8 {
+            match capability {
+                Capability::Imap4Rev1 => 0,
+                Capability::Auth(_) => 1,
+                _ => 2,
+            }
+        }
+
+        let mut normalized: Vec<(u8, String, Capability<'a>)> = self
+            .as_ref()
+            .iter()
+            .map(|capability| {
+                let folded = capability.to_string().to_ascii_uppercase();
+                // Re-parsing the case-folded form unifies, e.g., `Capability::Other`s.
+                let capability = Atom::try_from(folded.clone())
+                    .map(Capability::from)
+                    .unwrap_or_else(|_| capability.clone());
+
+                (rank(&capability), folded, capability)
+            })
+            .collect();
+
+        normalized.sort_by(|(rank_a, folded_a, _), (rank_b, folded_b, _)| {
+            rank_a.cmp(rank_b).then_with(|| folded_a.cmp(folded_b))
+        });
+        normalized.dedup_by(|(_, folded_a, _), (_, folded_b, _)| folded_a == folded_b);
+
+        normalized
+            .into_iter()
+            .map(|(_, _, capability)| capability)
+            .collect()
+    }
+}
+
 impl_try_from!(Atom<'a>, 'a, &'a [u8], Capability<'a>);
 impl_try_from!(Atom<'a>, 'a, Vec<u8>, Capability<'a>);
 impl_try_from!(Atom<'a>, 'a, &'a str, Capability<'a>);
@@ -1141,6 +1578,8 @@ impl<'a> From<Atom<'a>> for Capability<'a> {
             "unselect" => Self::Unselect,
             #[cfg(feature = "ext_uidplus")]
             "uidplus" => Self::UidPlus,
+            #[cfg(feature = "ext_acl")]
+            "acl" => Self::Acl,
             _ => {
                 // TODO(efficiency)
                 if let Some((left, right)) = split_once_cow(cow.clone(), "=") {
@@ -1197,6 +1636,12 @@ impl<'a> From<Atom<'a>> for Capability<'a> {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct CapabilityOther<'a>(Atom<'a>);
 
+impl<'a> CapabilityOther<'a> {
+    pub fn inner(&self) -> &Atom<'a> {
+        &self.0
+    }
+}
+
 /// Error-related types.
 pub mod error {
     use thiserror::Error;
@@ -1221,6 +1666,7 @@ pub mod error {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{fetch::Macro, flag::StoreType};
 
     #[test]
     fn test_conversion_data() {
@@ -1228,6 +1674,349 @@ mod tests {
         let _ = Data::fetch(1, vec![MessageDataItem::Rfc822Size(123)]).unwrap();
     }
 
+    #[test]
+    fn test_conversion_data_fetch_empty() {
+        assert!(Data::fetch(23, vec![]).is_err());
+    }
+
+    #[test]
+    fn test_conversion_data_uid_fetch() {
+        let uid = NonZeroU32::new(42).unwrap();
+
+        // The `UID` item is added ...
+        let got = Data::uid_fetch(1, uid, vec![MessageDataItem::Rfc822Size(123)]).unwrap();
+        assert_eq!(
+            got,
+            Data::fetch(
+                1,
+                vec![MessageDataItem::Uid(uid), MessageDataItem::Rfc822Size(123)]
+            )
+            .unwrap()
+        );
+
+        let got = Data::uid_fetch(1, uid, vec![]).unwrap();
+        assert_eq!(
+            got,
+            Data::fetch(1, vec![MessageDataItem::Uid(uid)]).unwrap()
+        );
+
+        // ... but not twice.
+        let items = vec![MessageDataItem::Rfc822Size(123), MessageDataItem::Uid(uid)];
+        let got = Data::uid_fetch(1, uid, items.clone()).unwrap();
+        assert_eq!(got, Data::fetch(1, items).unwrap());
+
+        // A contradicting `UID` item is replaced.
+        let items = vec![
+            MessageDataItem::Rfc822Size(123),
+            MessageDataItem::Uid(NonZeroU32::new(7).unwrap()),
+        ];
+        let got = Data::uid_fetch(1, uid, items).unwrap();
+        assert_eq!(
+            got,
+            Data::fetch(
+                1,
+                vec![MessageDataItem::Rfc822Size(123), MessageDataItem::Uid(uid)]
+            )
+            .unwrap()
+        );
+        assert_eq!(got.fetch_uid(), Some(uid));
+
+        assert!(Data::uid_fetch(0, uid, vec![]).is_err());
+    }
+
+    #[test]
+    fn test_data_accessors() {
+        let seq = NonZeroU32::new(1).unwrap();
+        let items = vec![MessageDataItem::Rfc822Size(123)];
+
+        let tests = [
+            Data::Exists(42),
+            Data::Recent(7),
+            Data::Flags(vec![Flag::Seen, Flag::Deleted]),
+            Data::fetch(seq, items.clone()).unwrap(),
+            Data::Search(vec![seq]),
+        ];
+
+        let got: Vec<_> = tests
+            .iter()
+            .map(|data| {
+                (
+                    data.as_exists(),
+                    data.as_recent(),
+                    data.as_flags(),
+                    data.as_fetch(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            got,
+            vec![
+                (Some(42), None, None, None),
+                (None, Some(7), None, None),
+                (None, None, Some([Flag::Seen, Flag::Deleted].as_ref()), None),
+                (None, None, None, Some((seq, items.as_ref()))),
+                (None, None, None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_data_unilateral_fetch() {
+        // `* 3 FETCH (UID 42 FLAGS (\Seen \Flagged))`
+        let update = Data::fetch(
+            3,
+            vec![
+                MessageDataItem::Uid(NonZeroU32::new(42).unwrap()),
+                MessageDataItem::Flags(vec![
+                    FlagFetch::Flag(Flag::Seen),
+                    FlagFetch::Flag(Flag::Flagged),
+                ]),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            update.fetch_flags(),
+            Some([FlagFetch::Flag(Flag::Seen), FlagFetch::Flag(Flag::Flagged)].as_ref())
+        );
+        assert_eq!(update.fetch_uid(), NonZeroU32::new(42));
+        assert_eq!(Data::Exists(1).fetch_flags(), None);
+        assert_eq!(
+            Data::fetch(1, vec![MessageDataItem::Rfc822Size(1)])
+                .unwrap()
+                .fetch_uid(),
+            None
+        );
+
+        let fetch = CommandBody::fetch("1:*", Macro::Fast, false).unwrap();
+        let store = |response| CommandBody::Store {
+            sequence_set: "1".try_into().unwrap(),
+            kind: StoreType::Add,
+            response,
+            flags: vec![Flag::Seen],
+            uid: true,
+        };
+
+        assert!(update.is_unilateral_fetch(None));
+        assert!(update.is_unilateral_fetch(Some(&CommandBody::Noop)));
+        assert!(update.is_unilateral_fetch(Some(&store(StoreResponse::Silent))));
+        assert!(!update.is_unilateral_fetch(Some(&fetch)));
+        assert!(!update.is_unilateral_fetch(Some(&store(StoreResponse::Answer))));
+        assert!(!Data::Exists(1).is_unilateral_fetch(None));
+    }
+
+    #[test]
+    fn test_status_is_trycreate() {
+        let tag = || Some(Tag::try_from("A1").unwrap());
+
+        let trycreate = Status::no(tag(), Some(Code::TryCreate), "Mailbox doesn't exist").unwrap();
+        assert!(trycreate.is_trycreate());
+        assert!(Status::no(None, Some(Code::TryCreate), "...")
+            .unwrap()
+            .is_trycreate());
+
+        assert!(!Status::no(tag(), None, "...").unwrap().is_trycreate());
+        assert!(!Status::no(tag(), Some(Code::Alert), "...")
+            .unwrap()
+            .is_trycreate());
+        assert!(!Status::ok(tag(), Some(Code::TryCreate), "...")
+            .unwrap()
+            .is_trycreate());
+        assert!(!Status::bad(tag(), Some(Code::TryCreate), "...")
+            .unwrap()
+            .is_trycreate());
+        assert!(!Status::bye(Some(Code::TryCreate), "...")
+            .unwrap()
+            .is_trycreate());
+
+        let append = CommandBody::append("foo", vec![], None, b"Hello, IMAP".as_ref()).unwrap();
+        assert_eq!(
+            append.create_for(),
+            Some(CommandBody::create("foo").unwrap())
+        );
+        let copy = CommandBody::copy("1:*", "bar", true).unwrap();
+        assert_eq!(copy.create_for(), Some(CommandBody::create("bar").unwrap()));
+        assert_eq!(CommandBody::Noop.create_for(), None);
+    }
+
+    #[test]
+    fn test_conversion_status_invalid_text() {
+        let tag = || Some(Tag::try_from("A1").unwrap());
+
+        for text in ["", "\r", "\n", "a\r\nb", "\x00"] {
+            assert!(Status::ok(tag(), None, text).is_err());
+            assert!(Status::no(tag(), None, text).is_err());
+            assert!(Status::bad(tag(), None, text).is_err());
+            assert!(Status::ok(None, None, text).is_err());
+            assert!(Status::bye(None, text).is_err());
+        }
+
+        assert!(Status::ok(tag(), None, "done").is_ok());
+        assert!(Status::no(tag(), Some(Code::TryCreate), "\x01").is_ok());
+    }
+
+    #[test]
+    fn test_response_is_completion() {
+        let tag = Tag::try_from("A1").unwrap();
+
+        let response = Response::Status(Status::ok(Some(tag.clone()), None, "done").unwrap());
+        assert!(response.is_completion());
+        assert_eq!(response.tag(), Some(&tag));
+
+        let response = Response::Status(Status::ok(None, None, "hello").unwrap());
+        assert!(!response.is_completion());
+        assert_eq!(response.tag(), None);
+
+        let response = Response::Data(Data::Exists(42));
+        assert!(!response.is_completion());
+        assert_eq!(response.tag(), None);
+
+        let response = Response::CommandContinuationRequest(
+            CommandContinuationRequest::basic(None, "go").unwrap(),
+        );
+        assert!(!response.is_completion());
+        assert_eq!(response.tag(), None);
+    }
+
+    #[test]
+    fn test_response_code() {
+        let tests = [
+            (
+                Response::Status(
+                    Status::ok(
+                        Some(Tag::try_from("A1").unwrap()),
+                        Some(Code::ReadWrite),
+                        "...",
+                    )
+                    .unwrap(),
+                ),
+                Some(Code::ReadWrite),
+            ),
+            (
+                Response::Status(
+                    Status::no(
+                        Some(Tag::try_from("A1").unwrap()),
+                        Some(Code::TryCreate),
+                        "...",
+                    )
+                    .unwrap(),
+                ),
+                Some(Code::TryCreate),
+            ),
+            (
+                Response::Status(Status::bad(None, None, "...").unwrap()),
+                None,
+            ),
+            (
+                Response::Status(Status::ok(None, Some(Code::Alert), "...").unwrap()),
+                Some(Code::Alert),
+            ),
+            (
+                Response::Status(Status::bye(Some(Code::Alert), "...").unwrap()),
+                Some(Code::Alert),
+            ),
+            (
+                Response::CommandContinuationRequest(
+                    CommandContinuationRequest::basic(Some(Code::Parse), "...").unwrap(),
+                ),
+                Some(Code::Parse),
+            ),
+            (
+                Response::CommandContinuationRequest(CommandContinuationRequest::base64(
+                    b"challenge".as_ref(),
+                )),
+                None,
+            ),
+            (Response::Data(Data::Exists(42)), None),
+        ];
+
+        for (response, expected) in tests {
+            assert_eq!(response.code(), expected.as_ref());
+        }
+    }
+
+    #[test]
+    fn test_greeting_kinds() {
+        let capabilities = Vec1::from(Capability::Imap4Rev1);
+
+        let greeting = Greeting::ok_with_capabilities(capabilities.clone(), "ready").unwrap();
+        assert!(greeting.is_ok());
+        assert!(!greeting.is_preauth());
+        assert!(!greeting.is_bye());
+        assert!(!greeting.authenticated_on_connect());
+        assert_eq!(greeting.capabilities(), Some(&capabilities));
+
+        let greeting = Greeting::preauth(None, "logged in").unwrap();
+        assert!(!greeting.is_ok());
+        assert!(greeting.is_preauth());
+        assert!(!greeting.is_bye());
+        assert!(greeting.authenticated_on_connect());
+        assert_eq!(greeting.code(), None);
+        assert_eq!(greeting.capabilities(), None);
+
+        let greeting = Greeting::bye(Some(Code::Alert), "go away").unwrap();
+        assert!(!greeting.is_ok());
+        assert!(!greeting.is_preauth());
+        assert!(greeting.is_bye());
+        assert!(!greeting.authenticated_on_connect());
+        assert_eq!(greeting.code(), Some(&Code::Alert));
+        assert_eq!(greeting.capabilities(), None);
+    }
+
+    #[test]
+    fn test_capability_case_insensitive() {
+        for test in ["IMAP4REV1", "imap4rev1", "Imap4Rev1"] {
+            assert_eq!(Capability::try_from(test).unwrap(), Capability::Imap4Rev1);
+        }
+
+        let upper = Capability::try_from("XPIG-LATIN").unwrap();
+        let lower = Capability::try_from("xpig-latin").unwrap();
+        assert_ne!(upper, lower);
+
+        match (&upper, &lower) {
+            (Capability::Other(upper), Capability::Other(lower)) => {
+                assert!(upper.inner().eq_ignore_case(lower.inner().inner()));
+                assert_ne!(upper.inner(), lower.inner());
+            }
+            _ => panic!("expected unknown capabilities"),
+        }
+    }
+
+    #[test]
+    fn test_capabilities_normalized() {
+        let a = Vec1::try_from(vec![
+            Capability::Idle,
+            Capability::Auth(AuthMechanism::Plain),
+            Capability::try_from("x-foo").unwrap(),
+            Capability::Imap4Rev1,
+            Capability::Auth(AuthMechanism::Login),
+        ])
+        .unwrap();
+        let b = Vec1::try_from(vec![
+            Capability::Imap4Rev1,
+            Capability::try_from("X-FOO").unwrap(),
+            Capability::Auth(AuthMechanism::Login),
+            Capability::try_from("idle").unwrap(),
+            Capability::Auth(AuthMechanism::Plain),
+            Capability::Idle,
+        ])
+        .unwrap();
+
+        assert_ne!(a, b);
+        assert_eq!(a.normalized(), b.normalized());
+        assert_eq!(
+            vec![
+                Capability::Imap4Rev1,
+                Capability::Auth(AuthMechanism::Login),
+                Capability::Auth(AuthMechanism::Plain),
+                Capability::Idle,
+                Capability::try_from("X-FOO").unwrap(),
+            ],
+            a.normalized()
+        );
+    }
+
     #[test]
     fn test_conversion_continue_failing() {
         let tests = [
@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     core::{AString, Atom, Vec1},
     datetime::NaiveDate,
-    sequence::SequenceSet,
+    sequence::{SeqSequenceSet, SequenceSet, UidSequenceSet},
 };
 
 /// The defined search keys.
@@ -170,3 +170,15 @@ impl<'a> SearchKey<'a> {
         Self::Uid(sequence_set.into())
     }
 }
+
+impl<'a> From<SeqSequenceSet> for SearchKey<'a> {
+    fn from(sequence_set: SeqSequenceSet) -> Self {
+        Self::SequenceSet(sequence_set.into())
+    }
+}
+
+impl<'a> From<UidSequenceSet> for SearchKey<'a> {
+    fn from(sequence_set: UidSequenceSet) -> Self {
+        Self::Uid(sequence_set.into())
+    }
+}
@@ -2,8 +2,16 @@
 //!
 //! This module provides a `Secret<T>` ensuring that sensitive values are not
 //! `Debug`-printed by accident.
+//!
+//! With the `zeroize` feature, secrets can be overwritten with zeros using
+//! `zeroize::Zeroize`. Note that `Secret` does not do this automatically on drop: A `Drop` impl
+//! would force borrowed input buffers to outlive every parsed command, which breaks the usual
+//! "decode, then consume the buffer" loop. Wrap an owned secret in `zeroize::Zeroizing` to
+//! overwrite it on drop instead, e.g., `Zeroizing::new(secret.into_static())`.
 
-use std::fmt::{Debug, Formatter};
+#[cfg(feature = "zeroize")]
+use std::borrow::Cow;
+use std::fmt::{Debug, Display, Formatter};
 
 #[cfg(feature = "arbitrary")]
 use arbitrary::Arbitrary;
@@ -11,6 +19,11 @@ use arbitrary::Arbitrary;
 use bounded_static::ToStatic;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+#[cfg(feature = "zeroize")]
+use crate::core::{AString, AtomExt, IString, Literal, Quoted};
 
 /// A wrapper to ensure that secrets are redacted during `Debug`-printing.
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
@@ -42,13 +55,71 @@ where
     T: Debug,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        #[cfg(not(debug_assertions))]
-        return write!(f, "/* REDACTED */");
-        #[cfg(debug_assertions)]
-        return self.0.fmt(f);
+        // Note: Debug builds are redacted, too. Logs of those end up in bug reports.
+        write!(f, "/* REDACTED */")
+    }
+}
+
+impl<T> Display for Secret<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "/* REDACTED */")
+    }
+}
+
+/// Overwrites owned data with zeros. Borrowed data is left untouched because it is owned by
+/// someone else.
+///
+/// Note: An atom can't be empty. Thus, a wiped atom is replaced with an empty quoted string.
+#[cfg(feature = "zeroize")]
+impl Zeroize for Secret<AString<'_>> {
+    fn zeroize(&mut self) {
+        match &mut self.0 {
+            AString::Atom(AtomExt(inner)) => {
+                if zeroize_cow_str(inner) {
+                    self.0 = AString::String(IString::Quoted(Quoted(Cow::Borrowed(""))));
+                }
+            }
+            AString::String(IString::Quoted(Quoted(inner))) => {
+                zeroize_cow_str(inner);
+            }
+            AString::String(IString::Literal(Literal { data, .. })) => {
+                zeroize_cow_bytes(data);
+            }
+        }
     }
 }
 
+/// Overwrites owned data with zeros. Borrowed data is left untouched because it is owned by
+/// someone else.
+#[cfg(feature = "zeroize")]
+impl Zeroize for Secret<Cow<'_, [u8]>> {
+    fn zeroize(&mut self) {
+        zeroize_cow_bytes(&mut self.0);
+    }
+}
+
+/// Returns `true` if the data was owned (and thus wiped).
+#[cfg(feature = "zeroize")]
+fn zeroize_cow_str(cow: &mut Cow<str>) -> bool {
+    if let Cow::Owned(inner) = cow {
+        inner.zeroize();
+        return true;
+    }
+
+    false
+}
+
+/// Returns `true` if the data was owned (and thus wiped).
+#[cfg(feature = "zeroize")]
+fn zeroize_cow_bytes(cow: &mut Cow<[u8]>) -> bool {
+    if let Cow::Owned(inner) = cow {
+        inner.zeroize();
+        return true;
+    }
+
+    false
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -57,7 +128,6 @@ mod tests {
     };
 
     #[test]
-    #[cfg(not(debug_assertions))]
     #[allow(clippy::redundant_clone)]
     fn test_that_secret_is_redacted() {
         use super::Secret;
@@ -68,6 +138,10 @@ mod tests {
         println!("{}", got);
         assert!(!got.contains("xyz123"));
 
+        let got = format!("{}", secret);
+        println!("{}", got);
+        assert_eq!(got, "/* REDACTED */");
+
         println!("-----");
 
         let tests = vec![
@@ -106,6 +180,58 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_that_secret_is_zeroized() {
+        use std::borrow::Cow;
+
+        use zeroize::Zeroize;
+
+        use super::Secret;
+
+        // Owned data is wiped. (`zeroize` truncates the buffer after overwriting it.)
+        let mut secret = Secret::new(AString::try_from(String::from("xyz123")).unwrap());
+        assert!(matches!(secret.declassify(), AString::Atom(_)));
+        secret.zeroize();
+        assert!(secret.declassify().as_ref().is_empty());
+        // ... and an empty atom would be invalid.
+        assert_eq!(
+            secret.declassify(),
+            &AString::from(Quoted::try_from("").unwrap())
+        );
+
+        let mut secret = Secret::new(AString::from(
+            Quoted::try_from(String::from("xyz 123")).unwrap(),
+        ));
+        secret.zeroize();
+        assert_eq!(
+            secret.declassify(),
+            &AString::from(Quoted::try_from("").unwrap())
+        );
+
+        let mut secret = Secret::new(AString::from(
+            Literal::try_from(b"xyz123".to_vec()).unwrap(),
+        ));
+        secret.zeroize();
+        assert!(secret.declassify().as_ref().is_empty());
+
+        let mut secret = Secret::new(Cow::<[u8]>::Owned(b"xyz123".to_vec()));
+        secret.zeroize();
+        assert!(secret.declassify().is_empty());
+
+        // Borrowed data is left alone.
+        let password = String::from("xyz123");
+        let mut secret = Secret::new(AString::try_from(password.as_str()).unwrap());
+        secret.zeroize();
+        assert_eq!(secret.declassify().as_ref(), b"xyz123");
+
+        // Owned secrets can be wiped on drop.
+        let secret = zeroize::Zeroizing::new(Secret::new(
+            AString::try_from(String::from("xyz123")).unwrap(),
+        ));
+        assert_eq!(secret.declassify().as_ref(), b"xyz123");
+    }
+
     #[test]
     fn test_that_secret_has_no_side_effects_on_eq() {
         assert_ne!(
@@ -3,6 +3,7 @@ use std::{
     collections::VecDeque,
     fmt::Debug,
     iter::Rev,
+    marker::PhantomData,
     num::NonZeroU32,
     ops::{Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive},
     str::FromStr,
@@ -11,7 +12,7 @@ use std::{
 #[cfg(feature = "arbitrary")]
 use arbitrary::Arbitrary;
 #[cfg(feature = "bounded-static")]
-use bounded_static::ToStatic;
+use bounded_static::{IntoBoundedStatic, ToBoundedStatic, ToStatic};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -132,6 +133,13 @@ impl FromStr for SequenceSet {
 pub enum Sequence {
     Single(SeqOrUid),
     Range(SeqOrUid, SeqOrUid),
+    /// The result of the last `SEARCH ... RETURN (SAVE)`, i.e., `$` (RFC 5182).
+    ///
+    /// Only the server knows which messages this refers to. Thus, [`SequenceSet::iter`],
+    /// [`SequenceSet::iter_naive`], and [`SequenceSet::contains`] ignore it.
+    #[cfg(feature = "ext_searchres")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_searchres")))]
+    SearchResult,
 }
 
 impl From<SeqOrUid> for Sequence {
@@ -158,6 +166,11 @@ impl FromStr for Sequence {
     type Err = ValidationError;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
+        #[cfg(feature = "ext_searchres")]
+        if value == "$" {
+            return Ok(Sequence::SearchResult);
+        }
+
         match value.split(':').count() {
             0 => Err(ValidationError::new(ValidationErrorKind::Empty)),
             1 => Ok(Sequence::Single(SeqOrUid::try_from(value)?)),
@@ -439,6 +452,132 @@ impl From<RangeInclusive<NonZeroU32>> for Sequence {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Kind of the numbers in a [`TypedSequenceSet`], i.e., [`Seq`] or [`Uid`].
+pub trait SequenceKind {
+    /// Whether the numbers are UIDs, i.e., whether the `UID` variant of a command must be used.
+    const UID: bool;
+}
+
+/// Marker for message sequence numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Seq {}
+
+/// Marker for unique identifiers (UIDs).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Uid {}
+
+impl SequenceKind for Seq {
+    const UID: bool = false;
+}
+
+impl SequenceKind for Uid {
+    const UID: bool = true;
+}
+
+/// A sequence set that is known to contain either message sequence numbers or UIDs.
+///
+/// Mixing up sequence numbers and UIDs is a common source of bugs. Thus, a [`SeqSequenceSet`] can't be
+/// used where a [`UidSequenceSet`] is expected and vice versa.
+///
+/// # Example
+///
+/// ```
+/// use imap_types::{
+///     command::CommandBody,
+///     fetch::MacroOrMessageDataItemNames,
+///     sequence::{SeqSequenceSet, UidSequenceSet},
+/// };
+///
+/// let uids = UidSequenceSet::try_from("1:*").unwrap();
+/// let fetch = CommandBody::fetch_typed(
+///     uids,
+///     MacroOrMessageDataItemNames::Macro(imap_types::fetch::Macro::Fast),
+/// );
+///
+/// assert!(matches!(fetch, CommandBody::Fetch { uid: true, .. }));
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TypedSequenceSet<K> {
+    inner: SequenceSet,
+    kind: PhantomData<K>,
+}
+
+/// A sequence set containing message sequence numbers.
+pub type SeqSequenceSet = TypedSequenceSet<Seq>;
+
+/// A sequence set containing UIDs.
+pub type UidSequenceSet = TypedSequenceSet<Uid>;
+
+impl<K: SequenceKind> TypedSequenceSet<K> {
+    /// Create a typed sequence set from an untyped one.
+    pub fn new(inner: SequenceSet) -> Self {
+        Self {
+            inner,
+            kind: PhantomData,
+        }
+    }
+
+    /// Whether this sequence set contains UIDs.
+    pub fn is_uid(&self) -> bool {
+        K::UID
+    }
+
+    /// Return a reference to the underlying sequence set.
+    pub fn inner(&self) -> &SequenceSet {
+        &self.inner
+    }
+
+    /// Consume this typed sequence set and return the underlying sequence set.
+    pub fn into_inner(self) -> SequenceSet {
+        self.inner
+    }
+}
+
+impl<K: SequenceKind> TryFrom<&str> for TypedSequenceSet<K> {
+    type Error = ValidationError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Ok(Self::new(SequenceSet::try_from(value)?))
+    }
+}
+
+impl<K: SequenceKind> From<NonZeroU32> for TypedSequenceSet<K> {
+    fn from(value: NonZeroU32) -> Self {
+        Self::new(SequenceSet::from(value))
+    }
+}
+
+impl<K> From<TypedSequenceSet<K>> for SequenceSet {
+    fn from(value: TypedSequenceSet<K>) -> Self {
+        value.inner
+    }
+}
+
+// `ToStatic` can't be derived because `PhantomData` doesn't implement it.
+#[cfg(feature = "bounded-static")]
+impl<K: 'static> IntoBoundedStatic for TypedSequenceSet<K> {
+    type Static = Self;
+
+    fn into_static(self) -> Self::Static {
+        self
+    }
+}
+
+#[cfg(feature = "bounded-static")]
+impl<K: 'static> ToBoundedStatic for TypedSequenceSet<K> {
+    type Static = Self;
+
+    fn to_static(&self) -> Self::Static {
+        Self {
+            inner: self.inner.clone(),
+            kind: PhantomData,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 impl<'a> SequenceSet {
     /// Iterate over a sorted, deduplicated set of sequence numbers or UIDs.
     ///
@@ -502,6 +641,99 @@ impl<'a> SequenceSet {
     }
 }
 
+impl SequenceSet {
+    /// Create a minimal sequence set from sorted sequence numbers or UIDs by collapsing
+    /// consecutive numbers into ranges, e.g., `1 2 3 5 6 9` into `1:3,5:6,9`.
+    ///
+    /// This is useful to return search results as a sequence set, e.g., for `ESEARCH ALL`.
+    ///
+    /// Duplicates are ignored. Unsorted input still yields the same set of numbers but not
+    /// necessarily a minimal one. Fails when `values` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::num::NonZeroU32;
+    ///
+    /// use imap_types::sequence::SequenceSet;
+    ///
+    /// let values = [1, 2, 3, 5, 6, 9].map(|value| NonZeroU32::new(value).unwrap());
+    ///
+    /// assert_eq!(
+    ///     SequenceSet::from_sorted(values).unwrap(),
+    ///     SequenceSet::try_from("1:3,5:6,9").unwrap(),
+    /// );
+    /// ```
+    pub fn from_sorted<I>(values: I) -> Result<Self, ValidationError>
+    where
+        I: IntoIterator<Item = NonZeroU32>,
+    {
+        fn to_sequence((start, end): (NonZeroU32, NonZeroU32)) -> Sequence {
+            if start == end {
+                Sequence::Single(SeqOrUid::Value(start))
+            } else {
+                Sequence::Range(SeqOrUid::Value(start), SeqOrUid::Value(end))
+            }
+        }
+
+        let mut sequences = Vec::new();
+        let mut run: Option<(NonZeroU32, NonZeroU32)> = None;
+
+        for value in values {
+            run = match run {
+                Some((start, end)) if value == end => Some((start, end)),
+                Some((start, end)) if Some(value) == end.checked_add(1) => Some((start, value)),
+                Some(run) => {
+                    sequences.push(to_sequence(run));
+                    Some((value, value))
+                }
+                None => Some((value, value)),
+            };
+        }
+
+        sequences.extend(run.map(to_sequence));
+
+        Self::try_from(sequences)
+    }
+
+    /// Check if the sequence set contains a sequence number or UID.
+    ///
+    /// `*` is interpreted as `largest`. Ranges are interpreted regardless of their order, i.e.,
+    /// `5:1` is the same as `1:5`. (Note: Ranges are encoded as entered.)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::num::NonZeroU32;
+    ///
+    /// use imap_types::sequence::SequenceSet;
+    ///
+    /// let seq = SequenceSet::try_from("5:1,10:*").unwrap();
+    /// let largest = NonZeroU32::new(12).unwrap();
+    ///
+    /// assert!(seq.contains(NonZeroU32::new(3).unwrap(), largest));
+    /// assert!(seq.contains(NonZeroU32::new(12).unwrap(), largest));
+    /// assert!(!seq.contains(NonZeroU32::new(7).unwrap(), largest));
+    /// ```
+    pub fn contains(&self, value: NonZeroU32, largest: NonZeroU32) -> bool {
+        self.0.as_ref().iter().any(|seq| match seq {
+            Sequence::Single(a) => a.expand(largest) == value,
+            Sequence::Range(a, b) => {
+                let a = a.expand(largest);
+                let b = b.expand(largest);
+
+                if a <= b {
+                    a <= value && value <= b
+                } else {
+                    b <= value && value <= a
+                }
+            }
+            #[cfg(feature = "ext_searchres")]
+            Sequence::SearchResult => false,
+        })
+    }
+}
+
 impl SeqOrUid {
     pub fn expand(&self, largest: NonZeroU32) -> NonZeroU32 {
         match self {
@@ -564,27 +796,33 @@ impl Iterator for Sorting {
 // * Use u32 instead of NonZeroU32 (for internal purposes)
 // * Expand Single(a) to (a, a)
 // * Sort Range(a, b) so that a <= b
+// * Skip `$` (which only the server can resolve)
+#[cfg_attr(not(feature = "ext_searchres"), allow(clippy::unnecessary_filter_map))]
 fn simplify(sequence_set: SequenceSet, largest: NonZeroU32, sort: bool) -> VecDeque<(u32, u32)> {
     sequence_set
         .0
          .0
         .into_iter()
-        .map(|seq| match seq {
-            Sequence::Single(a) => (u32::from(a.expand(largest)), u32::from(a.expand(largest))),
+        .filter_map(|seq| match seq {
+            Sequence::Single(a) => {
+                Some((u32::from(a.expand(largest)), u32::from(a.expand(largest))))
+            }
             Sequence::Range(a, b) => {
                 let a = u32::from(a.expand(largest));
                 let b = u32::from(b.expand(largest));
 
                 if sort {
                     if a <= b {
-                        (a, b)
+                        Some((a, b))
                     } else {
-                        (b, a)
+                        Some((b, a))
                     }
                 } else {
-                    (a, b)
+                    Some((a, b))
                 }
             }
+            #[cfg(feature = "ext_searchres")]
+            Sequence::SearchResult => None,
         })
         .collect()
 }
@@ -597,7 +835,12 @@ fn cleanup(remaining: VecDeque<(u32, u32)>) -> VecDeque<(u32, u32)> {
     };
 
     let mut stack = VecDeque::new();
-    stack.push_back(remaining.pop_front().unwrap());
+
+    // Empty when the sequence set only contains `$`.
+    let Some(first) = remaining.pop_front() else {
+        return stack;
+    };
+    stack.push_back(first);
 
     for (x, y) in remaining.into_iter() {
         let last = stack.back_mut().unwrap();
@@ -619,6 +862,33 @@ mod tests {
     use super::*;
     use crate::core::Vec1;
 
+    #[test]
+    fn test_sequence_set_from_sorted() {
+        let tests = [
+            (vec![1, 2, 3, 5, 6, 9], "1:3,5:6,9"),
+            (vec![1], "1"),
+            (vec![1, 2], "1:2"),
+            (vec![1, 3], "1,3"),
+            (vec![1, 1, 2, 2, 4], "1:2,4"),
+            (vec![4294967294, 4294967295], "4294967294:4294967295"),
+            // Not minimal but correct.
+            (vec![3, 1, 2], "3,1:2"),
+        ];
+
+        for (test, expected) in tests {
+            let values = test
+                .into_iter()
+                .map(|value| NonZeroU32::new(value).unwrap());
+
+            assert_eq!(
+                SequenceSet::from_sorted(values).unwrap(),
+                SequenceSet::try_from(expected).unwrap()
+            );
+        }
+
+        assert!(SequenceSet::from_sorted([]).is_err());
+    }
+
     #[test]
     fn test_creation_of_sequence_from_u32() {
         assert_eq!(
@@ -834,6 +1104,78 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_asterisk_range_is_order_independent() {
+        let largest = NonZeroU32::new(5).unwrap();
+
+        let forward = SequenceSet::try_from("1:*").unwrap();
+        let backward = SequenceSet::try_from("*:1").unwrap();
+
+        assert_eq!(
+            forward.iter(largest).collect::<Vec<_>>(),
+            backward.iter(largest).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![largest],
+            SequenceSet::try_from("*")
+                .unwrap()
+                .iter(largest)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_reversed_range() {
+        let largest = NonZeroU32::new(10).unwrap();
+
+        let forward = SequenceSet::try_from("1:5").unwrap();
+        let backward = SequenceSet::try_from("5:1").unwrap();
+
+        for value in 1..=10 {
+            let value = NonZeroU32::new(value).unwrap();
+            assert_eq!(
+                forward.contains(value, largest),
+                backward.contains(value, largest)
+            );
+        }
+
+        assert!(backward.contains(NonZeroU32::new(3).unwrap(), largest));
+        assert!(!backward.contains(NonZeroU32::new(6).unwrap(), largest));
+        assert_eq!(
+            forward.iter(largest).collect::<Vec<_>>(),
+            backward.iter(largest).collect::<Vec<_>>()
+        );
+
+        let asterisk = SequenceSet::try_from("*:8").unwrap();
+        assert!(asterisk.contains(NonZeroU32::new(9).unwrap(), largest));
+        assert!(!asterisk.contains(NonZeroU32::new(7).unwrap(), largest));
+    }
+
+    #[test]
+    fn test_typed_sequence_set() {
+        let seqs = SeqSequenceSet::try_from("1:5").unwrap();
+        let uids = UidSequenceSet::try_from("1:5").unwrap();
+
+        assert!(!seqs.is_uid());
+        assert!(uids.is_uid());
+
+        // Both represent the same numbers ...
+        assert_eq!(seqs.inner(), uids.inner());
+        // ... but can't be mixed up, e.g., `assert_eq!(seqs, uids)` does not compile.
+        assert_eq!(SequenceSet::from(seqs), SequenceSet::from(uids));
+    }
+
+    #[cfg(feature = "bounded-static")]
+    #[test]
+    fn test_typed_sequence_set_to_static() {
+        use bounded_static::{IntoBoundedStatic, ToBoundedStatic};
+
+        let uids = UidSequenceSet::try_from("1:5").unwrap();
+
+        assert_eq!(uids.to_static(), uids);
+        assert_eq!(uids.clone().into_static(), uids);
+    }
+
     /// See https://github.com/duesee/imap-codec/issues/411
     #[test]
     fn test_issue_411() {
@@ -891,4 +1233,25 @@ mod tests {
             assert_eq!(naive, clean);
         }
     }
+
+    #[cfg(feature = "ext_searchres")]
+    #[test]
+    fn test_sequence_set_search_result() {
+        let largest = NonZeroU32::new(5).unwrap();
+
+        let seq = SequenceSet::try_from("$").unwrap();
+        assert_eq!(seq, SequenceSet::from(Sequence::SearchResult));
+        assert_eq!(seq.iter(largest).count(), 0);
+        assert_eq!(seq.iter_naive(largest).count(), 0);
+        assert!(!seq.contains(NonZeroU32::new(1).unwrap(), largest));
+
+        let seq = SequenceSet::try_from("2,$").unwrap();
+        assert_eq!(
+            seq.iter(largest).collect::<Vec<_>>(),
+            [NonZeroU32::new(2).unwrap()]
+        );
+
+        assert!(Sequence::try_from("$:1").is_err());
+        assert!(Sequence::try_from("1:$").is_err());
+    }
 }
@@ -50,12 +50,23 @@
 //! (7) LOGOUT command, server shutdown, or connection closed
 //! ```
 
+use std::{collections::BTreeMap, num::NonZeroU32};
+
 #[cfg(feature = "bounded-static")]
 use bounded_static::ToStatic;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::{core::Tag, mailbox::Mailbox};
+use crate::{
+    command::CommandBody,
+    core::Tag,
+    extensions::enable::{CapabilityEnable, Utf8Kind},
+    fetch::MessageDataItem,
+    flag::{Flag, FlagPerm},
+    mailbox::Mailbox,
+    response::{Capability, Code, Data, Greeting, GreetingKind, Response, Status, Tagged},
+    state::error::{CommandGuardError, ExpungeTrackerError},
+};
 
 /// State of the IMAP4rev1 connection.
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
@@ -93,13 +104,713 @@ pub enum State<'a> {
     IdleSelected(Tag<'a>, Mailbox<'a>),
 }
 
+/// Client-side mapping of message sequence numbers to UIDs in the selected mailbox.
+///
+/// Every `EXPUNGE` response immediately decrements the sequence numbers of all subsequent messages,
+/// including the ones in subsequent `EXPUNGE` responses (see [`Data::Expunge`]). The tracker
+/// applies this rule, so that the mapping stays in sync regardless of whether the server expunges
+/// from lower to higher, or from higher to lower sequence numbers.
+///
+/// # Example
+///
+/// ```
+/// use std::num::NonZeroU32;
+///
+/// use imap_types::{response::Data, state::ExpungeTracker};
+///
+/// let uids = [10, 20, 30].map(|uid| NonZeroU32::new(uid).unwrap());
+/// let mut tracker = ExpungeTracker::with_uids(uids.to_vec());
+///
+/// tracker.update(&Data::expunge(1).unwrap()).unwrap();
+///
+/// assert_eq!(tracker.exists(), 2);
+/// assert_eq!(tracker.uid(NonZeroU32::new(1).unwrap()), Some(uids[1]));
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExpungeTracker {
+    /// Number of messages in the mailbox.
+    exists: u32,
+    /// Known UIDs keyed by sequence number.
+    ///
+    /// Note: The message count is controlled by the server. Thus, only known UIDs are stored.
+    uids: BTreeMap<NonZeroU32, NonZeroU32>,
+}
+
+impl ExpungeTracker {
+    /// Create a tracker for a mailbox with `exists` messages with (yet) unknown UIDs.
+    pub fn new(exists: u32) -> Self {
+        Self {
+            exists,
+            uids: BTreeMap::new(),
+        }
+    }
+
+    /// Create a tracker from the UIDs of all messages, ordered by sequence number.
+    pub fn with_uids(uids: Vec<NonZeroU32>) -> Self {
+        let uids: BTreeMap<_, _> = (1..).filter_map(NonZeroU32::new).zip(uids).collect();
+
+        Self {
+            exists: uids.len() as u32,
+            uids,
+        }
+    }
+
+    /// Number of messages in the mailbox.
+    pub fn exists(&self) -> u32 {
+        self.exists
+    }
+
+    /// Get the UID of the message with sequence number `seq` (if known).
+    pub fn uid(&self, seq: NonZeroU32) -> Option<NonZeroU32> {
+        self.uids.get(&seq).copied()
+    }
+
+    /// Get the sequence number of the message with UID `uid` (if known).
+    pub fn seq(&self, uid: NonZeroU32) -> Option<NonZeroU32> {
+        self.uids
+            .iter()
+            .find_map(|(seq, known)| (*known == uid).then_some(*seq))
+    }
+
+    /// Record the UID of the message with sequence number `seq`.
+    pub fn set_uid(&mut self, seq: NonZeroU32, uid: NonZeroU32) -> Result<(), ExpungeTrackerError> {
+        self.check(seq)?;
+        self.uids.insert(seq, uid);
+
+        Ok(())
+    }
+
+    /// Remove the message with sequence number `seq` and return its UID (if known).
+    ///
+    /// All subsequent messages move down by one.
+    pub fn expunge(&mut self, seq: NonZeroU32) -> Result<Option<NonZeroU32>, ExpungeTrackerError> {
+        self.check(seq)?;
+
+        let uid = self.uids.remove(&seq);
+        let subsequent = self.uids.split_off(&seq);
+        self.uids.extend(
+            subsequent
+                .into_iter()
+                // Safety: `seq` > 1 because it is larger than the expunged sequence number.
+                .map(|(seq, uid)| (NonZeroU32::new(seq.get() - 1).unwrap(), uid)),
+        );
+        self.exists -= 1;
+
+        Ok(uid)
+    }
+
+    /// Update the tracker from a response.
+    ///
+    /// * `EXISTS` appends messages with unknown UIDs,
+    /// * `EXPUNGE` removes a message (see [`ExpungeTracker::expunge`]), and
+    /// * `FETCH` records the UID (if present).
+    ///
+    /// All other responses are ignored.
+    pub fn update(&mut self, data: &Data) -> Result<(), ExpungeTrackerError> {
+        match data {
+            Data::Exists(exists) => {
+                if *exists < self.exists {
+                    return Err(ExpungeTrackerError::ExistsDecreased {
+                        exists: *exists,
+                        expected: self.exists,
+                    });
+                }
+
+                self.exists = *exists;
+            }
+            Data::Expunge(seq) => {
+                self.expunge(*seq)?;
+            }
+            Data::Fetch { seq, items } => {
+                for item in items.as_ref() {
+                    if let MessageDataItem::Uid(uid) = item {
+                        self.set_uid(*seq, *uid)?;
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn check(&self, seq: NonZeroU32) -> Result<(), ExpungeTrackerError> {
+        if seq.get() > self.exists {
+            return Err(ExpungeTrackerError::UnknownMessage {
+                seq,
+                exists: self.exists,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Information about a mailbox collected from the responses to a SELECT or EXAMINE command.
+///
+/// # Example
+///
+/// ```
+/// use imap_types::{
+///     response::{Data, Response},
+///     state::SelectResponse,
+/// };
+///
+/// let mut select = SelectResponse::default();
+///
+/// // Feed all responses until the command completes ...
+/// select.update(&Response::Data(Data::Exists(172)));
+///
+/// assert_eq!(select.exists, Some(172));
+/// ```
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SelectResponse<'a> {
+    /// Number of messages in the mailbox (`* <n> EXISTS`).
+    pub exists: Option<u32>,
+    /// Number of messages with the `\Recent` flag set (`* <n> RECENT`).
+    pub recent: Option<u32>,
+    /// Flags defined in the mailbox (`* FLAGS (...)`).
+    pub flags: Option<Vec<Flag<'a>>>,
+    /// Flags the client can change permanently (`[PERMANENTFLAGS (...)]`).
+    pub permanent_flags: Option<Vec<FlagPerm<'a>>>,
+    /// Sequence number of the first unseen message (`[UNSEEN <n>]`).
+    pub unseen: Option<NonZeroU32>,
+    /// Unique identifier validity value (`[UIDVALIDITY <n>]`).
+    pub uid_validity: Option<NonZeroU32>,
+    /// Predicted next unique identifier value (`[UIDNEXT <n>]`).
+    pub uid_next: Option<NonZeroU32>,
+    /// Whether the mailbox was opened read-write (`[READ-WRITE]`) or read-only (`[READ-ONLY]`).
+    pub read_write: Option<bool>,
+}
+
+impl<'a> SelectResponse<'a> {
+    /// Fold a response into the collected information.
+    ///
+    /// Responses that are not related to SELECT or EXAMINE are ignored.
+    pub fn update(&mut self, response: &Response<'a>) {
+        match response {
+            Response::Data(Data::Exists(exists)) => self.exists = Some(*exists),
+            Response::Data(Data::Recent(recent)) => self.recent = Some(*recent),
+            Response::Data(Data::Flags(flags)) => self.flags = Some(flags.clone()),
+            Response::Status(Status::Untagged(body)) => self.update_code(body.code.as_ref()),
+            Response::Status(Status::Tagged(tagged)) => self.update_code(tagged.body.code.as_ref()),
+            _ => {}
+        }
+    }
+
+    fn update_code(&mut self, code: Option<&Code<'a>>) {
+        match code {
+            Some(Code::PermanentFlags(flags)) => self.permanent_flags = Some(flags.clone()),
+            Some(Code::Unseen(unseen)) => self.unseen = Some(*unseen),
+            Some(Code::UidValidity(uid_validity)) => self.uid_validity = Some(*uid_validity),
+            Some(Code::UidNext(uid_next)) => self.uid_next = Some(*uid_next),
+            Some(Code::ReadWrite) => self.read_write = Some(true),
+            Some(Code::ReadOnly) => self.read_write = Some(false),
+            _ => {}
+        }
+    }
+}
+
+/// Correlation of tagged status responses with the commands sent by a client.
+///
+/// # Example
+///
+/// ```
+/// use imap_types::{
+///     core::Tag,
+///     response::Status,
+///     state::{TagMatch, TagMatcher},
+/// };
+///
+/// let mut matcher = TagMatcher::new();
+/// matcher.track(Tag::try_from("A1").unwrap());
+///
+/// let status = Status::ok(Some(Tag::try_from("A1").unwrap()), None, "done").unwrap();
+///
+/// assert_eq!(
+///     matcher.resolve(&status),
+///     Some(TagMatch::Matched(Tag::try_from("A1").unwrap()))
+/// );
+/// assert!(matcher.is_empty());
+/// ```
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TagMatcher<'a> {
+    /// Tags of the commands that were sent but not completed yet.
+    outstanding: Vec<Tag<'a>>,
+}
+
+/// Result of [`TagMatcher::resolve`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TagMatch<'a, 'b> {
+    /// The status completes the command with this tag.
+    Matched(Tag<'a>),
+    /// The status has a tag that was never sent (or was already completed).
+    Unknown(Tag<'b>),
+}
+
+impl<'a> TagMatcher<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track the tag of a command that was sent.
+    pub fn track(&mut self, tag: Tag<'a>) {
+        self.outstanding.push(tag);
+    }
+
+    /// Check if the command with `tag` was sent but not completed yet.
+    pub fn is_outstanding(&self, tag: &Tag) -> bool {
+        self.outstanding
+            .iter()
+            .any(|outstanding| outstanding == tag)
+    }
+
+    /// Check if all tracked commands were completed.
+    pub fn is_empty(&self) -> bool {
+        self.outstanding.is_empty()
+    }
+
+    /// Match a status response with a tracked command.
+    ///
+    /// A matched command is not tracked anymore. Returns `None` for untagged status responses.
+    pub fn resolve<'b>(&mut self, status: &Status<'b>) -> Option<TagMatch<'a, 'b>> {
+        let tag = match status {
+            Status::Tagged(Tagged { tag, .. }) => tag,
+            Status::Untagged(_) | Status::Bye(_) => return None,
+        };
+
+        match self
+            .outstanding
+            .iter()
+            .position(|outstanding| outstanding == tag)
+        {
+            Some(index) => Some(TagMatch::Matched(self.outstanding.remove(index))),
+            None => Some(TagMatch::Unknown(tag.clone())),
+        }
+    }
+}
+
+/// Negotiated protocol state of a client connection, i.e., login state, server capabilities, and
+/// enabled extensions.
+///
+/// The state is updated from the greeting ([`ProtocolState::update_greeting`]) and all subsequent
+/// responses ([`ProtocolState::update`]). Changes caused by commands, e.g., a successful LOGIN, must
+/// be applied via [`ProtocolState::set_state`].
+///
+/// # Example
+///
+/// ```
+/// use imap_types::{
+///     core::Vec1,
+///     response::{Capability, Data, Greeting, Response},
+///     state::{ProtocolState, State},
+/// };
+///
+/// let mut protocol = ProtocolState::new();
+///
+/// protocol.update_greeting(&Greeting::ok(None, "Hello").unwrap());
+/// assert_eq!(protocol.state(), &State::NotAuthenticated);
+///
+/// protocol.update(&Response::Data(Data::Capability(Vec1::from(
+///     Capability::Move,
+/// ))));
+/// assert!(protocol.can_move());
+/// ```
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProtocolState<'a> {
+    state: State<'a>,
+    capabilities: Vec<Capability<'a>>,
+    enabled: Vec<CapabilityEnable<'a>>,
+    tls: bool,
+    compression: bool,
+}
+
+impl<'a> Default for ProtocolState<'a> {
+    fn default() -> Self {
+        Self {
+            state: State::Greeting,
+            capabilities: Vec::new(),
+            enabled: Vec::new(),
+            tls: false,
+            compression: false,
+        }
+    }
+}
+
+impl<'a> ProtocolState<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the connection state.
+    pub fn state(&self) -> &State<'a> {
+        &self.state
+    }
+
+    /// Set the connection state, e.g., after a successful LOGIN or SELECT command.
+    pub fn set_state(&mut self, state: State<'a>) {
+        self.state = state;
+    }
+
+    /// Get the most recently announced server capabilities.
+    pub fn capabilities(&self) -> &[Capability<'a>] {
+        &self.capabilities
+    }
+
+    /// Get the extensions enabled via ENABLE.
+    pub fn enabled(&self) -> &[CapabilityEnable<'a>] {
+        &self.enabled
+    }
+
+    /// Forget the server capabilities, e.g., after STARTTLS.
+    pub fn clear_capabilities(&mut self) {
+        self.capabilities.clear();
+    }
+
+    /// Check if the connection is protected by TLS.
+    pub fn is_tls_active(&self) -> bool {
+        self.tls
+    }
+
+    /// Record that the connection is protected by TLS, e.g., after a successful STARTTLS or when
+    /// using implicit TLS.
+    pub fn set_tls_active(&mut self) {
+        self.tls = true;
+    }
+
+    /// Check if the connection is compressed.
+    pub fn is_compression_active(&self) -> bool {
+        self.compression
+    }
+
+    /// Record that the connection is compressed, i.e., after a successful COMPRESS.
+    pub fn set_compression_active(&mut self) {
+        self.compression = true;
+    }
+
+    /// Update the state from the server greeting.
+    ///
+    /// * `OK` enters the not authenticated state,
+    /// * `PREAUTH` enters the authenticated state, and
+    /// * `BYE` enters the logout state.
+    ///
+    /// Capabilities announced in the greeting are recorded.
+    pub fn update_greeting(&mut self, greeting: &Greeting<'a>) {
+        self.state = match greeting.kind {
+            GreetingKind::Ok => State::NotAuthenticated,
+            GreetingKind::PreAuth => State::Authenticated,
+            GreetingKind::Bye => State::Logout,
+        };
+
+        self.update_code(greeting.code.as_ref());
+    }
+
+    /// Update the state from a response.
+    ///
+    /// * `CAPABILITY` (response or response code) replaces the server capabilities,
+    /// * `ENABLED` records the enabled extensions, and
+    /// * `BYE` enters the logout state.
+    ///
+    /// All other responses are ignored.
+    pub fn update(&mut self, response: &Response<'a>) {
+        match response {
+            Response::Data(Data::Capability(capabilities)) => {
+                self.capabilities = capabilities.as_ref().to_vec();
+            }
+            Response::Data(Data::Enabled { capabilities }) => {
+                for capability in capabilities {
+                    if !self.enabled.contains(capability) {
+                        self.enabled.push(capability.clone());
+                    }
+                }
+            }
+            Response::Status(Status::Untagged(body)) => self.update_code(body.code.as_ref()),
+            Response::Status(Status::Tagged(tagged)) => self.update_code(tagged.body.code.as_ref()),
+            Response::Status(Status::Bye(bye)) => {
+                self.update_code(bye.code.as_ref());
+                self.state = State::Logout;
+            }
+            _ => {}
+        }
+    }
+
+    fn update_code(&mut self, code: Option<&Code<'a>>) {
+        if let Some(Code::Capability(capabilities)) = code {
+            self.capabilities = capabilities.as_ref().to_vec();
+        }
+    }
+
+    /// Check if the server announced `capability`.
+    pub fn has_capability(&self, capability: &Capability) -> bool {
+        self.capabilities
+            .iter()
+            .any(|announced| announced == capability)
+    }
+
+    /// Check if the server announced IMAP4rev1.
+    pub fn imap4rev1(&self) -> bool {
+        self.has_capability(&Capability::Imap4Rev1)
+    }
+
+    /// Check if the client is authenticated (including the selected and IDLE states).
+    pub fn is_authenticated(&self) -> bool {
+        matches!(
+            self.state,
+            State::Authenticated
+                | State::Selected(_)
+                | State::IdleAuthenticated(_)
+                | State::IdleSelected(..)
+        )
+    }
+
+    /// Check if the server supports MOVE.
+    pub fn can_move(&self) -> bool {
+        self.has_capability(&Capability::Move)
+    }
+
+    /// Check if the server supports IDLE.
+    pub fn can_idle(&self) -> bool {
+        self.has_capability(&Capability::Idle)
+    }
+
+    /// Check if the server supports ENABLE.
+    pub fn can_enable(&self) -> bool {
+        self.has_capability(&Capability::Enable)
+    }
+
+    /// Check if `UTF8=ACCEPT` (or `UTF8=ONLY`) was enabled.
+    pub fn utf8_accept_enabled(&self) -> bool {
+        self.enabled.iter().any(|enabled| {
+            matches!(
+                enabled,
+                CapabilityEnable::Utf8(Utf8Kind::Accept | Utf8Kind::Only)
+            )
+        })
+    }
+
+    /// Check if CONDSTORE was enabled.
+    #[cfg(feature = "ext_condstore_qresync")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_condstore_qresync")))]
+    pub fn condstore_enabled(&self) -> bool {
+        self.enabled.contains(&CapabilityEnable::CondStore)
+    }
+}
+
+/// Check if a command may be sent in the current [`ProtocolState`].
+///
+/// This enforces the states in which commands are valid, e.g., STARTTLS only before
+/// authentication, ENABLE only in the authenticated state, and COMPRESS only after authentication.
+/// Furthermore, STARTTLS is rejected when TLS is already active (see
+/// [`ProtocolState::set_tls_active`]) and COMPRESS when compression is already active (see
+/// [`ProtocolState::set_compression_active`]).
+///
+/// # Example
+///
+/// ```
+/// use imap_types::{
+///     command::CommandBody,
+///     state::{CommandGuard, ProtocolState, State},
+/// };
+///
+/// let mut protocol = ProtocolState::new();
+/// protocol.set_state(State::NotAuthenticated);
+///
+/// let guard = CommandGuard::new(&protocol);
+///
+/// assert!(guard
+///     .check(&CommandBody::login("alice", "password").unwrap())
+///     .is_ok());
+/// assert!(guard.check(&CommandBody::select("INBOX").unwrap()).is_err());
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct CommandGuard<'s, 'a> {
+    protocol: &'s ProtocolState<'a>,
+}
+
+/// States in which a command is valid.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ValidIn {
+    /// Any state (except logout and IDLE).
+    Any,
+    /// Not authenticated state.
+    NotAuthenticated,
+    /// Authenticated and selected state.
+    Authenticated,
+    /// Authenticated state (but not the selected state).
+    AuthenticatedOnly,
+    /// Selected state.
+    Selected,
+}
+
+impl<'s, 'a> CommandGuard<'s, 'a> {
+    /// Create a guard that checks commands against `protocol`.
+    pub fn new(protocol: &'s ProtocolState<'a>) -> Self {
+        Self { protocol }
+    }
+
+    /// Check if `command` may be sent now.
+    pub fn check(&self, command: &CommandBody) -> Result<(), CommandGuardError> {
+        let state = self.protocol.state();
+
+        let allowed = match state {
+            State::Greeting
+            | State::Logout
+            | State::IdleAuthenticated(_)
+            | State::IdleSelected(..) => false,
+            State::NotAuthenticated => {
+                matches!(
+                    Self::valid_in(command),
+                    ValidIn::Any | ValidIn::NotAuthenticated
+                )
+            }
+            State::Authenticated => matches!(
+                Self::valid_in(command),
+                ValidIn::Any | ValidIn::Authenticated | ValidIn::AuthenticatedOnly
+            ),
+            State::Selected(_) => matches!(
+                Self::valid_in(command),
+                ValidIn::Any | ValidIn::Authenticated | ValidIn::Selected
+            ),
+        };
+
+        if allowed {
+            match command {
+                #[cfg(feature = "starttls")]
+                CommandBody::StartTLS if self.protocol.is_tls_active() => {
+                    Err(CommandGuardError::AlreadyActive {
+                        command: command.name(),
+                        layer: "TLS",
+                    })
+                }
+                CommandBody::Compress { .. } if self.protocol.is_compression_active() => {
+                    Err(CommandGuardError::AlreadyActive {
+                        command: command.name(),
+                        layer: "compression",
+                    })
+                }
+                _ => Ok(()),
+            }
+        } else {
+            Err(CommandGuardError::InvalidState {
+                command: command.name(),
+                state: match state {
+                    State::Greeting => "greeting",
+                    State::NotAuthenticated => "not authenticated",
+                    State::Authenticated => "authenticated",
+                    State::Selected(_) => "selected",
+                    State::Logout => "logout",
+                    State::IdleAuthenticated(_) | State::IdleSelected(..) => "idle",
+                },
+            })
+        }
+    }
+
+    fn valid_in(command: &CommandBody) -> ValidIn {
+        match command {
+            CommandBody::Capability | CommandBody::Noop | CommandBody::Logout => ValidIn::Any,
+            #[cfg(feature = "ext_id")]
+            CommandBody::Id { .. } => ValidIn::Any,
+            #[cfg(feature = "starttls")]
+            CommandBody::StartTLS => ValidIn::NotAuthenticated,
+            CommandBody::Authenticate { .. } | CommandBody::Login { .. } => {
+                ValidIn::NotAuthenticated
+            }
+            // "The ENABLE command is only valid in the authenticated state, before any mailbox is
+            // selected." (RFC 5161)
+            CommandBody::Enable { .. } => ValidIn::AuthenticatedOnly,
+            CommandBody::Select { .. }
+            | CommandBody::Examine { .. }
+            | CommandBody::Create { .. }
+            | CommandBody::Delete { .. }
+            | CommandBody::Rename { .. }
+            | CommandBody::Subscribe { .. }
+            | CommandBody::Unsubscribe { .. }
+            | CommandBody::List { .. }
+            | CommandBody::Lsub { .. }
+            | CommandBody::Status { .. }
+            | CommandBody::Append { .. }
+            | CommandBody::Idle
+            | CommandBody::Compress { .. }
+            | CommandBody::GetQuota { .. }
+            | CommandBody::GetQuotaRoot { .. }
+            | CommandBody::SetQuota { .. } => ValidIn::Authenticated,
+            #[cfg(feature = "ext_metadata")]
+            CommandBody::SetMetadata { .. } | CommandBody::GetMetadata { .. } => {
+                ValidIn::Authenticated
+            }
+            #[cfg(feature = "ext_acl")]
+            CommandBody::SetAcl { .. }
+            | CommandBody::DeleteAcl { .. }
+            | CommandBody::GetAcl { .. }
+            | CommandBody::ListRights { .. }
+            | CommandBody::MyRights { .. } => ValidIn::Authenticated,
+            #[cfg(feature = "legacy")]
+            CommandBody::XList { .. } => ValidIn::Authenticated,
+            CommandBody::Unselect
+            | CommandBody::Check
+            | CommandBody::Close
+            | CommandBody::Expunge
+            | CommandBody::Search { .. }
+            | CommandBody::Fetch { .. }
+            | CommandBody::Store { .. }
+            | CommandBody::Copy { .. }
+            | CommandBody::Move { .. } => ValidIn::Selected,
+            #[cfg(feature = "ext_uidplus")]
+            CommandBody::ExpungeUid { .. } => ValidIn::Selected,
+            #[cfg(feature = "ext_sort_thread")]
+            CommandBody::Sort { .. } | CommandBody::Thread { .. } => ValidIn::Selected,
+        }
+    }
+}
+
+/// Error-related types.
+pub mod error {
+    use std::num::NonZeroU32;
+
+    use thiserror::Error;
+
+    #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+    pub enum ExpungeTrackerError {
+        #[error("Unknown message {seq} (mailbox has {exists} messages)")]
+        UnknownMessage { seq: NonZeroU32, exists: u32 },
+        #[error("EXISTS decreased from {expected} to {exists} (use EXPUNGE to remove messages)")]
+        ExistsDecreased { exists: u32, expected: u32 },
+    }
+
+    #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+    pub enum CommandGuardError {
+        #[error("{command} is not allowed in the {state} state")]
+        InvalidState {
+            command: &'static str,
+            state: &'static str,
+        },
+        #[error("{command} is not allowed because {layer} is already active")]
+        AlreadyActive {
+            command: &'static str,
+            layer: &'static str,
+        },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(feature = "bounded-static")]
     use bounded_static::{IntoBoundedStatic, ToBoundedStatic};
 
     use super::*;
-    use crate::{core::Tag, mailbox::Mailbox};
+    use crate::{
+        core::{Tag, Vec1},
+        extensions::compress::CompressionAlgorithm,
+        fetch::Macro,
+        mailbox::Mailbox,
+    };
 
     #[test]
     fn test_conversion() {
@@ -124,4 +835,407 @@ mod tests {
             }
         }
     }
+
+    fn uids(uids: &[u32]) -> Vec<NonZeroU32> {
+        uids.iter()
+            .map(|uid| NonZeroU32::new(*uid).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_expunge_tracker_orderings() {
+        // "[...] if the last 5 messages in a 9-message mailbox are expunged, a "lower to higher"
+        // server will send five untagged EXPUNGE responses for message sequence number 5, whereas
+        // a "higher to lower server" will send successive untagged EXPUNGE responses for message
+        // sequence numbers 9, 8, 7, 6, and 5."
+        let tests = [
+            ([5, 5, 5, 5, 5], [105, 106, 107, 108, 109]),
+            ([9, 8, 7, 6, 5], [109, 108, 107, 106, 105]),
+        ];
+
+        for (expunges, expected_uids) in tests {
+            let mut tracker =
+                ExpungeTracker::with_uids(uids(&[101, 102, 103, 104, 105, 106, 107, 108, 109]));
+
+            let got_uids: Vec<_> = expunges
+                .into_iter()
+                .map(|seq| tracker.expunge(NonZeroU32::new(seq).unwrap()).unwrap())
+                .collect();
+
+            assert_eq!(
+                uids(&expected_uids)
+                    .into_iter()
+                    .map(Some)
+                    .collect::<Vec<_>>(),
+                got_uids
+            );
+            assert_eq!(
+                ExpungeTracker::with_uids(uids(&[101, 102, 103, 104])),
+                tracker
+            );
+        }
+    }
+
+    #[test]
+    fn test_select_response() {
+        // Example from RFC 3501, 6.3.1.
+        let responses = [
+            Response::Data(Data::Exists(172)),
+            Response::Data(Data::Recent(1)),
+            Response::Status(
+                Status::ok(
+                    None,
+                    Some(Code::unseen(12).unwrap()),
+                    "Message 12 is first unseen",
+                )
+                .unwrap(),
+            ),
+            Response::Status(
+                Status::ok(
+                    None,
+                    Some(Code::uidvalidity(3857529045).unwrap()),
+                    "UIDs valid",
+                )
+                .unwrap(),
+            ),
+            Response::Status(
+                Status::ok(
+                    None,
+                    Some(Code::uidnext(4392).unwrap()),
+                    "Predicted next UID",
+                )
+                .unwrap(),
+            ),
+            Response::Data(Data::Flags(vec![
+                Flag::Answered,
+                Flag::Flagged,
+                Flag::Deleted,
+                Flag::Seen,
+                Flag::Draft,
+            ])),
+            Response::Status(
+                Status::ok(
+                    None,
+                    Some(Code::PermanentFlags(vec![
+                        FlagPerm::Flag(Flag::Deleted),
+                        FlagPerm::Flag(Flag::Seen),
+                        FlagPerm::Asterisk,
+                    ])),
+                    "Limited",
+                )
+                .unwrap(),
+            ),
+            Response::Status(
+                Status::ok(
+                    Some(Tag::try_from("A142").unwrap()),
+                    Some(Code::ReadWrite),
+                    "SELECT completed",
+                )
+                .unwrap(),
+            ),
+        ];
+
+        let mut got = SelectResponse::default();
+        for response in responses.iter() {
+            got.update(response);
+        }
+
+        assert_eq!(
+            SelectResponse {
+                exists: Some(172),
+                recent: Some(1),
+                flags: Some(vec![
+                    Flag::Answered,
+                    Flag::Flagged,
+                    Flag::Deleted,
+                    Flag::Seen,
+                    Flag::Draft,
+                ]),
+                permanent_flags: Some(vec![
+                    FlagPerm::Flag(Flag::Deleted),
+                    FlagPerm::Flag(Flag::Seen),
+                    FlagPerm::Asterisk,
+                ]),
+                unseen: NonZeroU32::new(12),
+                uid_validity: NonZeroU32::new(3857529045),
+                uid_next: NonZeroU32::new(4392),
+                read_write: Some(true),
+            },
+            got
+        );
+    }
+
+    #[test]
+    fn test_expunge_tracker_update() {
+        let mut tracker = ExpungeTracker::new(2);
+
+        tracker
+            .update(
+                &Data::fetch(2, vec![MessageDataItem::Uid(NonZeroU32::new(42).unwrap())]).unwrap(),
+            )
+            .unwrap();
+        tracker.update(&Data::Exists(3)).unwrap();
+        tracker.update(&Data::expunge(1).unwrap()).unwrap();
+
+        assert_eq!(tracker.exists(), 2);
+        assert_eq!(
+            tracker.uid(NonZeroU32::new(1).unwrap()),
+            NonZeroU32::new(42)
+        );
+        assert_eq!(
+            tracker.seq(NonZeroU32::new(42).unwrap()),
+            NonZeroU32::new(1)
+        );
+        assert_eq!(tracker.uid(NonZeroU32::new(2).unwrap()), None);
+
+        assert_eq!(
+            tracker.update(&Data::expunge(3).unwrap()),
+            Err(ExpungeTrackerError::UnknownMessage {
+                seq: NonZeroU32::new(3).unwrap(),
+                exists: 2
+            })
+        );
+        assert_eq!(
+            tracker.update(&Data::Exists(1)),
+            Err(ExpungeTrackerError::ExistsDecreased {
+                exists: 1,
+                expected: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_expunge_tracker_huge_exists() {
+        // The message count is controlled by the server and must not be used to allocate memory.
+        let mut tracker = ExpungeTracker::new(u32::MAX);
+        tracker.update(&Data::Exists(u32::MAX)).unwrap();
+
+        let last = NonZeroU32::new(u32::MAX).unwrap();
+        tracker.set_uid(last, NonZeroU32::new(42).unwrap()).unwrap();
+        tracker.update(&Data::expunge(1).unwrap()).unwrap();
+
+        assert_eq!(tracker.exists(), u32::MAX - 1);
+        assert_eq!(
+            tracker.uid(NonZeroU32::new(u32::MAX - 1).unwrap()),
+            NonZeroU32::new(42)
+        );
+        assert_eq!(tracker.uid(last), None);
+        assert_eq!(tracker.uids.len(), 1);
+    }
+
+    #[test]
+    fn test_protocol_state() {
+        let mut protocol = ProtocolState::new();
+        assert_eq!(protocol.state(), &State::Greeting);
+        assert!(!protocol.imap4rev1());
+
+        protocol.update_greeting(
+            &Greeting::ok_with_capabilities(
+                Vec1::try_from(vec![Capability::Imap4Rev1, Capability::Idle]).unwrap(),
+                "Hello",
+            )
+            .unwrap(),
+        );
+        assert_eq!(protocol.state(), &State::NotAuthenticated);
+        assert!(protocol.imap4rev1());
+        assert!(protocol.can_idle());
+        assert!(!protocol.can_move());
+        assert!(!protocol.is_authenticated());
+
+        // Capabilities may change after login.
+        protocol.set_state(State::Authenticated);
+        protocol.update(&Response::Status(
+            Status::ok(
+                Some(Tag::try_from("A1").unwrap()),
+                Some(Code::Capability(
+                    Vec1::try_from(vec![
+                        Capability::Imap4Rev1,
+                        Capability::Move,
+                        Capability::Enable,
+                    ])
+                    .unwrap(),
+                )),
+                "LOGIN completed",
+            )
+            .unwrap(),
+        ));
+        assert!(protocol.is_authenticated());
+        assert!(protocol.can_move());
+        assert!(protocol.can_enable());
+        assert!(!protocol.can_idle());
+
+        assert!(!protocol.utf8_accept_enabled());
+        protocol.update(&Response::Data(Data::Enabled {
+            capabilities: vec![CapabilityEnable::Utf8(Utf8Kind::Accept)],
+        }));
+        protocol.update(&Response::Data(Data::Enabled {
+            capabilities: vec![CapabilityEnable::Utf8(Utf8Kind::Accept)],
+        }));
+        assert!(protocol.utf8_accept_enabled());
+        assert_eq!(protocol.enabled().len(), 1);
+
+        protocol.update(&Response::Data(Data::Capability(Vec1::from(
+            Capability::Imap4Rev1,
+        ))));
+        assert_eq!(protocol.capabilities(), &[Capability::Imap4Rev1]);
+
+        protocol.update(&Response::Status(Status::bye(None, "Bye").unwrap()));
+        assert_eq!(protocol.state(), &State::Logout);
+    }
+
+    #[test]
+    fn test_protocol_state_greeting() {
+        let tests = [
+            (Greeting::ok(None, "...").unwrap(), State::NotAuthenticated),
+            (
+                Greeting::preauth(None, "...").unwrap(),
+                State::Authenticated,
+            ),
+            (Greeting::bye(None, "...").unwrap(), State::Logout),
+        ];
+
+        for (greeting, expected) in tests {
+            let mut protocol = ProtocolState::new();
+            protocol.update_greeting(&greeting);
+            assert_eq!(protocol.state(), &expected);
+            assert!(protocol.capabilities().is_empty());
+        }
+    }
+
+    #[cfg(feature = "ext_condstore_qresync")]
+    #[test]
+    fn test_protocol_state_condstore() {
+        let mut protocol = ProtocolState::new();
+        assert!(!protocol.condstore_enabled());
+
+        protocol.update(&Response::Data(Data::Enabled {
+            capabilities: vec![CapabilityEnable::CondStore],
+        }));
+        assert!(protocol.condstore_enabled());
+    }
+
+    #[test]
+    fn test_command_guard() {
+        let enable = CommandBody::enable(vec![CapabilityEnable::Utf8(Utf8Kind::Accept)]).unwrap();
+        let login = CommandBody::login("alice", "password").unwrap();
+        let select = CommandBody::select("INBOX").unwrap();
+        let fetch = CommandBody::fetch("1", Macro::Fast, false).unwrap();
+
+        let mut protocol = ProtocolState::new();
+        assert!(CommandGuard::new(&protocol)
+            .check(&CommandBody::Noop)
+            .is_err());
+
+        protocol.set_state(State::NotAuthenticated);
+        let guard = CommandGuard::new(&protocol);
+        assert_eq!(guard.check(&CommandBody::Capability), Ok(()));
+        assert_eq!(guard.check(&login), Ok(()));
+        assert_eq!(
+            guard.check(&enable),
+            Err(CommandGuardError::InvalidState {
+                command: "ENABLE",
+                state: "not authenticated"
+            })
+        );
+        assert!(guard.check(&select).is_err());
+        assert!(guard.check(&fetch).is_err());
+
+        protocol.set_state(State::Authenticated);
+        let guard = CommandGuard::new(&protocol);
+        assert_eq!(guard.check(&enable), Ok(()));
+        assert_eq!(guard.check(&select), Ok(()));
+        assert!(guard.check(&login).is_err());
+        assert!(guard.check(&fetch).is_err());
+
+        protocol.set_state(State::Selected(Mailbox::Inbox));
+        let guard = CommandGuard::new(&protocol);
+        assert_eq!(guard.check(&fetch), Ok(()));
+        assert_eq!(guard.check(&select), Ok(()));
+        assert!(guard.check(&enable).is_err());
+
+        protocol.set_state(State::Logout);
+        assert!(CommandGuard::new(&protocol)
+            .check(&CommandBody::Noop)
+            .is_err());
+    }
+
+    #[cfg(feature = "starttls")]
+    #[test]
+    fn test_command_guard_starttls() {
+        let mut protocol = ProtocolState::new();
+
+        protocol.set_state(State::NotAuthenticated);
+        assert_eq!(
+            CommandGuard::new(&protocol).check(&CommandBody::StartTLS),
+            Ok(())
+        );
+
+        protocol.set_tls_active();
+        assert_eq!(
+            CommandGuard::new(&protocol).check(&CommandBody::StartTLS),
+            Err(CommandGuardError::AlreadyActive {
+                command: "STARTTLS",
+                layer: "TLS"
+            })
+        );
+
+        protocol.set_state(State::Authenticated);
+        assert_eq!(
+            CommandGuard::new(&protocol).check(&CommandBody::StartTLS),
+            Err(CommandGuardError::InvalidState {
+                command: "STARTTLS",
+                state: "authenticated"
+            })
+        );
+    }
+
+    #[test]
+    fn test_command_guard_compress() {
+        let compress = CommandBody::Compress {
+            algorithm: CompressionAlgorithm::Deflate,
+        };
+
+        let mut protocol = ProtocolState::new();
+        protocol.set_state(State::Authenticated);
+        assert_eq!(CommandGuard::new(&protocol).check(&compress), Ok(()));
+
+        protocol.set_compression_active();
+        assert_eq!(
+            CommandGuard::new(&protocol).check(&compress),
+            Err(CommandGuardError::AlreadyActive {
+                command: "COMPRESS",
+                layer: "compression"
+            })
+        );
+    }
+
+    #[test]
+    fn test_tag_matcher() {
+        let mut matcher = TagMatcher::new();
+        matcher.track(Tag::try_from("A1").unwrap());
+        matcher.track(Tag::try_from("A2").unwrap());
+
+        assert!(matcher.is_outstanding(&Tag::try_from("A2").unwrap()));
+        assert_eq!(
+            matcher.resolve(&Status::ok(None, None, "untagged").unwrap()),
+            None
+        );
+        assert_eq!(
+            matcher.resolve(&Status::ok(Some(Tag::try_from("A2").unwrap()), None, "...").unwrap()),
+            Some(TagMatch::Matched(Tag::try_from("A2").unwrap()))
+        );
+        assert!(!matcher.is_outstanding(&Tag::try_from("A2").unwrap()));
+
+        // Already completed.
+        assert_eq!(
+            matcher.resolve(&Status::no(Some(Tag::try_from("A2").unwrap()), None, "...").unwrap()),
+            Some(TagMatch::Unknown(Tag::try_from("A2").unwrap()))
+        );
+        // Never sent.
+        assert_eq!(
+            matcher.resolve(&Status::bad(Some(Tag::try_from("X9").unwrap()), None, "...").unwrap()),
+            Some(TagMatch::Unknown(Tag::try_from("X9").unwrap()))
+        );
+        assert!(!matcher.is_empty());
+    }
 }
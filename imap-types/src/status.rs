@@ -1,4 +1,7 @@
-use std::num::NonZeroU32;
+use std::{
+    fmt::{Display, Formatter},
+    num::NonZeroU32,
+};
 
 #[cfg(feature = "arbitrary")]
 use arbitrary::Arbitrary;
@@ -7,6 +10,8 @@ use bounded_static::ToStatic;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+use crate::{core::Vec1, error::ValidationError};
+
 /// Status data item name used to request a status data item.
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
@@ -70,3 +75,145 @@ pub enum StatusDataItem {
     /// The amount of storage space that can be reclaimed by performing EXPUNGE on the mailbox.
     DeletedStorage(u64),
 }
+
+/// Render the status data item as it appears in a STATUS response, e.g., `MESSAGES 42`.
+impl Display for StatusDataItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Messages(count) => write!(f, "MESSAGES {count}"),
+            Self::Recent(count) => write!(f, "RECENT {count}"),
+            Self::UidNext(next) => write!(f, "UIDNEXT {next}"),
+            Self::UidValidity(identifier) => write!(f, "UIDVALIDITY {identifier}"),
+            Self::Unseen(count) => write!(f, "UNSEEN {count}"),
+            Self::Deleted(count) => write!(f, "DELETED {count}"),
+            Self::DeletedStorage(count) => write!(f, "DELETED-STORAGE {count}"),
+        }
+    }
+}
+
+/// Builder for the status data item names requested by a STATUS command.
+///
+/// Duplicates are ignored and an empty selection is rejected, because `STATUS <mailbox> ()` is
+/// invalid.
+///
+/// # Example
+///
+/// ```
+/// use imap_types::{command::CommandBody, status::StatusItemsBuilder};
+///
+/// let item_names = StatusItemsBuilder::new()
+///     .messages()
+///     .unseen()
+///     .build()
+///     .unwrap();
+///
+/// let status = CommandBody::status("INBOX", item_names.into_inner()).unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatusItemsBuilder {
+    item_names: Vec<StatusDataItemName>,
+}
+
+impl StatusItemsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request a status data item (if not already requested).
+    pub fn item(mut self, item_name: StatusDataItemName) -> Self {
+        if !self.item_names.contains(&item_name) {
+            self.item_names.push(item_name);
+        }
+
+        self
+    }
+
+    /// Request `MESSAGES`.
+    pub fn messages(self) -> Self {
+        self.item(StatusDataItemName::Messages)
+    }
+
+    /// Request `RECENT`.
+    pub fn recent(self) -> Self {
+        self.item(StatusDataItemName::Recent)
+    }
+
+    /// Request `UIDNEXT`.
+    pub fn uid_next(self) -> Self {
+        self.item(StatusDataItemName::UidNext)
+    }
+
+    /// Request `UIDVALIDITY`.
+    pub fn uid_validity(self) -> Self {
+        self.item(StatusDataItemName::UidValidity)
+    }
+
+    /// Request `UNSEEN`.
+    pub fn unseen(self) -> Self {
+        self.item(StatusDataItemName::Unseen)
+    }
+
+    /// Build the list of status data item names.
+    ///
+    /// Fails when no item was requested.
+    pub fn build(self) -> Result<Vec1<StatusDataItemName>, ValidationError> {
+        Vec1::try_from(self.item_names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_items_builder() {
+        let got = StatusItemsBuilder::new()
+            .unseen()
+            .messages()
+            .unseen()
+            .item(StatusDataItemName::Messages)
+            .uid_next()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                StatusDataItemName::Unseen,
+                StatusDataItemName::Messages,
+                StatusDataItemName::UidNext,
+            ],
+            got.into_inner()
+        );
+    }
+
+    #[test]
+    fn test_status_items_builder_empty() {
+        assert!(StatusItemsBuilder::new().build().is_err());
+    }
+
+    #[test]
+    fn test_display_status_data_item() {
+        let tests = [
+            (StatusDataItem::Messages(0), "MESSAGES 0"),
+            (StatusDataItem::Recent(1), "RECENT 1"),
+            (
+                StatusDataItem::UidNext(NonZeroU32::new(4392).unwrap()),
+                "UIDNEXT 4392",
+            ),
+            (
+                StatusDataItem::UidValidity(NonZeroU32::new(u32::MAX).unwrap()),
+                "UIDVALIDITY 4294967295",
+            ),
+            (StatusDataItem::Unseen(42), "UNSEEN 42"),
+            (StatusDataItem::Deleted(7), "DELETED 7"),
+            (
+                StatusDataItem::DeletedStorage(u64::MAX),
+                "DELETED-STORAGE 18446744073709551615",
+            ),
+        ];
+
+        for (test, expected) in tests {
+            assert_eq!(test.to_string(), expected);
+        }
+    }
+}
@@ -109,6 +109,9 @@ pub mod indicators {
     }
 }
 
+/// Escapes `\` and `"` so that the result can be used as the inner part of a quoted string.
+///
+/// This is the inverse of [`unescape_quoted`].
 pub fn escape_quoted(unescaped: &str) -> Cow<str> {
     let mut escaped = Cow::Borrowed(unescaped);
 
@@ -123,18 +126,34 @@ pub fn escape_quoted(unescaped: &str) -> Cow<str> {
     escaped
 }
 
+/// Unescapes the inner part of a quoted string.
+///
+/// Only `\"` and `\\` are legal escapes. Everything else, including a trailing backslash, is left
+/// as is. (The parser rejects these cases anyway.)
 pub fn unescape_quoted(escaped: &str) -> Cow<str> {
-    let mut unescaped = Cow::Borrowed(escaped);
-
-    if unescaped.contains("\\\\") {
-        unescaped = Cow::Owned(unescaped.replace("\\\\", "\\"));
+    if !escaped.contains('\\') {
+        return Cow::Borrowed(escaped);
     }
 
-    if unescaped.contains("\\\"") {
-        unescaped = Cow::Owned(unescaped.replace("\\\"", "\""));
+    let mut unescaped = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some(next @ ('\\' | '"')) => unescaped.push(next),
+                Some(next) => {
+                    unescaped.push(c);
+                    unescaped.push(next);
+                }
+                None => unescaped.push(c),
+            }
+        } else {
+            unescaped.push(c);
+        }
     }
 
-    unescaped
+    Cow::Owned(unescaped)
 }
 
 #[cfg(test)]
@@ -169,6 +188,26 @@ mod tests {
             ("\\\\alice\\\\", "\\alice\\"),
             ("alice\\\"", "alice\""),
             (r#"\\alice\\ \""#, r#"\alice\ ""#),
+            (r#"Hello \"World\""#, r#"Hello "World""#),
+            (r#"\\\""#, r#"\""#),
+            (r#"\\\\"#, r#"\\"#),
+            (r#"\"\\"#, r#""\"#),
+        ];
+
+        for (test, expected) in tests {
+            let got = unescape_quoted(test);
+            assert_eq!(expected, got);
+        }
+    }
+
+    #[test]
+    fn test_unescape_quoted_illegal() {
+        // Illegal escapes are left as is.
+        let tests = [
+            ("\\", "\\"),
+            ("alice\\", "alice\\"),
+            ("\\a", "\\a"),
+            ("\\\\\\", "\\\\"),
         ];
 
         for (test, expected) in tests {
@@ -177,6 +216,11 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_unescape_quoted_borrows() {
+        assert!(matches!(unescape_quoted("alice"), Cow::Borrowed("alice")));
+    }
+
     #[test]
     fn test_that_unescape_is_inverse_of_escape() {
         let input = "\\\"\\¹²³abc_*:;059^$%§!\"";